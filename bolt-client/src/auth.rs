@@ -0,0 +1,285 @@
+use std::fmt;
+#[cfg(feature = "tokio-stream")]
+use std::{io, sync::Arc};
+
+#[cfg(feature = "tokio-stream")]
+use async_trait::async_trait;
+use bolt_proto::{value::ValueMap, Value};
+
+use crate::Metadata;
+
+/// Authentication details sent to the server in the [`HELLO`](bolt_proto::Message::Hello)
+/// message that initializes a connection. See [`AuthToken::to_metadata`].
+///
+/// Credentials are left out of the [`Debug`](fmt::Debug) output.
+#[derive(Clone, PartialEq)]
+pub enum AuthToken {
+    /// No authentication, for servers that have it disabled.
+    None,
+    /// A username and password, optionally for a specific realm.
+    Basic {
+        principal: String,
+        credentials: String,
+        realm: Option<String>,
+    },
+    /// A token issued by an identity provider, such as an OIDC access token.
+    Bearer { credentials: String },
+    /// A base64-encoded Kerberos ticket.
+    Kerberos { ticket: String },
+    /// A scheme supported by a custom authentication plugin on the server.
+    Custom {
+        scheme: String,
+        principal: String,
+        credentials: String,
+        realm: Option<String>,
+        parameters: Option<ValueMap>,
+    },
+}
+
+impl AuthToken {
+    /// Get the `scheme`, `principal`, `credentials`, `realm`, and `parameters` entries of the
+    /// `HELLO` metadata for this token. Entries that don't apply to the scheme are left out.
+    pub fn to_metadata(&self) -> Metadata {
+        let mut entries = Vec::new();
+        match self {
+            AuthToken::None => entries.push(("scheme", Value::from("none"))),
+            AuthToken::Basic {
+                principal,
+                credentials,
+                realm,
+            } => {
+                entries.push(("scheme", Value::from("basic")));
+                entries.push(("principal", Value::from(principal.as_str())));
+                entries.push(("credentials", Value::from(credentials.as_str())));
+                if let Some(realm) = realm {
+                    entries.push(("realm", Value::from(realm.as_str())));
+                }
+            }
+            AuthToken::Bearer { credentials } => {
+                entries.push(("scheme", Value::from("bearer")));
+                entries.push(("credentials", Value::from(credentials.as_str())));
+            }
+            AuthToken::Kerberos { ticket } => {
+                entries.push(("scheme", Value::from("kerberos")));
+                entries.push(("principal", Value::from("")));
+                entries.push(("credentials", Value::from(ticket.as_str())));
+            }
+            AuthToken::Custom {
+                scheme,
+                principal,
+                credentials,
+                realm,
+                parameters,
+            } => {
+                entries.push(("scheme", Value::from(scheme.as_str())));
+                entries.push(("principal", Value::from(principal.as_str())));
+                entries.push(("credentials", Value::from(credentials.as_str())));
+                if let Some(realm) = realm {
+                    entries.push(("realm", Value::from(realm.as_str())));
+                }
+                if let Some(parameters) = parameters {
+                    entries.push(("parameters", Value::from(parameters.clone())));
+                }
+            }
+        }
+        Metadata::from_iter(entries)
+    }
+}
+
+impl fmt::Debug for AuthToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const REDACTED: &str = "<redacted>";
+        match self {
+            AuthToken::None => f.write_str("None"),
+            AuthToken::Basic {
+                principal, realm, ..
+            } => f
+                .debug_struct("Basic")
+                .field("principal", principal)
+                .field("credentials", &REDACTED)
+                .field("realm", realm)
+                .finish(),
+            AuthToken::Bearer { .. } => f
+                .debug_struct("Bearer")
+                .field("credentials", &REDACTED)
+                .finish(),
+            AuthToken::Kerberos { .. } => f
+                .debug_struct("Kerberos")
+                .field("ticket", &REDACTED)
+                .finish(),
+            AuthToken::Custom {
+                scheme,
+                principal,
+                realm,
+                parameters,
+                ..
+            } => f
+                .debug_struct("Custom")
+                .field("scheme", scheme)
+                .field("principal", principal)
+                .field("credentials", &REDACTED)
+                .field("realm", realm)
+                .field("parameters", parameters)
+                .finish(),
+        }
+    }
+}
+
+/// Supplies the [`AuthToken`]s that a [`ConnectionFactory`](crate::ConnectionFactory)
+/// authenticates new connections with, so that credentials which expire, like OIDC access tokens,
+/// can be refreshed without recreating the factory or pool.
+///
+/// The provider is asked for a token whenever a connection is opened, and once whenever a
+/// connection is taken from a [`Pool`](crate::Pool). Connections that were authenticated with a
+/// different token than the one the provider most recently returned are discarded and replaced
+/// when they're reused. Bolt v5.1 allows re-authenticating an open
+/// connection with a `LOGON` message instead, but this client doesn't support Bolt v5 yet.
+///
+/// Since the provider is called so often, it should cache its token and only fetch a new one
+/// when the cached one is about to expire, or has been rejected by the server:
+/// ```
+/// use std::{io, sync::Mutex, time::{Duration, Instant}};
+///
+/// use async_trait::async_trait;
+/// use bolt_client::{AuthProvider, AuthToken};
+///
+/// #[derive(Default)]
+/// struct CachingProvider {
+///     cached: Mutex<Option<(AuthToken, Instant)>>,
+/// }
+///
+/// impl CachingProvider {
+///     async fn fetch(&self) -> io::Result<(AuthToken, Instant)> {
+///         // Request a token from the identity provider here
+///         let token = AuthToken::Bearer { credentials: "...".to_string() };
+///         Ok((token, Instant::now() + Duration::from_secs(3600)))
+///     }
+/// }
+///
+/// #[async_trait]
+/// impl AuthProvider for CachingProvider {
+///     async fn token(&self) -> io::Result<AuthToken> {
+///         if let Some((token, expires_at)) = &*self.cached.lock().unwrap() {
+///             if Instant::now() < *expires_at {
+///                 return Ok(token.clone());
+///             }
+///         }
+///         let (token, expires_at) = self.fetch().await?;
+///         *self.cached.lock().unwrap() = Some((token.clone(), expires_at));
+///         Ok(token)
+///     }
+///
+///     fn token_expired(&self, token: &AuthToken) {
+///         let mut cached = self.cached.lock().unwrap();
+///         if cached.as_ref().is_some_and(|(cached, _)| cached == token) {
+///             *cached = None;
+///         }
+///     }
+/// }
+/// ```
+#[cfg(feature = "tokio-stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Get the token to authenticate connections with.
+    async fn token(&self) -> io::Result<AuthToken>;
+
+    /// Called when the server rejects a token because it has expired, so that the next call to
+    /// [`AuthProvider::token`] can return a new one. Does nothing by default.
+    fn token_expired(&self, _token: &AuthToken) {}
+}
+
+/// A fixed token that never expires.
+#[cfg(feature = "tokio-stream")]
+#[async_trait]
+impl AuthProvider for AuthToken {
+    async fn token(&self) -> io::Result<AuthToken> {
+        Ok(self.clone())
+    }
+}
+
+#[cfg(feature = "tokio-stream")]
+#[async_trait]
+impl<P: AuthProvider + ?Sized> AuthProvider for Arc<P> {
+    async fn token(&self) -> io::Result<AuthToken> {
+        (**self).token().await
+    }
+
+    fn token_expired(&self, token: &AuthToken) {
+        (**self).token_expired(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_metadata() {
+        let metadata = AuthToken::None.to_metadata();
+        assert_eq!(
+            metadata.value,
+            Metadata::from_iter(vec![("scheme", "none")]).value
+        );
+
+        let metadata = AuthToken::Basic {
+            principal: "neo4j".to_string(),
+            credentials: "password".to_string(),
+            realm: None,
+        }
+        .to_metadata();
+        assert_eq!(
+            metadata.value,
+            Metadata::from_iter(vec![
+                ("scheme", "basic"),
+                ("principal", "neo4j"),
+                ("credentials", "password"),
+            ])
+            .value
+        );
+
+        let metadata = AuthToken::Kerberos {
+            ticket: "dGlja2V0".to_string(),
+        }
+        .to_metadata();
+        assert_eq!(
+            metadata.value,
+            Metadata::from_iter(vec![
+                ("scheme", "kerberos"),
+                ("principal", ""),
+                ("credentials", "dGlja2V0"),
+            ])
+            .value
+        );
+
+        let parameters = ValueMap::from_iter(vec![("tenant".to_string(), Value::from("a"))]);
+        let metadata = AuthToken::Custom {
+            scheme: "plugin".to_string(),
+            principal: "neo4j".to_string(),
+            credentials: "secret".to_string(),
+            realm: Some("native".to_string()),
+            parameters: Some(parameters.clone()),
+        }
+        .to_metadata();
+        assert_eq!(metadata.value.get("realm"), Some(&Value::from("native")));
+        assert_eq!(
+            metadata.value.get("parameters"),
+            Some(&Value::from(parameters))
+        );
+    }
+
+    #[test]
+    fn debug_hides_credentials() {
+        let token = AuthToken::Bearer {
+            credentials: "secret".to_string(),
+        };
+        assert!(!format!("{:?}", token).contains("secret"));
+        let token = AuthToken::Basic {
+            principal: "neo4j".to_string(),
+            credentials: "secret".to_string(),
+            realm: None,
+        };
+        let debug = format!("{:?}", token);
+        assert!(debug.contains("neo4j") && !debug.contains("secret"));
+    }
+}
@@ -7,22 +7,31 @@
 //
 // The aforementioned documentation comments are thus licensed under CC BY-NC-SA 4.0.
 
-use std::{collections::VecDeque, io};
+use std::{
+    collections::VecDeque,
+    io,
+    time::{Duration, Instant},
+};
 
 use bytes::*;
 use futures_util::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 
 use bolt_client_macros::*;
 use bolt_proto::{
-    error::Error as ProtocolError, message::*, version::*, Message, ServerState, ServerState::*,
-    Value,
+    error::Error as ProtocolError, message::*, version::*, DeserializeOptions, Message,
+    SerializeOptions, ServerState, ServerState::*, Value,
 };
 
 use crate::{
     error::{CommunicationError, CommunicationResult, ConnectionError, ConnectionResult},
-    Metadata, Params, RoutingContext,
+    AuthToken, Metadata, Params, RoutingContext,
 };
 
+mod batch;
+mod keep_alive;
+mod query;
+mod split;
+mod timeout;
 mod v1;
 mod v2;
 mod v3;
@@ -32,6 +41,17 @@ mod v4_2;
 mod v4_3;
 mod v4_4;
 
+pub use batch::{
+    Batch, BatchResponse, BatchResponses, PullHandle, PullResult, ResponseHandle, RunHandle,
+    RunResult,
+};
+pub use query::{AccessMode, Query, RoutingTableRequest, Transaction};
+pub use split::{ClientReader, ClientWriter};
+pub use timeout::Timeouts;
+
+pub(crate) use timeout::timeout;
+use timeout::Tracked;
+
 const PREAMBLE: [u8; 4] = [0x60, 0x60, 0xB0, 0x17];
 
 /// Return whether a version is compatible with version specifier.
@@ -47,11 +67,61 @@ fn is_compatible(version: u32, specifier: u32) -> bool {
         && (specified_minor.saturating_sub(range)..=specified_minor).contains(&minor)
 }
 
+/// Send the preamble and version specifiers, and return the version chosen by the server.
+async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    version_specifiers: &[u32; 4],
+) -> io::Result<u32> {
+    let mut version_specifiers_bytes = BytesMut::with_capacity(16);
+    version_specifiers
+        .iter()
+        .for_each(|&v| version_specifiers_bytes.put_u32(v));
+    stream.write_all(&PREAMBLE).await?;
+    stream.write_all(&version_specifiers_bytes).await?;
+    stream.flush().await?;
+
+    let mut u32_bytes = [0, 0, 0, 0];
+    stream.read_exact(&mut u32_bytes).await?;
+    Ok(u32::from_be_bytes(u32_bytes))
+}
+
 /// An asynchronous client for Bolt servers.
+///
+/// # Cancellation
+/// Dropping the future of a `Client` method, for example because it was cancelled by a timeout,
+/// never leaves the client out of sync with the server:
+/// - If the future is dropped partway through reading or writing a message, the client can no
+///   longer tell where the next message starts, so it is marked
+///   [`Defunct`](ServerState::Defunct) and should be discarded.
+/// - If the future is dropped between messages, responses to the messages it sent may still be
+///   on their way. Until they've been read, any message other than [`RESET`](Message::Reset) or
+///   [`GOODBYE`](Message::Goodbye) is rejected and marks the client as defunct. Use
+///   [`Client::reset`] to read and discard them, which resynchronizes the client with the
+///   server.
+///
+/// Connection pools reset connections before reusing them, and discard defunct connections.
 #[derive(Debug)]
 pub struct Client<S: AsyncRead + AsyncWrite + Unpin> {
     stream: S,
     version: u32,
+    state: State,
+    serialize_options: SerializeOptions,
+    deserialize_options: DeserializeOptions,
+    timeouts: Timeouts,
+    // Set once part of a message has been read or written, and cleared once all of it has, so it
+    // stays set if the operation is dropped or times out partway through
+    io_in_progress: bool,
+    created_at: Instant,
+    last_activity: Instant,
+    recv_timeout: Option<Duration>,
+    // The token this client was initialized with by a ConnectionFactory
+    auth_token: Option<AuthToken>,
+}
+
+/// The state of the connection, as tracked by the client. This is shared between the halves of a
+/// [split](Client::split) client.
+#[derive(Debug, Clone)]
+pub(crate) struct State {
     server_state: ServerState,
     sent_queue: VecDeque<Message>,
     open_tx_streams: usize,
@@ -61,18 +131,22 @@ impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
     /// Attempt to create a new client from an asynchronous stream. A handshake will be performed
     /// with the provided protocol version specifiers, and, if this succeeds, a Client will be
     /// returned.
-    pub async fn new(mut stream: S, version_specifiers: &[u32; 4]) -> ConnectionResult<Self> {
-        let mut version_specifiers_bytes = BytesMut::with_capacity(16);
-        version_specifiers
-            .iter()
-            .for_each(|&v| version_specifiers_bytes.put_u32(v));
-        stream.write_all(&PREAMBLE).await?;
-        stream.write_all(&version_specifiers_bytes).await?;
-        stream.flush().await?;
-
-        let mut u32_bytes = [0, 0, 0, 0];
-        stream.read_exact(&mut u32_bytes).await?;
-        let version = u32::from_be_bytes(u32_bytes);
+    pub async fn new(stream: S, version_specifiers: &[u32; 4]) -> ConnectionResult<Self> {
+        Self::new_with_timeouts(stream, version_specifiers, Timeouts::default()).await
+    }
+
+    /// Like [`Client::new`], but with [timeouts](Timeouts) for the handshake and for every
+    /// operation performed by the client afterwards.
+    pub async fn new_with_timeouts(
+        mut stream: S,
+        version_specifiers: &[u32; 4],
+        timeouts: Timeouts,
+    ) -> ConnectionResult<Self> {
+        let version = timeout(
+            timeouts.handshake,
+            handshake(&mut stream, version_specifiers),
+        )
+        .await??;
 
         if version > 0 {
             for &specifier in version_specifiers {
@@ -80,9 +154,15 @@ impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
                     return Ok(Self {
                         stream,
                         version,
-                        server_state: Connected,
-                        sent_queue: VecDeque::default(),
-                        open_tx_streams: 0,
+                        state: State::default(),
+                        serialize_options: SerializeOptions::for_version(version, false),
+                        deserialize_options: DeserializeOptions::default(),
+                        timeouts,
+                        io_in_progress: false,
+                        created_at: Instant::now(),
+                        last_activity: Instant::now(),
+                        recv_timeout: None,
+                        auth_token: None,
                     });
                 }
             }
@@ -97,527 +177,284 @@ impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
 
     /// Get the current server state for this client.
     pub fn server_state(&self) -> ServerState {
-        self.server_state
+        if self.io_in_progress {
+            Defunct
+        } else {
+            self.state.server_state
+        }
+    }
+
+    /// Get the options used to serialize messages sent by this client. These depend on the
+    /// negotiated protocol version, and on the patches accepted by the server in response to
+    /// [`hello`](Client::hello). None of the supported versions allow vectors, so sending a
+    /// [`Value::Vector`](bolt_proto::Value::Vector) always fails.
+    pub fn serialize_options(&self) -> SerializeOptions {
+        self.serialize_options
+    }
+
+    /// Set the maximum size of the chunks that outgoing messages are split into. The size is
+    /// clamped to between 1 and 65,535 bytes, and defaults to 16,381 bytes.
+    pub fn set_max_chunk_size(&mut self, max_chunk_size: usize) {
+        self.serialize_options = self.serialize_options.with_max_chunk_size(max_chunk_size);
+    }
+
+    /// Get the options used to deserialize messages received by this client.
+    pub fn deserialize_options(&self) -> DeserializeOptions {
+        self.deserialize_options
+    }
+
+    /// Set the options used to deserialize messages received by this client, such as the maximum
+    /// size of an inbound message and the maximum nesting depth of values. Messages that exceed
+    /// these limits produce a [`DeserializationError`](bolt_proto::error::DeserializationError).
+    pub fn set_deserialize_options(&mut self, options: DeserializeOptions) {
+        self.deserialize_options = options;
+    }
+
+    /// Get the timeouts for operations performed by this client.
+    pub fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+
+    /// Set the timeouts for operations performed by this client. The handshake timeout is unused,
+    /// since the handshake has already been performed. The halves of a [split](Client::split)
+    /// client don't use timeouts.
+    pub fn set_timeouts(&mut self, timeouts: Timeouts) {
+        self.timeouts = timeouts;
+    }
+
+    /// Get the token this client was authenticated with, if it was initialized by a
+    /// [`ConnectionFactory`](crate::ConnectionFactory) with an [`AuthProvider`](crate::AuthProvider).
+    pub fn auth_token(&self) -> Option<&AuthToken> {
+        self.auth_token.as_ref()
+    }
+
+    #[cfg(feature = "tokio-stream")]
+    pub(crate) fn set_auth_token(&mut self, auth_token: Option<AuthToken>) {
+        self.auth_token = auth_token;
+    }
+
+    /// Get the time since this client was created.
+    pub fn age(&self) -> Duration {
+        self.created_at.elapsed()
+    }
+
+    /// Get the time since a message was last sent to or received from the server.
+    pub fn idle_time(&self) -> Duration {
+        self.last_activity.elapsed()
+    }
+
+    /// Get the time after which the server closes an idle connection, as given by the
+    /// `connection.recv_timeout_seconds` hint in the response to [`hello`](Client::hello).
+    /// _(Bolt v4.3+ only.)_
+    pub fn server_recv_timeout(&self) -> Option<Duration> {
+        self.recv_timeout
     }
 
     pub(crate) async fn read_message(&mut self) -> CommunicationResult<Message> {
-        let message = Message::from_stream(&mut self.stream)
-            .await
-            .map_err(ProtocolError::from)?;
+        let stream = Tracked::new(&mut self.stream, &mut self.io_in_progress);
+        let result = timeout(
+            self.timeouts.read,
+            Message::from_stream_with(stream, &self.deserialize_options),
+        )
+        .await?;
+        let message = match result {
+            Ok(message) => message,
+            Err(error) => {
+                // The rest of the message can't be skipped, and the stream may be closed
+                self.state.server_state = Defunct;
+                return Err(ProtocolError::from(error).into());
+            }
+        };
+        self.io_in_progress = false;
+        self.last_activity = Instant::now();
 
         #[cfg(test)]
         println!("<<< {:?}\n", message);
 
-        match (self.server_state, self.sent_queue.pop_front(), message) {
-            // CONNECTED
-            (Connected, Some(Message::Init(_)), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Connected, Some(Message::Init(_)), Message::Failure(failure)) => {
-                self.server_state = Defunct;
-                Ok(Message::Failure(failure))
-            }
-            (Connected, Some(Message::Hello(_)), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Connected, Some(Message::Hello(_)), Message::Failure(failure)) => {
-                self.server_state = Defunct;
-                Ok(Message::Failure(failure))
-            }
+        self.state.handle_response(message)
+    }
 
-            // READY
-            (Ready, Some(Message::Run(_)), Message::Success(success)) => {
-                self.server_state = Streaming;
-                Ok(Message::Success(success))
-            }
-            (Ready, Some(Message::Run(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Ready, Some(Message::RunWithMetadata(_)), Message::Success(success)) => {
-                self.server_state = Streaming;
-                Ok(Message::Success(success))
-            }
-            (Ready, Some(Message::RunWithMetadata(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Ready, Some(Message::Begin(_)), Message::Success(success)) => {
-                self.server_state = TxReady;
-                Ok(Message::Success(success))
-            }
-            (Ready, Some(Message::Begin(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Ready, Some(Message::Route(_)), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Ready, Some(Message::Route(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Ready, Some(Message::RouteWithMetadata(_)), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Ready, Some(Message::RouteWithMetadata(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
+    pub(crate) async fn send_message(&mut self, message: Message) -> CommunicationResult<()> {
+        // Responses to an operation that was dropped between messages may still be on their way
+        let awaiting_responses = !self.state.sent_queue.is_empty()
+            && !matches!(message, Message::Reset | Message::Goodbye);
+        if awaiting_responses || !self.state.accepts(&message) {
+            let state = self.state.server_state;
+            self.state.server_state = Defunct;
+            return Err(CommunicationError::InvalidState { state, message });
+        }
 
-            // STREAMING
-            (Streaming, Some(Message::PullAll), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Streaming, Some(Message::PullAll), Message::Record(record)) => {
-                self.server_state = Streaming;
-                // Put the PULL_ALL message back so we can keep consuming records
-                self.sent_queue.push_front(Message::PullAll);
-                Ok(Message::Record(record))
-            }
-            (Streaming, Some(Message::PullAll), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Streaming, Some(Message::Pull(_)), Message::Success(success)) => {
-                self.server_state = match success.metadata().get("has_more") {
-                    Some(&Value::Boolean(true)) => Streaming,
-                    _ => Ready,
-                };
-                Ok(Message::Success(success))
-            }
-            (Streaming, Some(Message::Pull(pull)), Message::Record(record)) => {
-                self.server_state = Streaming;
-                // Put the PULL message back so we can keep consuming records
-                self.sent_queue.push_front(Message::Pull(pull));
-                Ok(Message::Record(record))
-            }
-            (Streaming, Some(Message::Pull(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Streaming, Some(Message::DiscardAll), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Streaming, Some(Message::DiscardAll), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (Streaming, Some(Message::Discard(_)), Message::Success(success)) => {
-                self.server_state = match success.metadata().get("has_more") {
-                    Some(&Value::Boolean(true)) => Streaming,
-                    _ => Ready,
-                };
-                Ok(Message::Success(success))
-            }
-            (Streaming, Some(Message::Discard(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
+        self.write_messages(vec![message]).await
+    }
 
-            // TX_READY
-            (TxReady, Some(Message::RunWithMetadata(_)), Message::Success(success)) => {
-                self.open_tx_streams += 1;
-                self.server_state = TxStreaming;
-                Ok(Message::Success(success))
-            }
-            (TxReady, Some(Message::RunWithMetadata(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (TxReady, Some(Message::Commit), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
+    /// Write messages to the server and flush the stream, then queue the messages so they can be
+    /// matched up with their responses.
+    pub(crate) async fn write_messages(
+        &mut self,
+        messages: Vec<Message>,
+    ) -> CommunicationResult<()> {
+        if self.io_in_progress {
+            self.state.server_state = Defunct;
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "an earlier operation stopped partway through a message",
+            )
+            .into());
+        }
+
+        // Serialize everything up front, so a serialization error can't leave a message half-sent
+        let mut chunks = Vec::new();
+        for message in &messages {
+            #[cfg(test)]
+            println!(">>> {:?}", message);
+
+            chunks.extend(
+                message
+                    .clone()
+                    .into_chunks_with(&self.serialize_options)
+                    .map_err(ProtocolError::from)?,
+            );
+        }
+
+        let mut stream = Tracked::new(&mut self.stream, &mut self.io_in_progress);
+        let result = timeout(self.timeouts.write, async move {
+            for chunk in chunks {
+                stream.write_all(&chunk).await?;
+            }
+            stream.flush().await
+        })
+        .await?;
+        if let Err(error) = result {
+            self.state.server_state = Defunct;
+            return Err(error.into());
+        }
+        self.io_in_progress = false;
+        self.last_activity = Instant::now();
+
+        for message in messages {
+            self.state.handle_request(message);
+        }
+        Ok(())
+    }
+
+    /// Send a [`HELLO`](Message::Hello) (or [`INIT`](Message::Init)) message to the server.
+    /// _(Sends `INIT` for Bolt v1 - v2, and `HELLO` for Bolt v3+.)_
+    ///
+    /// # Description
+    /// The `HELLO` message requests the connection to be authorized for use with the remote
+    /// database. Clients should send a `HELLO` message to the server immediately after connection
+    /// and process the response before using that connection in any other way.
+    ///
+    /// The server must be in the [`Connected`](ServerState::Connected) state to be able to process
+    /// a `HELLO` message. For any other states, receipt of a `HELLO` message is considered a
+    /// protocol violation and leads to connection closure.
+    ///
+    /// If authentication fails, the server will respond with a [`FAILURE`](Message::Failure)
+    /// message and immediately close the connection. Clients wishing to retry initialization
+    /// should establish a new connection.
+    ///
+    /// # Fields
+    /// `metadata` should contain at least two entries:
+    /// - `user_agent`, which should conform to the format `"Name/Version"`, for example
+    ///   `"Example/1.0.0"` (see
+    ///   [here](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent)).
+    /// - `scheme` is the authentication scheme. Predefined schemes are `"none"`, `"basic"`, or
+    ///   `"kerberos"`.
+    ///
+    /// If using Bolt v4.1 or later, the following additional `metadata` entries can be specified:
+    /// - `routing`, a map which should contain routing context information as well as an `address`
+    ///   field indicating to which address the client should initially connect. Leaving this
+    ///   unspecified indicates that the server should not carry out any routing.
+    ///   _(Bolt v4.1+ only.)_
+    /// - `patch_bolt`, a list of protocol patches the client would like to use. Unless specified
+    ///   otherwise, the client requests the `"utc"` patch, which makes date-times with an offset or
+    ///   time zone ID use unambiguous UTC-based encodings if the server accepts it.
+    ///   _(Bolt v4.3+ only.)_
+    ///
+    /// Further entries in `metadata` are passed to the implementation of the chosen authentication
+    /// scheme. Their names, types, and defaults depend on that choice. For example, the scheme
+    /// `"basic"` requires `metadata` to contain the username and password in the form
+    /// `{"principal": "<username>", "credentials": "<password>"}`.
+    ///
+    /// # Response
+    /// - [`Message::Success`] - initialization has completed successfully and the server has
+    ///   entered the [`Ready`](ServerState::Ready) state. The server may include metadata that
+    ///   describes details of the server environment and/or the connection. The following fields
+    ///   are defined for inclusion in the `SUCCESS` metadata:
+    ///   - `server`, the server agent string (e.g. `"Neo4j/4.3.0"`)
+    ///   - `connection_id`, a unique identifier for the connection (e.g. `"bolt-61"`)
+    ///     _(Bolt v3+ only.)_
+    ///   - `hints`, a map of configuration hints (e.g. `{"connection.recv_timeout_seconds": 120}`)
+    ///     These hints may be interpreted or ignored by drivers at their own discretion in order
+    ///     to augment operations where applicable. Hints remain valid throughout the lifetime of a
+    ///     given connection and cannot be changed. As such, newly established connections may
+    ///     observe different hints as the server configuration is adjusted.
+    ///     _(Bolt v4.3+ only.)_
+    /// - [`Message::Failure`] - initialization has failed and the server has entered the
+    ///   [`Defunct`](ServerState::Defunct) state. The server may choose to include metadata
+    ///   describing the nature of the failure but will immediately close the connection after the
+    ///   failure has been sent.
+    #[bolt_version(1, 2, 3, 4, 4.1, 4.2, 4.3, 4.4)]
+    pub async fn hello(&mut self, mut metadata: Metadata) -> CommunicationResult<Message> {
+        let message = match self.version() {
+            V1_0 | V2_0 => {
+                let user_agent: String = metadata
+                    .value
+                    .remove("user_agent")
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "missing user_agent")
+                    })?
+                    .try_into()
+                    .map_err(|_| {
+                        io::Error::new(io::ErrorKind::InvalidInput, "user_agent must be a string")
+                    })?;
+                let auth_token = metadata.value;
+
+                Message::Init(Init::new(user_agent, auth_token))
             }
-            (TxReady, Some(Message::Commit), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (TxReady, Some(Message::Rollback), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (TxReady, Some(Message::Rollback), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
+            V4_3 | V4_4 => {
+                metadata
+                    .value
+                    .get_or_insert_with(String::from("patch_bolt"), || Value::from(vec!["utc"]));
+                Message::Hello(Hello::new(metadata.value))
             }
+            _ => Message::Hello(Hello::new(metadata.value)),
+        };
 
-            // TX_STREAMING
-            (TxStreaming, Some(Message::RunWithMetadata(_)), Message::Success(success)) => {
-                self.open_tx_streams += 1;
-                self.server_state = TxStreaming;
-                Ok(Message::Success(success))
-            }
-            (TxStreaming, Some(Message::RunWithMetadata(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (TxStreaming, Some(Message::PullAll), Message::Success(success)) => {
-                self.open_tx_streams -= 1;
-                self.server_state = TxReady;
-                Ok(Message::Success(success))
-            }
-            (TxStreaming, Some(Message::PullAll), Message::Record(record)) => {
-                self.server_state = TxStreaming;
-                // Put the PULL_ALL message back so we can keep consuming records
-                self.sent_queue.push_front(Message::PullAll);
-                Ok(Message::Record(record))
-            }
-            (TxStreaming, Some(Message::PullAll), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (TxStreaming, Some(Message::Pull(_)), Message::Success(success)) => {
-                self.server_state = match success.metadata().get("has_more") {
-                    Some(&Value::Boolean(true)) => TxStreaming,
-                    _ => {
-                        self.open_tx_streams -= 1;
-                        if self.open_tx_streams > 0 {
-                            TxStreaming
-                        } else {
-                            TxReady
-                        }
-                    }
-                };
-                Ok(Message::Success(success))
-            }
-            (TxStreaming, Some(Message::Pull(pull)), Message::Record(record)) => {
-                self.server_state = TxStreaming;
-                // Put the PULL message back so we can keep consuming records
-                self.sent_queue.push_front(Message::Pull(pull));
-                Ok(Message::Record(record))
-            }
-            (TxStreaming, Some(Message::Pull(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (TxStreaming, Some(Message::DiscardAll), Message::Success(success)) => {
-                self.open_tx_streams -= 1;
-                self.server_state = TxReady;
-                Ok(Message::Success(success))
-            }
-            (TxStreaming, Some(Message::DiscardAll), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-            (TxStreaming, Some(Message::Discard(_)), Message::Success(success)) => {
-                self.server_state = match success.metadata().get("has_more") {
-                    Some(&Value::Boolean(true)) => TxStreaming,
-                    _ => {
-                        self.open_tx_streams -= 1;
-                        if self.open_tx_streams > 0 {
-                            TxStreaming
-                        } else {
-                            TxReady
-                        }
+        self.send_message(message).await?;
+        let response = self.read_message().await?;
+        if let Message::Success(success) = &response {
+            let utc_patch = match success.metadata().get("patch_bolt") {
+                Some(Value::List(patches)) => patches.contains(&Value::from("utc")),
+                _ => false,
+            };
+            self.serialize_options = SerializeOptions::for_version(self.version(), utc_patch)
+                .with_max_chunk_size(self.serialize_options.max_chunk_size());
+            self.recv_timeout = match success.metadata().get("hints") {
+                Some(Value::Map(hints)) => match hints.get("connection.recv_timeout_seconds") {
+                    Some(&Value::Integer(seconds)) if seconds > 0 => {
+                        Some(Duration::from_secs(seconds as u64))
                     }
-                };
-                Ok(Message::Success(success))
-            }
-            (TxStreaming, Some(Message::Discard(_)), Message::Failure(failure)) => {
-                self.server_state = Failed;
-                Ok(Message::Failure(failure))
-            }
-
-            // FAILED
-            (Failed, Some(Message::Run(_)), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::RunWithMetadata(_)), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::PullAll), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::Pull(_)), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::DiscardAll), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::Discard(_)), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::Route(_)), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::RouteWithMetadata(_)), Message::Ignored) => {
-                self.server_state = Failed;
-                Ok(Message::Ignored)
-            }
-            (Failed, Some(Message::AckFailure), Message::Success(success)) => {
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Failed, Some(Message::AckFailure), Message::Failure(failure)) => {
-                self.server_state = Defunct;
-                Ok(Message::Failure(failure))
-            }
-
-            // INTERRUPTED
-            (Interrupted, Some(Message::Run(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::RunWithMetadata(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::PullAll), Message::Record(_)) => {
-                self.server_state = Interrupted;
-                // Put the PULL_ALL message back so we can keep consuming records
-                self.sent_queue.push_front(Message::PullAll);
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::PullAll), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Pull(pull)), Message::Record(_)) => {
-                self.server_state = Interrupted;
-                // Put the PULL message back so we can keep consuming records
-                self.sent_queue.push_front(Message::Pull(pull));
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Pull(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::DiscardAll), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Discard(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Begin(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Commit), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Rollback), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::AckFailure), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Route(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::RouteWithMetadata(_)), _) => {
-                self.server_state = Interrupted;
-                Ok(Message::Ignored)
-            }
-            (Interrupted, Some(Message::Reset), Message::Success(success)) => {
-                self.open_tx_streams = 0;
-                self.server_state = Ready;
-                Ok(Message::Success(success))
-            }
-            (Interrupted, Some(Message::Reset), Message::Failure(failure)) => {
-                self.server_state = Defunct;
-                Ok(Message::Failure(failure))
-            }
-            (state, request, response) => {
-                self.server_state = Defunct;
-                Err(CommunicationError::InvalidResponse {
-                    state,
-                    request,
-                    response,
-                })
-            }
-        }
-    }
-
-    pub(crate) async fn send_message(&mut self, message: Message) -> CommunicationResult<()> {
-        match (self.server_state, &message) {
-            (Connected, Message::Init(_)) => {}
-            (Connected, Message::Hello(_)) => {}
-            (Ready, Message::Run(_)) => {}
-            (Ready, Message::RunWithMetadata(_)) => {}
-            (Ready, Message::Begin(_)) => {}
-            (Ready, Message::Route(_)) => {}
-            (Ready, Message::RouteWithMetadata(_)) => {}
-            (Ready, Message::Reset) => {}
-            (Ready, Message::Goodbye) => {}
-            (Streaming, Message::PullAll) => {}
-            (Streaming, Message::Pull(_)) => {}
-            (Streaming, Message::DiscardAll) => {}
-            (Streaming, Message::Discard(_)) => {}
-            (Streaming, Message::Reset) => {}
-            (Streaming, Message::Goodbye) => {}
-            (TxReady, Message::RunWithMetadata(_)) => {}
-            (TxReady, Message::Commit) => {}
-            (TxReady, Message::Rollback) => {}
-            (TxReady, Message::Reset) => {}
-            (TxReady, Message::Goodbye) => {}
-            (TxStreaming, Message::RunWithMetadata(_)) => {}
-            (TxStreaming, Message::PullAll) => {}
-            (TxStreaming, Message::Pull(_)) => {}
-            (TxStreaming, Message::DiscardAll) => {}
-            (TxStreaming, Message::Discard(_)) => {}
-            (TxStreaming, Message::Reset) => {}
-            (TxStreaming, Message::Goodbye) => {}
-            (Failed, Message::Run(_)) => {}
-            (Failed, Message::RunWithMetadata(_)) => {}
-            (Failed, Message::PullAll) => {}
-            (Failed, Message::Pull(_)) => {}
-            (Failed, Message::DiscardAll) => {}
-            (Failed, Message::Discard(_)) => {}
-            (Failed, Message::AckFailure) => {}
-            (Failed, Message::Reset) => {}
-            (Failed, Message::Goodbye) => {}
-            (Interrupted, Message::Run(_)) => {}
-            (Interrupted, Message::RunWithMetadata(_)) => {}
-            (Interrupted, Message::PullAll) => {}
-            (Interrupted, Message::Pull(_)) => {}
-            (Interrupted, Message::DiscardAll) => {}
-            (Interrupted, Message::Discard(_)) => {}
-            (Interrupted, Message::AckFailure) => {}
-            (Interrupted, Message::Begin(_)) => {}
-            (Interrupted, Message::Commit) => {}
-            (Interrupted, Message::Rollback) => {}
-            (Interrupted, Message::Reset) => {}
-            (Interrupted, Message::Goodbye) => {}
-            (state, message) => {
-                self.server_state = Defunct;
-                return Err(CommunicationError::InvalidState {
-                    state,
-                    message: message.clone(),
-                });
-            }
-        }
-
-        #[cfg(test)]
-        println!(">>> {:?}", message);
-
-        let chunks = message.clone().into_chunks().map_err(ProtocolError::from)?;
-
-        for chunk in chunks {
-            self.stream.write_all(&chunk).await?;
+                    _ => None,
+                },
+                _ => None,
+            };
         }
-        self.stream.flush().await?;
-
-        // Immediate state changes
-        match message {
-            Message::Reset => self.server_state = Interrupted,
-            Message::Goodbye => self.server_state = Disconnected,
-            _ => {}
-        }
-
-        self.sent_queue.push_back(message);
-        Ok(())
+        Ok(response)
     }
 
-    /// Send a [`HELLO`](Message::Hello) (or [`INIT`](Message::Init)) message to the server.
-    /// _(Sends `INIT` for Bolt v1 - v2, and `HELLO` for Bolt v3+.)_
+    /// Send a [`ROUTE`](Message::RouteWithMetadata) message to the server.
+    /// _(Bolt v4.3+ only. For Bolt v4.3, an [alternate version](Message::Route) of the message is
+    /// sent.)_
     ///
     /// # Description
-    /// The `HELLO` message requests the connection to be authorized for use with the remote
-    /// database. Clients should send a `HELLO` message to the server immediately after connection
-    /// and process the response before using that connection in any other way.
-    ///
-    /// The server must be in the [`Connected`](ServerState::Connected) state to be able to process
-    /// a `HELLO` message. For any other states, receipt of a `HELLO` message is considered a
-    /// protocol violation and leads to connection closure.
+    /// The `ROUTE` message instructs the server to return the current routing table.
     ///
-    /// If authentication fails, the server will respond with a [`FAILURE`](Message::Failure)
-    /// message and immediately close the connection. Clients wishing to retry initialization
-    /// should establish a new connection.
-    ///
-    /// # Fields
-    /// `metadata` should contain at least two entries:
-    /// - `user_agent`, which should conform to the format `"Name/Version"`, for example
-    ///   `"Example/1.0.0"` (see
-    ///   [here](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/User-Agent)).
-    /// - `scheme` is the authentication scheme. Predefined schemes are `"none"`, `"basic"`, or
-    ///   `"kerberos"`.
-    ///
-    /// If using Bolt v4.1 or later, the following additional `metadata` entries can be specified:
-    /// - `routing`, a map which should contain routing context information as well as an `address`
-    ///   field indicating to which address the client should initially connect. Leaving this
-    ///   unspecified indicates that the server should not carry out any routing.
-    ///   _(Bolt v4.1+ only.)_
-    ///
-    /// Further entries in `metadata` are passed to the implementation of the chosen authentication
-    /// scheme. Their names, types, and defaults depend on that choice. For example, the scheme
-    /// `"basic"` requires `metadata` to contain the username and password in the form
-    /// `{"principal": "<username>", "credentials": "<password>"}`.
-    ///
-    /// # Response
-    /// - [`Message::Success`] - initialization has completed successfully and the server has
-    ///   entered the [`Ready`](ServerState::Ready) state. The server may include metadata that
-    ///   describes details of the server environment and/or the connection. The following fields
-    ///   are defined for inclusion in the `SUCCESS` metadata:
-    ///   - `server`, the server agent string (e.g. `"Neo4j/4.3.0"`)
-    ///   - `connection_id`, a unique identifier for the connection (e.g. `"bolt-61"`)
-    ///     _(Bolt v3+ only.)_
-    ///   - `hints`, a map of configuration hints (e.g. `{"connection.recv_timeout_seconds": 120}`)
-    ///     These hints may be interpreted or ignored by drivers at their own discretion in order
-    ///     to augment operations where applicable. Hints remain valid throughout the lifetime of a
-    ///     given connection and cannot be changed. As such, newly established connections may
-    ///     observe different hints as the server configuration is adjusted.
-    ///     _(Bolt v4.3+ only.)_
-    /// - [`Message::Failure`] - initialization has failed and the server has entered the
-    ///   [`Defunct`](ServerState::Defunct) state. The server may choose to include metadata
-    ///   describing the nature of the failure but will immediately close the connection after the
-    ///   failure has been sent.
-    #[bolt_version(1, 2, 3, 4, 4.1, 4.2, 4.3, 4.4)]
-    pub async fn hello(&mut self, mut metadata: Metadata) -> CommunicationResult<Message> {
-        let message = match self.version() {
-            V1_0 | V2_0 => {
-                let user_agent: String = metadata
-                    .value
-                    .remove("user_agent")
-                    .ok_or_else(|| {
-                        io::Error::new(io::ErrorKind::InvalidInput, "missing user_agent")
-                    })?
-                    .try_into()
-                    .map_err(|_| {
-                        io::Error::new(io::ErrorKind::InvalidInput, "user_agent must be a string")
-                    })?;
-                let auth_token = metadata.value;
-
-                Message::Init(Init::new(user_agent, auth_token))
-            }
-            _ => Message::Hello(Hello::new(metadata.value)),
-        };
-
-        self.send_message(message).await?;
-        self.read_message().await
-    }
-
-    /// Send a [`ROUTE`](Message::RouteWithMetadata) message to the server.
-    /// _(Bolt v4.3+ only. For Bolt v4.3, an [alternate version](Message::Route) of the message is
-    /// sent.)_
-    ///
-    /// # Description
-    /// The `ROUTE` message instructs the server to return the current routing table.
-    ///
-    /// The server must be in the [`Ready`](ServerState::Ready) state to be able to successfully
-    /// process a `ROUTE` request. If the server is in the [`Failed`](ServerState::Failed) or
-    /// [`Interrupted`](ServerState::Interrupted) state, the response will be
-    /// [`IGNORED`](Message::Ignored). For any other states, receipt of a `ROUTE` request will be
-    /// considered a protocol violation and will lead to connection closure.
+    /// The server must be in the [`Ready`](ServerState::Ready) state to be able to successfully
+    /// process a `ROUTE` request. If the server is in the [`Failed`](ServerState::Failed) or
+    /// [`Interrupted`](ServerState::Interrupted) state, the response will be
+    /// [`IGNORED`](Message::Ignored). For any other states, receipt of a `ROUTE` request will be
+    /// considered a protocol violation and will lead to connection closure.
     ///
     /// # Fields
     /// - `context`, which should contain routing context information as well as an `address` field
@@ -1087,8 +924,8 @@ impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
     #[bolt_version(3, 4, 4.1, 4.2, 4.3, 4.4)]
     pub async fn goodbye(&mut self) -> CommunicationResult<()> {
         self.send_message(Message::Goodbye).await?;
-        self.server_state = Defunct;
-        Ok(self.stream.close().await?)
+        self.state.server_state = Defunct;
+        Ok(timeout(self.timeouts.write, self.stream.close()).await??)
     }
 
     /// Send multiple messages to the server without waiting for a response. Returns a [`Vec`]
@@ -1117,33 +954,443 @@ impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
     /// message to the server. Until the server receives the `RESET`/`ACK_FAILURE` message, it will
     /// send an [`IGNORED`](Message::Ignored) message in response to any other message from the
     /// client, including messages that were sent in a pipeline.
+    ///
+    /// Unlike the other `Client` methods, this doesn't check whether each message is valid in the
+    /// server state that the previous messages would lead to. Use [`Client::batch`] for that, and
+    /// to get the responses grouped by the message they respond to. To keep sending messages
+    /// while responses are being consumed, [split](Client::split) the client instead.
     pub async fn pipeline(&mut self, messages: Vec<Message>) -> CommunicationResult<Vec<Message>> {
         // This Vec is too small if we're expecting some RECORD messages, so there's no "good" size
         let mut responses = Vec::with_capacity(messages.len());
+        self.write_messages(messages).await?;
 
-        for message in &messages {
-            #[cfg(test)]
-            println!(">>> {:?}", message);
+        while !self.state.sent_queue.is_empty() {
+            responses.push(self.read_message().await?);
+        }
+        Ok(responses)
+    }
+}
 
-            let chunks = message.clone().into_chunks().map_err(ProtocolError::from)?;
+impl Default for State {
+    fn default() -> Self {
+        Self {
+            server_state: Connected,
+            sent_queue: VecDeque::default(),
+            open_tx_streams: 0,
+        }
+    }
+}
 
-            for chunk in chunks {
-                self.stream.write_all(&chunk).await?;
+impl State {
+    /// Return whether the server accepts the given message in the current state.
+    pub(crate) fn accepts(&self, message: &Message) -> bool {
+        matches!(
+            (self.server_state, message),
+            (Connected, Message::Init(_))
+                | (Connected, Message::Hello(_))
+                | (Ready, Message::Run(_))
+                | (Ready, Message::RunWithMetadata(_))
+                | (Ready, Message::Begin(_))
+                | (Ready, Message::Route(_))
+                | (Ready, Message::RouteWithMetadata(_))
+                | (Ready, Message::Reset)
+                | (Ready, Message::Goodbye)
+                | (Streaming, Message::PullAll)
+                | (Streaming, Message::Pull(_))
+                | (Streaming, Message::DiscardAll)
+                | (Streaming, Message::Discard(_))
+                | (Streaming, Message::Reset)
+                | (Streaming, Message::Goodbye)
+                | (TxReady, Message::RunWithMetadata(_))
+                | (TxReady, Message::Commit)
+                | (TxReady, Message::Rollback)
+                | (TxReady, Message::Reset)
+                | (TxReady, Message::Goodbye)
+                | (TxStreaming, Message::RunWithMetadata(_))
+                | (TxStreaming, Message::PullAll)
+                | (TxStreaming, Message::Pull(_))
+                | (TxStreaming, Message::DiscardAll)
+                | (TxStreaming, Message::Discard(_))
+                | (TxStreaming, Message::Reset)
+                | (TxStreaming, Message::Goodbye)
+                | (Failed, Message::Run(_))
+                | (Failed, Message::RunWithMetadata(_))
+                | (Failed, Message::PullAll)
+                | (Failed, Message::Pull(_))
+                | (Failed, Message::DiscardAll)
+                | (Failed, Message::Discard(_))
+                | (Failed, Message::AckFailure)
+                | (Failed, Message::Reset)
+                | (Failed, Message::Goodbye)
+                | (Interrupted, Message::Run(_))
+                | (Interrupted, Message::RunWithMetadata(_))
+                | (Interrupted, Message::PullAll)
+                | (Interrupted, Message::Pull(_))
+                | (Interrupted, Message::DiscardAll)
+                | (Interrupted, Message::Discard(_))
+                | (Interrupted, Message::AckFailure)
+                | (Interrupted, Message::Begin(_))
+                | (Interrupted, Message::Commit)
+                | (Interrupted, Message::Rollback)
+                | (Interrupted, Message::Reset)
+                | (Interrupted, Message::Goodbye)
+        )
+    }
+
+    /// Update the state after sending a message to the server.
+    pub(crate) fn handle_request(&mut self, message: Message) {
+        // Immediate state changes
+        match message {
+            Message::Reset => self.server_state = Interrupted,
+            Message::Goodbye => self.server_state = Disconnected,
+            _ => {}
+        }
+
+        self.sent_queue.push_back(message);
+    }
+
+    /// Update the state after receiving a response from the server, which is matched up with the
+    /// oldest message that hasn't been fully responded to.
+    // CommunicationError is large, but this is the error type of every Client method
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn handle_response(&mut self, message: Message) -> CommunicationResult<Message> {
+        match (self.server_state, self.sent_queue.pop_front(), message) {
+            // CONNECTED
+            (Connected, Some(Message::Init(_)), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Connected, Some(Message::Init(_)), Message::Failure(failure)) => {
+                self.server_state = Defunct;
+                Ok(Message::Failure(failure))
+            }
+            (Connected, Some(Message::Hello(_)), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Connected, Some(Message::Hello(_)), Message::Failure(failure)) => {
+                self.server_state = Defunct;
+                Ok(Message::Failure(failure))
             }
 
-            // Immediate state changes
-            match message {
-                Message::Reset => self.server_state = Interrupted,
-                Message::Goodbye => self.server_state = Disconnected,
-                _ => {}
+            // READY
+            (Ready, Some(Message::Run(_)), Message::Success(success)) => {
+                self.server_state = Streaming;
+                Ok(Message::Success(success))
+            }
+            (Ready, Some(Message::Run(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Ready, Some(Message::RunWithMetadata(_)), Message::Success(success)) => {
+                self.server_state = Streaming;
+                Ok(Message::Success(success))
+            }
+            (Ready, Some(Message::RunWithMetadata(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Ready, Some(Message::Begin(_)), Message::Success(success)) => {
+                self.server_state = TxReady;
+                Ok(Message::Success(success))
+            }
+            (Ready, Some(Message::Begin(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Ready, Some(Message::Route(_)), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Ready, Some(Message::Route(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Ready, Some(Message::RouteWithMetadata(_)), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Ready, Some(Message::RouteWithMetadata(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
             }
-        }
-        self.stream.flush().await?;
-        self.sent_queue.extend(messages);
 
-        while !self.sent_queue.is_empty() {
-            responses.push(self.read_message().await?);
+            // STREAMING
+            (Streaming, Some(Message::PullAll), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Streaming, Some(Message::PullAll), Message::Record(record)) => {
+                self.server_state = Streaming;
+                // Put the PULL_ALL message back so we can keep consuming records
+                self.sent_queue.push_front(Message::PullAll);
+                Ok(Message::Record(record))
+            }
+            (Streaming, Some(Message::PullAll), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Streaming, Some(Message::Pull(_)), Message::Success(success)) => {
+                self.server_state = match success.metadata().get("has_more") {
+                    Some(&Value::Boolean(true)) => Streaming,
+                    _ => Ready,
+                };
+                Ok(Message::Success(success))
+            }
+            (Streaming, Some(Message::Pull(pull)), Message::Record(record)) => {
+                self.server_state = Streaming;
+                // Put the PULL message back so we can keep consuming records
+                self.sent_queue.push_front(Message::Pull(pull));
+                Ok(Message::Record(record))
+            }
+            (Streaming, Some(Message::Pull(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Streaming, Some(Message::DiscardAll), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Streaming, Some(Message::DiscardAll), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (Streaming, Some(Message::Discard(_)), Message::Success(success)) => {
+                self.server_state = match success.metadata().get("has_more") {
+                    Some(&Value::Boolean(true)) => Streaming,
+                    _ => Ready,
+                };
+                Ok(Message::Success(success))
+            }
+            (Streaming, Some(Message::Discard(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+
+            // TX_READY
+            (TxReady, Some(Message::RunWithMetadata(_)), Message::Success(success)) => {
+                self.open_tx_streams += 1;
+                self.server_state = TxStreaming;
+                Ok(Message::Success(success))
+            }
+            (TxReady, Some(Message::RunWithMetadata(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (TxReady, Some(Message::Commit), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (TxReady, Some(Message::Commit), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (TxReady, Some(Message::Rollback), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (TxReady, Some(Message::Rollback), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+
+            // TX_STREAMING
+            (TxStreaming, Some(Message::RunWithMetadata(_)), Message::Success(success)) => {
+                self.open_tx_streams += 1;
+                self.server_state = TxStreaming;
+                Ok(Message::Success(success))
+            }
+            (TxStreaming, Some(Message::RunWithMetadata(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (TxStreaming, Some(Message::PullAll), Message::Success(success)) => {
+                self.open_tx_streams -= 1;
+                self.server_state = TxReady;
+                Ok(Message::Success(success))
+            }
+            (TxStreaming, Some(Message::PullAll), Message::Record(record)) => {
+                self.server_state = TxStreaming;
+                // Put the PULL_ALL message back so we can keep consuming records
+                self.sent_queue.push_front(Message::PullAll);
+                Ok(Message::Record(record))
+            }
+            (TxStreaming, Some(Message::PullAll), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (TxStreaming, Some(Message::Pull(_)), Message::Success(success)) => {
+                self.server_state = match success.metadata().get("has_more") {
+                    Some(&Value::Boolean(true)) => TxStreaming,
+                    _ => {
+                        self.open_tx_streams -= 1;
+                        if self.open_tx_streams > 0 {
+                            TxStreaming
+                        } else {
+                            TxReady
+                        }
+                    }
+                };
+                Ok(Message::Success(success))
+            }
+            (TxStreaming, Some(Message::Pull(pull)), Message::Record(record)) => {
+                self.server_state = TxStreaming;
+                // Put the PULL message back so we can keep consuming records
+                self.sent_queue.push_front(Message::Pull(pull));
+                Ok(Message::Record(record))
+            }
+            (TxStreaming, Some(Message::Pull(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (TxStreaming, Some(Message::DiscardAll), Message::Success(success)) => {
+                self.open_tx_streams -= 1;
+                self.server_state = TxReady;
+                Ok(Message::Success(success))
+            }
+            (TxStreaming, Some(Message::DiscardAll), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+            (TxStreaming, Some(Message::Discard(_)), Message::Success(success)) => {
+                self.server_state = match success.metadata().get("has_more") {
+                    Some(&Value::Boolean(true)) => TxStreaming,
+                    _ => {
+                        self.open_tx_streams -= 1;
+                        if self.open_tx_streams > 0 {
+                            TxStreaming
+                        } else {
+                            TxReady
+                        }
+                    }
+                };
+                Ok(Message::Success(success))
+            }
+            (TxStreaming, Some(Message::Discard(_)), Message::Failure(failure)) => {
+                self.server_state = Failed;
+                Ok(Message::Failure(failure))
+            }
+
+            // FAILED
+            (Failed, Some(Message::Run(_)), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::RunWithMetadata(_)), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::PullAll), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::Pull(_)), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::DiscardAll), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::Discard(_)), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::Route(_)), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::RouteWithMetadata(_)), Message::Ignored) => {
+                self.server_state = Failed;
+                Ok(Message::Ignored)
+            }
+            (Failed, Some(Message::AckFailure), Message::Success(success)) => {
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Failed, Some(Message::AckFailure), Message::Failure(failure)) => {
+                self.server_state = Defunct;
+                Ok(Message::Failure(failure))
+            }
+
+            // INTERRUPTED
+            (Interrupted, Some(Message::Run(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::RunWithMetadata(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::PullAll), Message::Record(_)) => {
+                self.server_state = Interrupted;
+                // Put the PULL_ALL message back so we can keep consuming records
+                self.sent_queue.push_front(Message::PullAll);
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::PullAll), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Pull(pull)), Message::Record(_)) => {
+                self.server_state = Interrupted;
+                // Put the PULL message back so we can keep consuming records
+                self.sent_queue.push_front(Message::Pull(pull));
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Pull(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::DiscardAll), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Discard(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Begin(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Commit), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Rollback), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::AckFailure), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Route(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::RouteWithMetadata(_)), _) => {
+                self.server_state = Interrupted;
+                Ok(Message::Ignored)
+            }
+            (Interrupted, Some(Message::Reset), Message::Success(success)) => {
+                self.open_tx_streams = 0;
+                self.server_state = Ready;
+                Ok(Message::Success(success))
+            }
+            (Interrupted, Some(Message::Reset), Message::Failure(failure)) => {
+                self.server_state = Defunct;
+                Ok(Message::Failure(failure))
+            }
+            (state, request, response) => {
+                self.server_state = Defunct;
+                Err(CommunicationError::InvalidResponse {
+                    state,
+                    request,
+                    response,
+                })
+            }
         }
-        Ok(responses)
     }
 }
@@ -0,0 +1,498 @@
+use std::{collections::VecDeque, ops::Index, slice};
+
+use futures_util::io::{AsyncRead, AsyncWrite};
+
+use bolt_proto::{message::*, value::ValueMap, version::*, Message, ServerState::*, Value};
+
+use crate::{
+    client::State,
+    error::{CommunicationError, CommunicationResult},
+    Client, Metadata, Params,
+};
+
+/// A builder for a batch of messages that are sent to the server together, created by
+/// [`Client::batch`].
+///
+/// Each message is checked against the server states that the previous messages in the batch
+/// could lead to if they succeed. A [`PULL`](Message::Pull) or [`DISCARD`](Message::Discard) with
+/// a limit `n` other than `-1` may or may not leave records in the result, so the next message
+/// only needs to be valid in one of those cases. If a message isn't valid in any of the possible
+/// states, or isn't supported by the client's protocol version, [`send`](Batch::send) returns an
+/// error without sending anything.
+/// Like with the other `Client` methods, responses to a cancelled operation must be read with
+/// [`Client::reset`] before a batch can be sent.
+#[derive(Debug)]
+pub struct Batch<'a, S: AsyncRead + AsyncWrite + Unpin> {
+    client: &'a mut Client<S>,
+    messages: Vec<Message>,
+    // Every state the server could be in after the messages so far
+    predicted: Vec<State>,
+    error: Option<CommunicationError>,
+}
+
+/// Identifies the response to a [`RUN`](Message::RunWithMetadata) message added with
+/// [`Batch::run`]. Index the [`BatchResponses`] returned by [`Batch::send`] with it to get a
+/// [`RunResult`].
+///
+/// Handles are only meaningful for the batch that created them. Indexing the responses to a
+/// different batch returns an unrelated response, or panics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RunHandle(usize);
+
+/// Identifies the response to a [`PULL`](Message::Pull) message added with [`Batch::pull`].
+/// Index the [`BatchResponses`] returned by [`Batch::send`] with it to get a [`PullResult`].
+///
+/// Handles are only meaningful for the batch that created them, see [`RunHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PullHandle(usize);
+
+/// Identifies the response to a `DISCARD`, `BEGIN`, `COMMIT`, or `ROLLBACK` message added to a
+/// [`Batch`]. Index the [`BatchResponses`] returned by [`Batch::send`] with it to get the
+/// response [`Message`].
+///
+/// Handles are only meaningful for the batch that created them, see [`RunHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResponseHandle(usize);
+
+/// The responses to the messages sent in a [`Batch`], in the order the messages were added.
+/// Index it with the handles returned when adding messages to the batch:
+/// `responses[run_handle]` is a [`RunResult`], `responses[pull_handle]` a [`PullResult`], and
+/// `responses[response_handle]` a [`Message`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResponses {
+    responses: Vec<BatchResponse>,
+}
+
+/// The response to a message sent in a [`Batch`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum BatchResponse {
+    Run(RunResult),
+    Pull(PullResult),
+    Discard(Message),
+    Begin(Message),
+    Commit(Message),
+    Rollback(Message),
+}
+
+/// The response to a [`RUN`](Message::RunWithMetadata) message sent in a [`Batch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunResult {
+    response: Message,
+}
+
+/// The records and summary sent in response to a [`PULL`](Message::Pull) message in a [`Batch`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullResult {
+    records: Vec<Record>,
+    response: Message,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Start building a [`Batch`] of messages, which are sent together like with
+    /// [`Client::pipeline`], but whose responses are grouped by the message they respond to:
+    /// ```
+    /// # use std::env;
+    /// #
+    /// # use bolt_client::*;
+    /// # use bolt_proto::{version::*, Message, ServerState::*};
+    /// # use tokio::io::BufStream;
+    /// # use tokio_util::compat::*;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #     let stream = Stream::connect(env::var("BOLT_TEST_ADDR")?,
+    /// #                                  env::var("BOLT_TEST_DOMAIN").ok()).await?;
+    /// #     let stream = BufStream::new(stream).compat();
+    /// #     let mut client = Client::new(stream, &[V4_4, 0, 0, 0]).await?;
+    /// #     client.hello(Metadata::from_iter(vec![
+    /// #         ("user_agent", "my-client-name/1.0"),
+    /// #         ("scheme", "basic"),
+    /// #         ("principal", &env::var("BOLT_TEST_USERNAME")?),
+    /// #         ("credentials", &env::var("BOLT_TEST_PASSWORD")?),
+    /// #     ])).await?;
+    /// let pull_all = || Some(Metadata::from_iter(vec![("n", -1)]));
+    /// let mut batch = client.batch();
+    /// let first_run = batch.run("RETURN 1;", None, None);
+    /// let first_pull = batch.pull(pull_all());
+    /// batch.run("RETURN 2;", None, None);
+    /// let second_pull = batch.pull(pull_all());
+    /// let responses = batch.send().await?;
+    ///
+    /// assert!(matches!(responses[first_run].response(), Message::Success(_)));
+    /// assert_eq!(responses[first_pull].records().len(), 1);
+    /// assert_eq!(responses[second_pull].records().len(), 1);
+    /// assert_eq!(client.server_state(), Ready);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn batch(&mut self) -> Batch<'_, S> {
+        let predicted = State {
+            server_state: self.state.server_state,
+            sent_queue: VecDeque::default(),
+            open_tx_streams: self.state.open_tx_streams,
+        };
+        Batch {
+            client: self,
+            messages: Vec::new(),
+            predicted: vec![predicted],
+            error: None,
+        }
+    }
+}
+
+impl<'a, S: AsyncRead + AsyncWrite + Unpin> Batch<'a, S> {
+    /// Add a [`RUN`](Message::RunWithMetadata) message to the batch. See [`Client::run`].
+    pub fn run(
+        &mut self,
+        query: impl Into<String>,
+        parameters: Option<Params>,
+        metadata: Option<Metadata>,
+    ) -> RunHandle {
+        let message = match self.client.version() {
+            V1_0 | V2_0 => {
+                Message::Run(Run::new(query.into(), parameters.unwrap_or_default().value))
+            }
+            _ => Message::RunWithMetadata(RunWithMetadata::new(
+                query.into(),
+                parameters.unwrap_or_default().value,
+                metadata.unwrap_or_default().value,
+            )),
+        };
+        RunHandle(self.push(message))
+    }
+
+    /// Add a [`PULL`](Message::Pull) (or [`PULL_ALL`](Message::PullAll)) message to the batch.
+    /// See [`Client::pull`].
+    pub fn pull(&mut self, metadata: Option<Metadata>) -> PullHandle {
+        let message = match self.client.version() {
+            V1_0 | V2_0 | V3_0 => Message::PullAll,
+            _ => Message::Pull(Pull::new(metadata.unwrap_or_default().value)),
+        };
+        PullHandle(self.push(message))
+    }
+
+    /// Add a [`DISCARD`](Message::Discard) (or [`DISCARD_ALL`](Message::DiscardAll)) message to
+    /// the batch. See [`Client::discard`].
+    pub fn discard(&mut self, metadata: Option<Metadata>) -> ResponseHandle {
+        let message = match self.client.version() {
+            V1_0 | V2_0 | V3_0 => Message::DiscardAll,
+            _ => Message::Discard(Discard::new(metadata.unwrap_or_default().value)),
+        };
+        ResponseHandle(self.push(message))
+    }
+
+    /// Add a [`BEGIN`](Message::Begin) message to the batch. See [`Client::begin`].
+    /// _(Bolt v3+ only.)_
+    pub fn begin(&mut self, metadata: Option<Metadata>) -> ResponseHandle {
+        let message = Message::Begin(Begin::new(metadata.unwrap_or_default().value));
+        self.push_v3(message)
+    }
+
+    /// Add a [`COMMIT`](Message::Commit) message to the batch. See [`Client::commit`].
+    /// _(Bolt v3+ only.)_
+    pub fn commit(&mut self) -> ResponseHandle {
+        self.push_v3(Message::Commit)
+    }
+
+    /// Add a [`ROLLBACK`](Message::Rollback) message to the batch. See [`Client::rollback`].
+    /// _(Bolt v3+ only.)_
+    pub fn rollback(&mut self) -> ResponseHandle {
+        self.push_v3(Message::Rollback)
+    }
+
+    fn push_v3(&mut self, message: Message) -> ResponseHandle {
+        let version = self.client.version();
+        if matches!(version, V1_0 | V2_0) && self.error.is_none() {
+            self.error = Some(CommunicationError::UnsupportedOperation(version));
+        }
+        ResponseHandle(self.push(message))
+    }
+
+    // Returns the index of the message in the batch
+    fn push(&mut self, message: Message) -> usize {
+        if self.error.is_none() {
+            if let Err(error) = self.predict(&message) {
+                self.error = Some(error);
+            }
+        }
+        self.messages.push(message);
+        self.messages.len() - 1
+    }
+
+    // Predict the next states by assuming the message succeeds in each state that accepts it
+    #[allow(clippy::result_large_err)]
+    fn predict(&mut self, message: &Message) -> CommunicationResult<()> {
+        // Responses to an operation that was dropped between messages may still be on their way
+        let awaiting_responses = !self.client.state.sent_queue.is_empty();
+        let accepting: Vec<State> = self
+            .predicted
+            .iter()
+            .filter(|state| state.accepts(message))
+            .cloned()
+            .collect();
+        if awaiting_responses || accepting.is_empty() {
+            return Err(CommunicationError::InvalidState {
+                state: self.predicted[0].server_state,
+                message: message.clone(),
+            });
+        }
+
+        let mut predicted: Vec<State> = Vec::new();
+        for state in accepting {
+            for response in predicted_responses(&state, message) {
+                let mut next = state.clone();
+                next.handle_request(message.clone());
+                next.handle_response(response)?;
+                let seen = predicted.iter().any(|other| {
+                    other.server_state == next.server_state
+                        && other.open_tx_streams == next.open_tx_streams
+                });
+                if !seen {
+                    predicted.push(next);
+                }
+            }
+        }
+        self.predicted = predicted;
+        Ok(())
+    }
+
+    /// Send the messages in the batch, and wait for all of their responses.
+    pub async fn send(self) -> CommunicationResult<BatchResponses> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let client = self.client;
+        client.write_messages(self.messages.clone()).await?;
+
+        let mut responses = Vec::with_capacity(self.messages.len());
+        for message in self.messages {
+            let response = match message {
+                Message::Run(_) | Message::RunWithMetadata(_) => BatchResponse::Run(RunResult {
+                    response: client.read_message().await?,
+                }),
+                Message::PullAll | Message::Pull(_) => {
+                    let mut records = vec![];
+                    loop {
+                        match client.read_message().await? {
+                            Message::Record(record) => records.push(record),
+                            response => {
+                                break BatchResponse::Pull(PullResult { records, response })
+                            }
+                        }
+                    }
+                }
+                Message::DiscardAll | Message::Discard(_) => {
+                    BatchResponse::Discard(client.read_message().await?)
+                }
+                Message::Begin(_) => BatchResponse::Begin(client.read_message().await?),
+                Message::Commit => BatchResponse::Commit(client.read_message().await?),
+                Message::Rollback => BatchResponse::Rollback(client.read_message().await?),
+                _ => unreachable!("only the messages above can be added to a batch"),
+            };
+            responses.push(response);
+        }
+        Ok(BatchResponses { responses })
+    }
+}
+
+// Every message is ignored once the server has failed or been interrupted. A PULL or DISCARD with
+// a finite limit may or may not leave more records in the result.
+fn predicted_responses(state: &State, message: &Message) -> Vec<Message> {
+    let success = |metadata| Message::Success(Success::new(metadata));
+    let limit = match message {
+        Message::Pull(pull) => pull.metadata().get("n"),
+        Message::Discard(discard) => discard.metadata().get("n"),
+        _ => None,
+    };
+    match (state.server_state, limit) {
+        (Failed | Interrupted, _) => vec![Message::Ignored],
+        (_, Some(Value::Integer(n))) if *n != -1 => vec![
+            success(ValueMap::from([(
+                String::from("has_more"),
+                Value::Boolean(true),
+            )])),
+            success(ValueMap::new()),
+        ],
+        _ => vec![success(ValueMap::new())],
+    }
+}
+
+impl BatchResponses {
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+
+    /// Iterate over the responses in the order the messages were added to the batch.
+    pub fn iter(&self) -> slice::Iter<'_, BatchResponse> {
+        self.responses.iter()
+    }
+
+    /// Get the responses in the order the messages were added to the batch.
+    pub fn into_vec(self) -> Vec<BatchResponse> {
+        self.responses
+    }
+}
+
+impl<'a> IntoIterator for &'a BatchResponses {
+    type Item = &'a BatchResponse;
+    type IntoIter = slice::Iter<'a, BatchResponse>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Index<RunHandle> for BatchResponses {
+    type Output = RunResult;
+
+    fn index(&self, handle: RunHandle) -> &RunResult {
+        match &self.responses[handle.0] {
+            BatchResponse::Run(result) => result,
+            _ => panic!("handle belongs to a different batch"),
+        }
+    }
+}
+
+impl Index<PullHandle> for BatchResponses {
+    type Output = PullResult;
+
+    fn index(&self, handle: PullHandle) -> &PullResult {
+        match &self.responses[handle.0] {
+            BatchResponse::Pull(result) => result,
+            _ => panic!("handle belongs to a different batch"),
+        }
+    }
+}
+
+impl Index<ResponseHandle> for BatchResponses {
+    type Output = Message;
+
+    fn index(&self, handle: ResponseHandle) -> &Message {
+        match &self.responses[handle.0] {
+            BatchResponse::Discard(response)
+            | BatchResponse::Begin(response)
+            | BatchResponse::Commit(response)
+            | BatchResponse::Rollback(response) => response,
+            _ => panic!("handle belongs to a different batch"),
+        }
+    }
+}
+
+impl RunResult {
+    /// Get the server's response, which is a [`SUCCESS`](Message::Success),
+    /// [`FAILURE`](Message::Failure), or [`IGNORED`](Message::Ignored) message.
+    pub fn response(&self) -> &Message {
+        &self.response
+    }
+
+    pub fn into_response(self) -> Message {
+        self.response
+    }
+}
+
+impl PullResult {
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    /// Get the summary that followed the records, which is a [`SUCCESS`](Message::Success),
+    /// [`FAILURE`](Message::Failure), or [`IGNORED`](Message::Ignored) message.
+    pub fn response(&self) -> &Message {
+        &self.response
+    }
+
+    /// Get the records and the summary, like the return value of [`Client::pull`].
+    pub fn into_parts(self) -> (Vec<Record>, Message) {
+        (self.records, self.response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bolt_proto::{message::*, value::ValueMap, Message, ServerState::*, Value};
+    use futures_util::FutureExt;
+    use tokio::io::AsyncReadExt;
+
+    use crate::{client::timeout::tests::*, error::CommunicationError, Metadata, Timeouts};
+
+    fn pull(n: i64) -> Option<Metadata> {
+        Some(Metadata::from_iter(vec![("n", n)]))
+    }
+
+    fn has_more() -> Message {
+        Message::Success(Success::new(ValueMap::from([(
+            String::from("has_more"),
+            Value::Boolean(true),
+        )])))
+    }
+
+    #[tokio::test]
+    async fn finite_pulls() {
+        let (mut client, mut server) = connect(Timeouts::default()).await;
+
+        // A finite PULL may leave the result open, or finish it
+        let mut batch = client.batch();
+        batch.run("RETURN 1;", None, None);
+        batch.pull(pull(100));
+        batch.pull(pull(100));
+        assert!(batch.error.is_none());
+
+        let mut batch = client.batch();
+        batch.run("RETURN 1;", None, None);
+        batch.pull(pull(10));
+        batch.discard(pull(-1));
+        assert!(batch.error.is_none());
+
+        let mut batch = client.batch();
+        batch.begin(None);
+        batch.run("RETURN 1;", None, None);
+        batch.pull(pull(10));
+        batch.commit();
+        assert!(batch.error.is_none());
+
+        // PULL with n = -1 always finishes the result
+        let mut batch = client.batch();
+        batch.run("RETURN 1;", None, None);
+        batch.pull(pull(-1));
+        batch.pull(pull(-1));
+        assert!(matches!(
+            batch.send().await,
+            Err(CommunicationError::InvalidState { state: Ready, .. })
+        ));
+        assert_eq!(client.server_state(), Ready);
+        assert!(server.read(&mut [0; 1]).now_or_never().is_none());
+    }
+
+    #[tokio::test]
+    async fn send() {
+        let (mut client, mut server) = connect(Timeouts::default()).await;
+        let record = |n: i64| Message::Record(Record::new(vec![Value::from(n)]));
+        for response in [success(), record(1), has_more(), record(2), success()] {
+            write_message(&mut server, response).await;
+        }
+
+        let mut batch = client.batch();
+        let run = batch.run("UNWIND [1, 2] AS n RETURN n;", None, None);
+        let first = batch.pull(pull(1));
+        let second = batch.pull(pull(1));
+        let responses = batch.send().await.unwrap();
+
+        assert_eq!(responses.len(), 3);
+        assert_eq!(responses[run].response(), &success());
+        assert_eq!(
+            responses[first].records(),
+            &[Record::new(vec![Value::from(1)])]
+        );
+        assert_eq!(responses[first].response(), &has_more());
+        assert_eq!(
+            responses[second].records(),
+            &[Record::new(vec![Value::from(2)])]
+        );
+        assert_eq!(client.server_state(), Ready);
+    }
+}
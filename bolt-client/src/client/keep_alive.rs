@@ -0,0 +1,178 @@
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use futures_util::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+
+use bolt_proto::{Message, ServerState::*};
+
+use crate::{
+    client::{timeout, Tracked},
+    error::CommunicationResult,
+    Client,
+};
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Send a [`RESET`](Message::Reset) message to check that the connection is still alive, but
+    /// only if needed. Returns `None` without contacting the server if the client is in the
+    /// [`Ready`](bolt_proto::ServerState::Ready) state and was last used less than `max_idle` ago.
+    /// Otherwise, returns the response to [`Client::reset`].
+    ///
+    /// If the server gave a [receive timeout](Client::server_recv_timeout), connections idle for
+    /// more than half of it are also reset. Connections idle for longer than the whole timeout
+    /// have probably been closed by the server, so they're marked
+    /// [`Defunct`](bolt_proto::ServerState::Defunct) and an error is returned.
+    pub async fn reset_if_idle(
+        &mut self,
+        max_idle: Duration,
+    ) -> CommunicationResult<Option<Message>> {
+        let mut max_idle = max_idle;
+        if let Some(recv_timeout) = self.recv_timeout {
+            self.check_recv_timeout(recv_timeout)?;
+            max_idle = max_idle.min(recv_timeout / 2);
+        }
+
+        if self.server_state() == Ready
+            && self.state.sent_queue.is_empty()
+            && self.idle_time() < max_idle
+        {
+            return Ok(None);
+        }
+        self.reset().await.map(Some)
+    }
+
+    /// Send a no-op chunk to keep the connection alive, if it has been idle for at least half of
+    /// the server's [receive timeout](Client::server_recv_timeout). Returns whether a chunk was
+    /// sent. Unlike [`Client::reset_if_idle`], this doesn't wait for a response or change the
+    /// state of the server, so it's cheap enough to call periodically on idle connections.
+    /// _(Does nothing unless the server gave a receive timeout, which requires Bolt v4.3+.)_
+    ///
+    /// Connections idle for longer than the whole timeout have probably been closed by the
+    /// server, so they're marked [`Defunct`](bolt_proto::ServerState::Defunct) and an error is
+    /// returned.
+    #[allow(clippy::result_large_err)]
+    pub async fn keep_alive(&mut self) -> CommunicationResult<bool> {
+        let recv_timeout = match self.recv_timeout {
+            Some(recv_timeout) => recv_timeout,
+            None => return Ok(false),
+        };
+        self.check_recv_timeout(recv_timeout)?;
+        if self.idle_time() < recv_timeout / 2 {
+            return Ok(false);
+        }
+        if self.io_in_progress {
+            self.state.server_state = Defunct;
+            return Err(io::Error::new(
+                io::ErrorKind::BrokenPipe,
+                "an earlier operation stopped partway through a message",
+            )
+            .into());
+        }
+
+        let mut stream = Tracked::new(&mut self.stream, &mut self.io_in_progress);
+        let result = timeout(self.timeouts.write, async move {
+            stream.write_all(&[0, 0]).await?;
+            stream.flush().await
+        })
+        .await?;
+        if let Err(error) = result {
+            self.state.server_state = Defunct;
+            return Err(error.into());
+        }
+        self.io_in_progress = false;
+        self.last_activity = Instant::now();
+        Ok(true)
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn check_recv_timeout(&mut self, recv_timeout: Duration) -> CommunicationResult<()> {
+        if self.idle_time() >= recv_timeout {
+            self.state.server_state = Defunct;
+            return Err(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "connection was idle for longer than the server's receive timeout",
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bolt_proto::{message::*, value::ValueMap, Value};
+    use futures_timer::Delay;
+    use futures_util::FutureExt;
+    use tokio::io::AsyncReadExt;
+
+    use super::*;
+    use crate::{client::timeout::tests::*, Metadata, Timeouts};
+
+    #[tokio::test]
+    async fn recv_timeout_hint() {
+        let (mut client, mut server) = connect(Timeouts::default()).await;
+        client.state.server_state = Connected;
+
+        let hints = ValueMap::from_iter(vec![(
+            "connection.recv_timeout_seconds".to_string(),
+            Value::from(120),
+        )]);
+        let metadata = ValueMap::from_iter(vec![("hints".to_string(), Value::from(hints))]);
+        write_message(&mut server, Message::Success(Success::new(metadata))).await;
+        client
+            .hello(Metadata::from_iter(vec![("user_agent", "test/1.0")]))
+            .await
+            .unwrap();
+        assert_eq!(client.server_recv_timeout(), Some(Duration::from_secs(120)));
+    }
+
+    #[tokio::test]
+    async fn reset_if_idle() {
+        let (mut client, mut server) = connect(Timeouts::default()).await;
+
+        // Recently used and ready, so nothing is sent
+        assert_eq!(
+            client.reset_if_idle(Duration::from_secs(60)).await.unwrap(),
+            None
+        );
+        assert!(server.read(&mut [0; 1]).now_or_never().is_none());
+
+        write_message(&mut server, success()).await;
+        assert_eq!(
+            client.reset_if_idle(Duration::ZERO).await.unwrap(),
+            Some(success())
+        );
+
+        // Idle for longer than the server allows
+        client.recv_timeout = Some(Duration::from_millis(20));
+        Delay::new(Duration::from_millis(30)).await;
+        assert!(client.reset_if_idle(Duration::from_secs(60)).await.is_err());
+        assert_eq!(client.server_state(), Defunct);
+    }
+
+    #[tokio::test]
+    async fn keep_alive() {
+        let (mut client, mut server) = connect(Timeouts::default()).await;
+
+        // Without a receive timeout, there's nothing to keep alive
+        Delay::new(Duration::from_millis(10)).await;
+        assert!(!client.keep_alive().await.unwrap());
+
+        client.recv_timeout = Some(Duration::from_millis(100));
+        assert!(!client.keep_alive().await.unwrap());
+        assert!(server.read(&mut [0; 1]).now_or_never().is_none());
+
+        Delay::new(Duration::from_millis(60)).await;
+        assert!(client.keep_alive().await.unwrap());
+        let mut noop = [1; 2];
+        server.read_exact(&mut noop).await.unwrap();
+        assert_eq!(noop, [0, 0]);
+        assert!(client.idle_time() < Duration::from_millis(50));
+        assert_eq!(client.server_state(), Ready);
+
+        Delay::new(Duration::from_millis(110)).await;
+        assert!(client.keep_alive().await.is_err());
+        assert_eq!(client.server_state(), Defunct);
+    }
+}
@@ -0,0 +1,500 @@
+use std::time::Duration;
+
+use futures_util::io::{AsyncRead, AsyncWrite};
+
+use bolt_proto::{message::*, value::ValueMap, version::*, Message, Value};
+
+use crate::{
+    client::{BatchResponse, PullResult, RunResult},
+    error::{CommunicationError, CommunicationResult},
+    Client, Metadata, Params, RoutingContext,
+};
+
+/// The kind of server access a transaction needs. Sent as the `mode` entry of the transaction
+/// metadata.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccessMode {
+    Read,
+    #[default]
+    Write,
+}
+
+/// Settings shared by auto-commit queries and explicit transactions, which are sent as the extra
+/// metadata of a `RUN` or `BEGIN` message.
+#[derive(Debug, Default, Clone)]
+struct TxConfig {
+    bookmarks: Option<Vec<String>>,
+    timeout: Option<Duration>,
+    metadata: Option<ValueMap>,
+    mode: Option<AccessMode>,
+    database: Option<String>,
+    impersonated_user: Option<String>,
+}
+
+impl TxConfig {
+    fn is_empty(&self) -> bool {
+        self.bookmarks.is_none()
+            && self.timeout.is_none()
+            && self.metadata.is_none()
+            && self.mode.is_none()
+            && self.database.is_none()
+            && self.impersonated_user.is_none()
+    }
+
+    /// Convert the settings into metadata for the given protocol version. Fails if a setting
+    /// isn't supported by the version, rather than silently leaving it out.
+    #[allow(clippy::result_large_err)]
+    fn into_metadata(self, version: u32) -> CommunicationResult<ValueMap> {
+        let supported = match version {
+            V1_0 | V2_0 => self.is_empty(),
+            V3_0 => self.database.is_none() && self.impersonated_user.is_none(),
+            V4_0 | V4_1 | V4_2 | V4_3 => self.impersonated_user.is_none(),
+            _ => true,
+        };
+        if !supported {
+            return Err(CommunicationError::UnsupportedOperation(version));
+        }
+
+        let mut metadata = ValueMap::default();
+        if let Some(bookmarks) = self.bookmarks {
+            metadata.insert("bookmarks".to_string(), Value::from(bookmarks));
+        }
+        if let Some(timeout) = self.timeout {
+            let millis = i64::try_from(timeout.as_millis()).unwrap_or(i64::MAX);
+            metadata.insert("tx_timeout".to_string(), Value::from(millis));
+        }
+        if let Some(tx_metadata) = self.metadata {
+            metadata.insert("tx_metadata".to_string(), Value::from(tx_metadata));
+        }
+        // Write access is the server default, so only read access needs to be sent
+        if let Some(AccessMode::Read) = self.mode {
+            metadata.insert("mode".to_string(), Value::from("r"));
+        }
+        if let Some(database) = self.database {
+            metadata.insert("db".to_string(), Value::from(database));
+        }
+        if let Some(user) = self.impersonated_user {
+            metadata.insert("imp_user".to_string(), Value::from(user));
+        }
+        Ok(metadata)
+    }
+}
+
+// Setters for the transaction settings, shared by the query and transaction builders
+macro_rules! tx_config_methods {
+    () => {
+        /// Set the bookmarks the server must have seen before running the transaction.
+        /// _(Bolt v3+ only.)_
+        pub fn bookmarks<T: Into<String>>(
+            mut self,
+            bookmarks: impl IntoIterator<Item = T>,
+        ) -> Self {
+            self.config.bookmarks = Some(bookmarks.into_iter().map(Into::into).collect());
+            self
+        }
+
+        /// Set the transaction timeout, which is sent in whole milliseconds. If unset, the
+        /// server-side configured timeout is used. _(Bolt v3+ only.)_
+        pub fn timeout(mut self, timeout: Duration) -> Self {
+            self.config.timeout = Some(timeout);
+            self
+        }
+
+        /// Add an entry to the transaction metadata, which is mainly used for logging.
+        /// _(Bolt v3+ only.)_
+        pub fn tx_metadata(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+            self.config
+                .metadata
+                .get_or_insert_with(ValueMap::default)
+                .insert(key.into(), value.into());
+            self
+        }
+
+        /// Set the kind of server access the transaction needs. Default is
+        /// [`AccessMode::Write`]. _(Bolt v3+ only.)_
+        pub fn mode(mut self, mode: AccessMode) -> Self {
+            self.config.mode = Some(mode);
+            self
+        }
+
+        /// Set the database where the transaction should take place. If unset, the server-side
+        /// configured default database is used. _(Bolt v4+ only.)_
+        pub fn database(mut self, database: impl Into<String>) -> Self {
+            self.config.database = Some(database.into());
+            self
+        }
+
+        /// Set the user to impersonate while executing the transaction. _(Bolt v4.4+ only.)_
+        pub fn impersonate(mut self, user: impl Into<String>) -> Self {
+            self.config.impersonated_user = Some(user.into());
+            self
+        }
+    };
+}
+
+/// A builder for a query, created by [`Client::query`].
+///
+/// Transaction settings are only supported by some protocol versions (see each method). If a
+/// setting isn't supported by the client's version, sending the query fails with
+/// [`CommunicationError::UnsupportedOperation`] and nothing is sent.
+#[derive(Debug)]
+pub struct Query<'a, S: AsyncRead + AsyncWrite + Unpin> {
+    client: &'a mut Client<S>,
+    query: String,
+    params: ValueMap,
+    config: TxConfig,
+    fetch_size: i64,
+}
+
+/// A builder for an explicit transaction, created by [`Client::transaction`].
+#[derive(Debug)]
+pub struct Transaction<'a, S: AsyncRead + AsyncWrite + Unpin> {
+    client: &'a mut Client<S>,
+    config: TxConfig,
+}
+
+/// A builder for a [`ROUTE`](Message::RouteWithMetadata) request, created by
+/// [`Client::routing_table`].
+///
+/// Like with [`Query`], sending the request fails with
+/// [`CommunicationError::UnsupportedOperation`] if a setting isn't supported by the client's
+/// protocol version.
+#[derive(Debug)]
+pub struct RoutingTableRequest<'a, S: AsyncRead + AsyncWrite + Unpin> {
+    client: &'a mut Client<S>,
+    context: RoutingContext,
+    bookmarks: Vec<String>,
+    database: Option<String>,
+    impersonated_user: Option<String>,
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Start building a query, which can be sent with [`Query::run`] like [`Client::run`], or
+    /// sent together with a [`PULL`](Message::Pull) using [`Query::fetch`]. The builder takes care
+    /// of the metadata keys for the negotiated protocol version:
+    /// ```
+    /// # use std::{env, time::Duration};
+    /// #
+    /// # use bolt_client::*;
+    /// # use bolt_proto::{message::*, version::*, Value};
+    /// # use tokio::io::BufStream;
+    /// # use tokio_util::compat::*;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #     let stream = Stream::connect(env::var("BOLT_TEST_ADDR")?,
+    /// #                                  env::var("BOLT_TEST_DOMAIN").ok()).await?;
+    /// #     let stream = BufStream::new(stream).compat();
+    /// #     let result = Client::new(stream, &[V4_4, V4_3, 0, 0]).await;
+    /// #     skip_if_handshake_failed!(result, Ok(()));
+    /// #     let mut client = result.unwrap();
+    /// #     client.hello(Metadata::from_iter(vec![
+    /// #         ("user_agent", "my-client-name/1.0"),
+    /// #         ("scheme", "basic"),
+    /// #         ("principal", &env::var("BOLT_TEST_USERNAME")?),
+    /// #         ("credentials", &env::var("BOLT_TEST_PASSWORD")?),
+    /// #     ])).await?;
+    /// let (run, pull) = client
+    ///     .query("RETURN $x AS x;")
+    ///     .param("x", 1)
+    ///     .database("neo4j")
+    ///     .timeout(Duration::from_secs(10))
+    ///     .mode(AccessMode::Read)
+    ///     .fetch_size(1000)
+    ///     .fetch()
+    ///     .await?;
+    /// assert!(Success::try_from(run.into_response()).is_ok());
+    /// assert_eq!(pull.records()[0].fields(), &[Value::from(1)]);
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn query(&mut self, query: impl Into<String>) -> Query<'_, S> {
+        Query {
+            client: self,
+            query: query.into(),
+            params: ValueMap::default(),
+            config: TxConfig::default(),
+            fetch_size: -1,
+        }
+    }
+
+    /// Start building an explicit transaction, which is started with [`Transaction::begin`].
+    /// Queries run inside the transaction use its settings, so they shouldn't set their own.
+    pub fn transaction(&mut self) -> Transaction<'_, S> {
+        Transaction {
+            client: self,
+            config: TxConfig::default(),
+        }
+    }
+
+    /// Start building a request for the routing table, which is sent with
+    /// [`RoutingTableRequest::send`] like [`Client::route`]. The `context` should contain the
+    /// `address` the client initially connected to:
+    /// ```
+    /// # use std::env;
+    /// #
+    /// # use bolt_client::*;
+    /// # use bolt_proto::{message::*, version::*};
+    /// # use tokio::io::BufStream;
+    /// # use tokio_util::compat::*;
+    /// #
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// #     let stream = Stream::connect(env::var("BOLT_TEST_ADDR")?,
+    /// #                                  env::var("BOLT_TEST_DOMAIN").ok()).await?;
+    /// #     let stream = BufStream::new(stream).compat();
+    /// #     let result = Client::new(stream, &[V4_4, V4_3, 0, 0]).await;
+    /// #     skip_if_handshake_failed!(result, Ok(()));
+    /// #     let mut client = result.unwrap();
+    /// #     client.hello(Metadata::from_iter(vec![
+    /// #         ("user_agent", "my-client-name/1.0"),
+    /// #         ("scheme", "basic"),
+    /// #         ("principal", &env::var("BOLT_TEST_USERNAME")?),
+    /// #         ("credentials", &env::var("BOLT_TEST_PASSWORD")?),
+    /// #     ])).await?;
+    /// let response = client
+    ///     .routing_table(RoutingContext::from_iter(vec![("address", "localhost:7687")]))
+    ///     .database("neo4j")
+    ///     .send()
+    ///     .await?;
+    /// assert!(Success::try_from(response).is_ok());
+    /// #     Ok(())
+    /// # }
+    /// ```
+    pub fn routing_table(&mut self, context: RoutingContext) -> RoutingTableRequest<'_, S> {
+        RoutingTableRequest {
+            client: self,
+            context,
+            bookmarks: Vec::new(),
+            database: None,
+            impersonated_user: None,
+        }
+    }
+
+    /// Send a [`PULL`](Message::Pull) (or [`PULL_ALL`](Message::PullAll)) message for at most
+    /// `fetch_size` more records of the last query, or all of them if `fetch_size` is `-1`. See
+    /// [`Client::pull`]. _(Bolt v1 - v3 always fetch all records.)_
+    pub async fn fetch(&mut self, fetch_size: i64) -> CommunicationResult<(Vec<Record>, Message)> {
+        self.pull(Some(pull_metadata(fetch_size))).await
+    }
+}
+
+fn pull_metadata(fetch_size: i64) -> Metadata {
+    Metadata::from_iter(vec![("n", fetch_size)])
+}
+
+impl<'a, S: AsyncRead + AsyncWrite + Unpin> Query<'a, S> {
+    /// Add a query parameter.
+    pub fn param(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.params.insert(key.into(), value.into());
+        self
+    }
+
+    /// Add multiple query parameters.
+    pub fn params(mut self, params: Params) -> Self {
+        self.params.extend(params.value);
+        self
+    }
+
+    /// Set how many records [`Query::fetch`] pulls at first. `-1`, the default, fetches all
+    /// records. _(Bolt v4+ only. Earlier versions always fetch all records.)_
+    pub fn fetch_size(mut self, fetch_size: i64) -> Self {
+        self.fetch_size = fetch_size;
+        self
+    }
+
+    tx_config_methods!();
+
+    /// Send the query in a [`RUN`](Message::RunWithMetadata) message. See [`Client::run`].
+    pub async fn run(self) -> CommunicationResult<Message> {
+        let metadata = self.config.into_metadata(self.client.version())?;
+        self.client
+            .run(
+                self.query,
+                Some(Params { value: self.params }),
+                Some(Metadata { value: metadata }),
+            )
+            .await
+    }
+
+    /// Send the query in a [`RUN`](Message::RunWithMetadata) message, followed by a
+    /// [`PULL`](Message::Pull) for the first [`fetch_size`](Query::fetch_size) records, and wait
+    /// for both responses. If the summary says there are more records, use [`Client::fetch`] to
+    /// get them.
+    pub async fn fetch(self) -> CommunicationResult<(RunResult, PullResult)> {
+        let metadata = self.config.into_metadata(self.client.version())?;
+        let mut batch = self.client.batch();
+        batch.run(
+            self.query,
+            Some(Params { value: self.params }),
+            Some(Metadata { value: metadata }),
+        );
+        batch.pull(Some(pull_metadata(self.fetch_size)));
+        // Take the results by value instead of indexing with the handles
+        match <[BatchResponse; 2]>::try_from(batch.send().await?.into_vec()) {
+            Ok([BatchResponse::Run(run), BatchResponse::Pull(pull)]) => Ok((run, pull)),
+            _ => unreachable!("a batch returns one response per message"),
+        }
+    }
+}
+
+impl<'a, S: AsyncRead + AsyncWrite + Unpin> Transaction<'a, S> {
+    tx_config_methods!();
+
+    /// Start the transaction with a [`BEGIN`](Message::Begin) message. See [`Client::begin`].
+    pub async fn begin(self) -> CommunicationResult<Message> {
+        let metadata = self.config.into_metadata(self.client.version())?;
+        self.client.begin(Some(Metadata { value: metadata })).await
+    }
+}
+
+impl<'a, S: AsyncRead + AsyncWrite + Unpin> RoutingTableRequest<'a, S> {
+    /// Set the bookmarks the server must have seen before returning the routing table.
+    pub fn bookmarks<T: Into<String>>(mut self, bookmarks: impl IntoIterator<Item = T>) -> Self {
+        self.bookmarks = bookmarks.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the database to get the routing table for. If unset, the server-side configured
+    /// default database is used.
+    pub fn database(mut self, database: impl Into<String>) -> Self {
+        self.database = Some(database.into());
+        self
+    }
+
+    /// Set the user to impersonate, whose home database is used if no database is set.
+    /// _(Bolt v4.4+ only.)_
+    pub fn impersonate(mut self, user: impl Into<String>) -> Self {
+        self.impersonated_user = Some(user.into());
+        self
+    }
+
+    /// Send the [`ROUTE`](Message::RouteWithMetadata) message. See [`Client::route`].
+    pub async fn send(self) -> CommunicationResult<Message> {
+        let metadata =
+            route_metadata(self.database, self.impersonated_user, self.client.version())?;
+        self.client
+            .route(
+                self.context,
+                self.bookmarks,
+                Some(Metadata { value: metadata }),
+            )
+            .await
+    }
+}
+
+#[allow(clippy::result_large_err)]
+fn route_metadata(
+    database: Option<String>,
+    impersonated_user: Option<String>,
+    version: u32,
+) -> CommunicationResult<ValueMap> {
+    // Versions before v4.3 are rejected by Client::route itself
+    if impersonated_user.is_some() && version == V4_3 {
+        return Err(CommunicationError::UnsupportedOperation(version));
+    }
+    let mut metadata = ValueMap::default();
+    if let Some(database) = database {
+        metadata.insert("db".to_string(), Value::from(database));
+    }
+    if let Some(user) = impersonated_user {
+        metadata.insert("imp_user".to_string(), Value::from(user));
+    }
+    Ok(metadata)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_config_metadata() {
+        assert!(TxConfig::default().into_metadata(V1_0).unwrap().is_empty());
+
+        let config = TxConfig {
+            bookmarks: Some(vec!["bookmark:1".to_string()]),
+            timeout: Some(Duration::from_millis(1500)),
+            metadata: Some(ValueMap::from_iter(vec![(
+                "app".to_string(),
+                Value::from("test"),
+            )])),
+            mode: Some(AccessMode::Read),
+            database: Some("movies".to_string()),
+            impersonated_user: Some("alice".to_string()),
+        };
+        let metadata = config.clone().into_metadata(V4_4).unwrap();
+        assert_eq!(
+            metadata.get("bookmarks"),
+            Some(&Value::from(vec!["bookmark:1"]))
+        );
+        assert_eq!(metadata.get("tx_timeout"), Some(&Value::from(1500)));
+        assert_eq!(
+            metadata.get("tx_metadata"),
+            Some(&Value::from(ValueMap::from_iter(vec![(
+                "app".to_string(),
+                Value::from("test")
+            )])))
+        );
+        assert_eq!(metadata.get("mode"), Some(&Value::from("r")));
+        assert_eq!(metadata.get("db"), Some(&Value::from("movies")));
+        assert_eq!(metadata.get("imp_user"), Some(&Value::from("alice")));
+
+        let write = TxConfig {
+            mode: Some(AccessMode::Write),
+            ..Default::default()
+        };
+        assert!(write.into_metadata(V3_0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn route_metadata_for_version() {
+        let metadata = route_metadata(Some("movies".to_string()), None, V4_3).unwrap();
+        assert_eq!(
+            metadata,
+            ValueMap::from_iter(vec![("db".to_string(), Value::from("movies"))])
+        );
+
+        let metadata = route_metadata(None, Some("alice".to_string()), V4_4).unwrap();
+        assert_eq!(
+            metadata,
+            ValueMap::from_iter(vec![("imp_user".to_string(), Value::from("alice"))])
+        );
+        assert!(matches!(
+            route_metadata(None, Some("alice".to_string()), V4_3),
+            Err(CommunicationError::UnsupportedOperation(V4_3))
+        ));
+    }
+
+    #[test]
+    fn tx_config_unsupported() {
+        let bookmarks = TxConfig {
+            bookmarks: Some(vec![]),
+            ..Default::default()
+        };
+        assert!(matches!(
+            bookmarks.clone().into_metadata(V2_0),
+            Err(CommunicationError::UnsupportedOperation(V2_0))
+        ));
+        assert!(bookmarks.into_metadata(V3_0).is_ok());
+
+        let database = TxConfig {
+            database: Some("movies".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            database.clone().into_metadata(V3_0),
+            Err(CommunicationError::UnsupportedOperation(V3_0))
+        ));
+        assert!(database.into_metadata(V4_0).is_ok());
+
+        let user = TxConfig {
+            impersonated_user: Some("alice".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            user.clone().into_metadata(V4_3),
+            Err(CommunicationError::UnsupportedOperation(V4_3))
+        ));
+        assert!(user.into_metadata(V4_4).is_ok());
+    }
+}
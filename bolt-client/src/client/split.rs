@@ -0,0 +1,258 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use futures_util::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+
+use bolt_proto::{
+    error::Error as ProtocolError, DeserializeOptions, Message, SerializeOptions, ServerState,
+    ServerState::*,
+};
+
+use crate::{
+    client::{State, Timeouts},
+    error::{CommunicationResult, ReuniteError},
+    AuthToken, Client,
+};
+
+/// The half of a [split](Client::split) client that receives responses from the server.
+#[derive(Debug)]
+pub struct ClientReader<S> {
+    stream: ReadHalf<S>,
+    version: u32,
+    state: Arc<Mutex<State>>,
+    deserialize_options: DeserializeOptions,
+    timeouts: Timeouts,
+    created_at: Instant,
+    recv_timeout: Option<Duration>,
+    auth_token: Option<AuthToken>,
+}
+
+/// The half of a [split](Client::split) client that sends messages to the server.
+#[derive(Debug)]
+pub struct ClientWriter<S> {
+    stream: WriteHalf<S>,
+    state: Arc<Mutex<State>>,
+    serialize_options: SerializeOptions,
+}
+
+// The lock is never held across an await point, and the state is consistent between statements,
+// so a panic in another thread can't leave it in a bad state
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> Client<S> {
+    /// Split this client into a [`ClientReader`] that receives responses from the server, and a
+    /// [`ClientWriter`] that sends messages to the server. The halves can be moved to separate
+    /// tasks, so that one task can keep sending messages while another consumes the responses.
+    ///
+    /// Both halves share the [server state](ServerState) and the queue of messages that are
+    /// waiting for a response, so responses are matched up with the messages they respond to, in
+    /// the order the messages were sent. Like [`Client::pipeline`], the writer doesn't check
+    /// whether a message is valid in the current server state, since responses to earlier
+    /// messages may still be in flight. Use [`ClientReader::reunite`] to get the client back.
+    pub fn split(mut self) -> (ClientReader<S>, ClientWriter<S>) {
+        if self.io_in_progress {
+            self.state.server_state = Defunct;
+        }
+        let (read_half, write_half) = self.stream.split();
+        let state = Arc::new(Mutex::new(self.state));
+        let reader = ClientReader {
+            stream: read_half,
+            version: self.version,
+            state: Arc::clone(&state),
+            deserialize_options: self.deserialize_options,
+            timeouts: self.timeouts,
+            created_at: self.created_at,
+            recv_timeout: self.recv_timeout,
+            auth_token: self.auth_token,
+        };
+        let writer = ClientWriter {
+            stream: write_half,
+            state,
+            serialize_options: self.serialize_options,
+        };
+        (reader, writer)
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ClientReader<S> {
+    /// Get the current server state.
+    pub fn server_state(&self) -> ServerState {
+        lock(&self.state).server_state
+    }
+
+    /// Get the number of sent messages that haven't been fully responded to yet. Messages that
+    /// stream records, like [`PULL`](Message::Pull), count until their summary is received.
+    pub fn pending(&self) -> usize {
+        lock(&self.state).sent_queue.len()
+    }
+
+    /// Receive the next response from the server. This waits for a response even if no messages
+    /// are pending, since the writer may send one at any time. If this fails, or the returned
+    /// future is dropped after part of a response was read, the connection becomes
+    /// [`Defunct`](ServerState::Defunct).
+    pub async fn recv(&mut self) -> CommunicationResult<Message> {
+        let mut stream = DefunctIfPartial {
+            stream: &mut self.stream,
+            state: &self.state,
+            started: false,
+        };
+        let result = Message::from_stream_with(&mut stream, &self.deserialize_options).await;
+        stream.started = false;
+        let message = match result {
+            Ok(message) => message,
+            Err(error) => {
+                // The rest of the message can't be skipped, and the stream may be closed
+                lock(&self.state).server_state = Defunct;
+                return Err(ProtocolError::from(error).into());
+            }
+        };
+
+        #[cfg(test)]
+        println!("<<< {:?}\n", message);
+
+        lock(&self.state).handle_response(message)
+    }
+
+    /// Put a [split](Client::split) client back together. Fails if the halves came from different
+    /// clients.
+    // The halves are returned in the error, like the other reunite methods in the ecosystem
+    #[allow(clippy::result_large_err)]
+    pub fn reunite(self, writer: ClientWriter<S>) -> Result<Client<S>, ReuniteError<S>> {
+        if !Arc::ptr_eq(&self.state, &writer.state) {
+            return Err(ReuniteError(self, writer));
+        }
+
+        let stream = self
+            .stream
+            .reunite(writer.stream)
+            .expect("halves with the same state should have the same stream");
+        drop(writer.state);
+        let state = Arc::into_inner(self.state)
+            .expect("no other references to the state should exist")
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        Ok(Client {
+            stream,
+            version: self.version,
+            state,
+            serialize_options: writer.serialize_options,
+            deserialize_options: self.deserialize_options,
+            timeouts: self.timeouts,
+            io_in_progress: false,
+            created_at: self.created_at,
+            last_activity: Instant::now(),
+            recv_timeout: self.recv_timeout,
+            auth_token: self.auth_token,
+        })
+    }
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ClientWriter<S> {
+    /// Get the current server state.
+    pub fn server_state(&self) -> ServerState {
+        lock(&self.state).server_state
+    }
+
+    /// Send a message to the server without waiting for a response. The response can be received
+    /// from the corresponding [`ClientReader`].
+    pub async fn send(&mut self, message: Message) -> CommunicationResult<()> {
+        self.send_all(vec![message]).await
+    }
+
+    /// Send multiple messages to the server without waiting for a response, flushing the stream
+    /// once at the end. If this fails, or the returned future is dropped before it completes, the
+    /// messages may have been partially sent, so the connection becomes
+    /// [`Defunct`](ServerState::Defunct).
+    pub async fn send_all(&mut self, messages: Vec<Message>) -> CommunicationResult<()> {
+        // Serialize everything up front, so a serialization error can't leave a message half-sent
+        let mut chunks = Vec::new();
+        for message in &messages {
+            #[cfg(test)]
+            println!(">>> {:?}", message);
+
+            chunks.extend(
+                message
+                    .clone()
+                    .into_chunks_with(&self.serialize_options)
+                    .map_err(ProtocolError::from)?,
+            );
+        }
+
+        // Queue the messages before writing them, so the reader can't receive a response first.
+        // From here on the queue only matches what the server received if all writes complete.
+        let guard = DefunctUnlessDone {
+            state: &self.state,
+            done: false,
+        };
+        {
+            let mut state = lock(guard.state);
+            for message in messages {
+                state.handle_request(message);
+            }
+        }
+        for chunk in chunks {
+            self.stream.write_all(&chunk).await?;
+        }
+        self.stream.flush().await?;
+        guard.finish();
+        Ok(())
+    }
+}
+
+// Marks the connection as defunct when dropped, unless the operation it guards has finished
+struct DefunctUnlessDone<'a> {
+    state: &'a Mutex<State>,
+    done: bool,
+}
+
+impl DefunctUnlessDone<'_> {
+    fn finish(mut self) {
+        self.done = true;
+    }
+}
+
+impl Drop for DefunctUnlessDone<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            lock(self.state).server_state = Defunct;
+        }
+    }
+}
+
+// Marks the connection as defunct when dropped, if part of a message has been read and the
+// reader hasn't cleared `started`
+struct DefunctIfPartial<'a, S> {
+    stream: &'a mut S,
+    state: &'a Mutex<State>,
+    started: bool,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for DefunctIfPartial<'_, S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut *this.stream).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            this.started |= n > 0;
+        }
+        poll
+    }
+}
+
+impl<S> Drop for DefunctIfPartial<'_, S> {
+    fn drop(&mut self) {
+        if self.started {
+            lock(self.state).server_state = Defunct;
+        }
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_timer::Delay;
+use futures_util::{
+    future::{self, Either},
+    io::{AsyncRead, AsyncWrite},
+    pin_mut,
+};
+
+/// Timeouts for the I/O performed by a [`Client`](crate::Client). By default, there are no
+/// timeouts.
+///
+/// An operation that times out fails with an [`io::Error`] of kind
+/// [`TimedOut`](io::ErrorKind::TimedOut). If a message was partially read or written when the
+/// timeout elapsed, the client can no longer tell where the next message starts, so it marks
+/// itself [`Defunct`](bolt_proto::ServerState::Defunct).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timeouts {
+    pub(crate) handshake: Option<Duration>,
+    pub(crate) read: Option<Duration>,
+    pub(crate) write: Option<Duration>,
+}
+
+impl Timeouts {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the time allowed for the version handshake performed by
+    /// [`Client::new_with_timeouts`](crate::Client::new_with_timeouts).
+    pub fn with_handshake(mut self, handshake: Option<Duration>) -> Self {
+        self.handshake = handshake;
+        self
+    }
+
+    pub fn handshake(&self) -> Option<Duration> {
+        self.handshake
+    }
+
+    /// Set the time allowed for reading each message from the server. When pulling records, this
+    /// applies to each record separately, rather than to the whole result.
+    pub fn with_read(mut self, read: Option<Duration>) -> Self {
+        self.read = read;
+        self
+    }
+
+    pub fn read(&self) -> Option<Duration> {
+        self.read
+    }
+
+    /// Set the time allowed for writing and flushing the messages sent by an operation. For
+    /// [`Client::pipeline`](crate::Client::pipeline) and [`Batch`](crate::Batch), this applies to
+    /// all of the messages together.
+    pub fn with_write(mut self, write: Option<Duration>) -> Self {
+        self.write = write;
+        self
+    }
+
+    pub fn write(&self) -> Option<Duration> {
+        self.write
+    }
+}
+
+/// Run a future to completion, or fail with [`io::ErrorKind::TimedOut`] if it takes longer than
+/// the given duration. The timer doesn't depend on an async runtime.
+pub(crate) async fn timeout<F: Future>(
+    duration: Option<Duration>,
+    future: F,
+) -> io::Result<F::Output> {
+    let duration = match duration {
+        Some(duration) => duration,
+        None => return Ok(future.await),
+    };
+
+    let delay = Delay::new(duration);
+    pin_mut!(future);
+    match future::select(future, delay).await {
+        Either::Left((output, _)) => Ok(output),
+        Either::Right(((), _)) => Err(io::Error::new(
+            io::ErrorKind::TimedOut,
+            format!("operation timed out after {:?}", duration),
+        )),
+    }
+}
+
+/// A stream wrapper that sets a flag once any bytes have been read or written, so a caller can
+/// tell whether an operation that was dropped or timed out had already started on a message.
+pub(crate) struct Tracked<'a, S> {
+    stream: &'a mut S,
+    started: &'a mut bool,
+}
+
+impl<'a, S> Tracked<'a, S> {
+    pub(crate) fn new(stream: &'a mut S, started: &'a mut bool) -> Self {
+        Self { stream, started }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Tracked<'_, S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut *this.stream).poll_read(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            *this.started |= n > 0;
+        }
+        poll
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Tracked<'_, S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let poll = Pin::new(&mut *this.stream).poll_write(cx, buf);
+        if let Poll::Ready(Ok(n)) = poll {
+            *this.started |= n > 0;
+        }
+        poll
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.get_mut().stream).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut *self.get_mut().stream).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use bolt_proto::{message::*, version::*, Message, ServerState::*};
+    use futures_util::{future::pending, FutureExt};
+    use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream};
+    use tokio_util::compat::*;
+
+    use super::*;
+    use crate::{error::CommunicationError, Client};
+
+    // Perform the handshake with an in-memory server, which is driven by the test
+    pub(crate) async fn connect(
+        timeouts: Timeouts,
+    ) -> (Client<Compat<DuplexStream>>, DuplexStream) {
+        let (client_stream, mut server) = duplex(64 * 1024);
+        let server_handshake = async {
+            server.read_exact(&mut [0; 20]).await.unwrap();
+            server.write_all(&V4_4.to_be_bytes()).await.unwrap();
+        };
+        let (client, ()) = tokio::join!(
+            Client::new_with_timeouts(client_stream.compat(), &[V4_4, 0, 0, 0], timeouts),
+            server_handshake
+        );
+        let mut client = client.unwrap();
+        // Skip the HELLO exchange
+        client.state.server_state = Ready;
+        (client, server)
+    }
+
+    pub(crate) async fn write_message(server: &mut DuplexStream, message: Message) {
+        for chunk in message.into_chunks().unwrap() {
+            server.write_all(&chunk).await.unwrap();
+        }
+    }
+
+    pub(crate) fn success() -> Message {
+        Message::Success(Success::new(Default::default()))
+    }
+
+    #[tokio::test]
+    async fn timeout_elapses() {
+        let result = timeout(Some(Duration::from_millis(10)), pending::<()>()).await;
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::TimedOut);
+
+        let result = timeout(Some(Duration::from_secs(10)), async { 1 }).await;
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(timeout(None, async { 2 }).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn dropped_between_messages() {
+        let (mut client, mut server) = connect(Timeouts::default()).await;
+
+        // The RUN message is sent, but the operation is dropped while waiting for the response
+        assert!(client.run("RETURN 1;", None, None).now_or_never().is_none());
+        assert_eq!(client.server_state(), Ready);
+
+        write_message(&mut server, success()).await;
+        write_message(&mut server, success()).await;
+        let response = client.reset().await.unwrap();
+        assert_eq!(response, success());
+        assert_eq!(client.server_state(), Ready);
+        assert!(client.state.sent_queue.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejected_until_reset() {
+        let (mut client, _server) = connect(Timeouts::default()).await;
+
+        assert!(client.run("RETURN 1;", None, None).now_or_never().is_none());
+        let result = client.run("RETURN 2;", None, None).await;
+        assert!(matches!(
+            result,
+            Err(CommunicationError::InvalidState { state: Ready, .. })
+        ));
+        assert_eq!(client.server_state(), Defunct);
+    }
+
+    #[tokio::test]
+    async fn read_timeout() {
+        let timeouts = Timeouts::new().with_read(Some(Duration::from_millis(50)));
+        let (mut client, mut server) = connect(timeouts).await;
+
+        // No part of the response has arrived, so the client can still be reset
+        let result = client.run("RETURN 1;", None, None).await;
+        match result {
+            Err(CommunicationError::IoError(error)) => {
+                assert_eq!(error.kind(), io::ErrorKind::TimedOut)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(client.server_state(), Ready);
+        write_message(&mut server, success()).await;
+        write_message(&mut server, success()).await;
+        assert_eq!(client.reset().await.unwrap(), success());
+
+        // Only part of the response arrives
+        let mut partial = success().into_chunks().unwrap().concat();
+        partial.truncate(3);
+        server.write_all(&partial).await.unwrap();
+        assert!(client.run("RETURN 1;", None, None).await.is_err());
+        assert_eq!(client.server_state(), Defunct);
+        assert!(client.reset().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn split_send_dropped_partway() {
+        let (client, _server) = connect(Timeouts::default()).await;
+        let (reader, mut writer) = client.split();
+
+        // Larger than the in-memory stream's buffer, so the write can't complete
+        let query = "x".repeat(128 * 1024);
+        let message = Message::RunWithMetadata(RunWithMetadata::new(
+            query,
+            Default::default(),
+            Default::default(),
+        ));
+        assert!(writer.send(message).now_or_never().is_none());
+        assert_eq!(writer.server_state(), Defunct);
+        assert_eq!(reader.server_state(), Defunct);
+    }
+
+    #[tokio::test]
+    async fn split_recv_dropped_partway() {
+        let (client, mut server) = connect(Timeouts::default()).await;
+        let (mut reader, writer) = client.split();
+
+        // Nothing has arrived yet, so the reader can stop waiting
+        assert!(reader.recv().now_or_never().is_none());
+        assert_eq!(reader.server_state(), Ready);
+
+        // Only part of the response arrives
+        let mut partial = success().into_chunks().unwrap().concat();
+        partial.truncate(3);
+        server.write_all(&partial).await.unwrap();
+        assert!(reader.recv().now_or_never().is_none());
+        assert_eq!(writer.server_state(), Defunct);
+        assert_eq!(reader.reunite(writer).unwrap().server_state(), Defunct);
+    }
+
+    #[tokio::test]
+    async fn split_recv_invalid_message() {
+        let (client, mut server) = connect(Timeouts::default()).await;
+        let (mut reader, writer) = client.split();
+
+        // A chunk holding an invalid marker byte
+        server.write_all(&[0, 1, 0xC7, 0, 0]).await.unwrap();
+        assert!(reader.recv().await.is_err());
+        assert_eq!(writer.server_state(), Defunct);
+    }
+}
@@ -199,12 +199,15 @@ mod tests {
         assert_eq!(
             c.properties().get("starting"),
             Some(&Value::from(
-                FixedOffset::east_opt(-8 * 3600).unwrap().from_utc_datetime(
-                    &NaiveDate::from_ymd_opt(2019, 12, 19)
-                        .unwrap()
-                        .and_hms_milli_opt(16, 8, 4, 322)
-                        .unwrap()
-                )
+                FixedOffset::east_opt(-8 * 3600)
+                    .unwrap()
+                    .from_local_datetime(
+                        &NaiveDate::from_ymd_opt(2019, 12, 19)
+                            .unwrap()
+                            .and_hms_milli_opt(16, 8, 4, 322)
+                            .unwrap()
+                    )
+                    .unwrap()
             ))
         );
         assert_eq!(l.labels(), &[String::from("Language")]);
@@ -60,19 +60,19 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.1-pipelined'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.1-pipelined'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (neo4j:Database {name: 'neo4j', test: 'v4.1-pipelined'}) CREATE (:Library {name: 'bolt-client', v1_release: date('2019-12-23'), test: 'v4.1-pipelined'})-[:CLIENT_FOR]->(neo4j);".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (neo4j:Database {name: 'neo4j', test: 'v4.1-pipelined'}), (bolt_client:Library {name: 'bolt-client', test: 'v4.1-pipelined'}) RETURN duration.between(neo4j.v1_release, bolt_client.v1_release);".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         for response in client.pipeline(messages).await.unwrap() {
             assert!(match response {
@@ -220,11 +220,11 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.1-commit'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.1-commit'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         client.pipeline(messages).await.unwrap();
         assert_eq!(client.server_state(), TxReady);
@@ -238,7 +238,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
             )),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 "n".to_string(),
                 Value::from(1),
             )]))),
@@ -291,11 +291,11 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.1-rollback'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.1-rollback'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         client.pipeline(messages).await.unwrap();
         assert_eq!(client.server_state(), TxReady);
@@ -309,7 +309,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
             )),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 "n".to_string(),
                 Value::from(1),
             )]))),
@@ -376,7 +376,7 @@ mod tests {
             }
         }
 
-        assert_eq!(client.open_tx_streams, NUM_STREAMS);
+        assert_eq!(client.state.open_tx_streams, NUM_STREAMS);
 
         for (n, qid) in qids {
             assert_eq!(client.server_state(), TxStreaming);
@@ -392,7 +392,7 @@ mod tests {
         }
 
         assert_eq!(client.server_state(), TxReady);
-        assert_eq!(client.open_tx_streams, 0);
+        assert_eq!(client.state.open_tx_streams, 0);
     }
 
     #[tokio::test]
@@ -408,7 +408,7 @@ mod tests {
                     Default::default(),
                     Default::default(),
                 )),
-                Message::Pull(Pull::new(HashMap::from_iter(vec![(
+                Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                     String::from("n"),
                     Value::from(1),
                 )]))),
@@ -417,7 +417,7 @@ mod tests {
                     Default::default(),
                     Default::default(),
                 )),
-                Message::Pull(Pull::new(HashMap::from_iter(vec![(
+                Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                     String::from("n"),
                     Value::from(1),
                 )]))),
@@ -447,7 +447,7 @@ mod tests {
 
         client.run("RETURN 1;", None, None).await.unwrap();
         client
-            .send_message(Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            .send_message(Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 String::from("n"),
                 Value::from(1),
             )]))))
@@ -61,19 +61,19 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.3-pipelined'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.3-pipelined'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (neo4j:Database {name: 'neo4j', test: 'v4.3-pipelined'}) CREATE (:Library {name: 'bolt-client', v1_release: date('2019-12-23'), test: 'v4.3-pipelined'})-[:CLIENT_FOR]->(neo4j);".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (neo4j:Database {name: 'neo4j', test: 'v4.3-pipelined'}), (bolt_client:Library {name: 'bolt-client', test: 'v4.3-pipelined'}) RETURN duration.between(neo4j.v1_release, bolt_client.v1_release);".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         for response in client.pipeline(messages).await.unwrap() {
             assert!(match response {
@@ -221,11 +221,11 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.3-commit'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.3-commit'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         client.pipeline(messages).await.unwrap();
         assert_eq!(client.server_state(), TxReady);
@@ -239,7 +239,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
             )),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 "n".to_string(),
                 Value::from(1),
             )]))),
@@ -292,11 +292,11 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.3-rollback'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.3-rollback'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         client.pipeline(messages).await.unwrap();
         assert_eq!(client.server_state(), TxReady);
@@ -310,7 +310,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
             )),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 "n".to_string(),
                 Value::from(1),
             )]))),
@@ -377,7 +377,7 @@ mod tests {
             }
         }
 
-        assert_eq!(client.open_tx_streams, NUM_STREAMS);
+        assert_eq!(client.state.open_tx_streams, NUM_STREAMS);
 
         for (n, qid) in qids {
             assert_eq!(client.server_state(), TxStreaming);
@@ -393,7 +393,7 @@ mod tests {
         }
 
         assert_eq!(client.server_state(), TxReady);
-        assert_eq!(client.open_tx_streams, 0);
+        assert_eq!(client.state.open_tx_streams, 0);
     }
 
     #[tokio::test]
@@ -409,7 +409,7 @@ mod tests {
                     Default::default(),
                     Default::default(),
                 )),
-                Message::Pull(Pull::new(HashMap::from_iter(vec![(
+                Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                     String::from("n"),
                     Value::from(1),
                 )]))),
@@ -418,7 +418,7 @@ mod tests {
                     Default::default(),
                     Default::default(),
                 )),
-                Message::Pull(Pull::new(HashMap::from_iter(vec![(
+                Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                     String::from("n"),
                     Value::from(1),
                 )]))),
@@ -448,7 +448,7 @@ mod tests {
 
         client.run("RETURN 1;", None, None).await.unwrap();
         client
-            .send_message(Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            .send_message(Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 String::from("n"),
                 Value::from(1),
             )]))))
@@ -3,10 +3,11 @@ mod tests {
     use std::collections::HashMap;
 
     use bolt_proto::{message::*, value::*, version::*, Message, ServerState::*};
+    use chrono::{NaiveDate, TimeZone};
 
     use crate::{
-        client::v1::tests::*, error::CommunicationError, skip_if_handshake_failed, Metadata,
-        RoutingContext,
+        client::v1::tests::*, error::CommunicationError, skip_if_handshake_failed, AccessMode,
+        Metadata, Params, RoutingContext,
     };
 
     #[tokio::test]
@@ -31,6 +32,41 @@ mod tests {
         assert_eq!(client.server_state(), Defunct);
     }
 
+    #[tokio::test]
+    async fn utc_patch() {
+        let client = get_initialized_client(V4_4).await;
+        skip_if_handshake_failed!(client);
+        let mut client = client.unwrap();
+        assert!(client.serialize_options().utc_date_times());
+
+        // 02:30 happens twice in Europe/Berlin on this day, so only the UTC encoding can tell
+        // these apart
+        let date_time = chrono_tz::Europe::Berlin.from_utc_datetime(
+            &NaiveDate::from_ymd_opt(2021, 10, 31)
+                .unwrap()
+                .and_hms_opt(1, 30, 0)
+                .unwrap(),
+        );
+        let response = client
+            .run(
+                "RETURN $dt, $dt.offset;",
+                Some(Params::from_iter(vec![(
+                    "dt",
+                    Value::DateTimeZoned(date_time),
+                )])),
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(Success::try_from(response).is_ok());
+        let (records, response) = client.pull(None).await.unwrap();
+        assert!(Success::try_from(response).is_ok());
+        assert_eq!(
+            records[0].fields(),
+            &[Value::DateTimeZoned(date_time), Value::from("+01:00")]
+        );
+    }
+
     #[tokio::test]
     async fn goodbye() {
         let client = get_initialized_client(V4_4).await;
@@ -61,19 +97,19 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.4-pipelined'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.4-pipelined'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (neo4j:Database {name: 'neo4j', test: 'v4.4-pipelined'}) CREATE (:Library {name: 'bolt-client', v1_release: date('2019-12-23'), test: 'v4.4-pipelined'})-[:CLIENT_FOR]->(neo4j);".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (neo4j:Database {name: 'neo4j', test: 'v4.4-pipelined'}), (bolt_client:Library {name: 'bolt-client', test: 'v4.4-pipelined'}) RETURN duration.between(neo4j.v1_release, bolt_client.v1_release);".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         for response in client.pipeline(messages).await.unwrap() {
             assert!(match response {
@@ -96,6 +132,127 @@ mod tests {
     //     to discard ALL records, but it was requested to discard " + n
     //   - Sending DISCARD with 'n' equal to -1 indicates discard of all records in the
     //     result stream.
+    #[tokio::test]
+    async fn split() {
+        let client = get_initialized_client(V4_4).await;
+        skip_if_handshake_failed!(client);
+        let (mut reader, mut writer) = client.unwrap().split();
+
+        let writer = tokio::spawn(async move {
+            for _ in 0..3 {
+                let messages = vec![
+                    Message::RunWithMetadata(RunWithMetadata::new(
+                        "UNWIND range(1, 100) AS n RETURN n;".to_string(),
+                        Default::default(),
+                        Default::default(),
+                    )),
+                    Message::Pull(Pull::new(ValueMap::from_iter(vec![(
+                        "n".to_string(),
+                        Value::from(-1),
+                    )]))),
+                ];
+                writer.send_all(messages).await.unwrap();
+            }
+            writer
+        });
+
+        let (mut records, mut summaries) = (0, 0);
+        while summaries < 6 {
+            match reader.recv().await.unwrap() {
+                Message::Record(_) => records += 1,
+                Message::Success(_) => summaries += 1,
+                other => panic!("unexpected response: {:?}", other),
+            }
+        }
+        assert_eq!(records, 300);
+        assert_eq!(reader.pending(), 0);
+
+        let client = reader.reunite(writer.await.unwrap()).unwrap();
+        assert_eq!(client.server_state(), Ready);
+    }
+
+    #[tokio::test]
+    async fn batch() {
+        let client = get_initialized_client(V4_4).await;
+        skip_if_handshake_failed!(client);
+        let mut client = client.unwrap();
+        let pull_all = || Some(Metadata::from_iter(vec![("n", -1)]));
+
+        let mut batch = client.batch();
+        let begin = batch.begin(None);
+        let first_run = batch.run("UNWIND range(1, 10) AS n RETURN n;", None, None);
+        let first_pull = batch.pull(pull_all());
+        let second_run = batch.run("RETURN 1 AS num;", None, None);
+        let second_pull = batch.pull(pull_all());
+        let commit = batch.commit();
+        let responses = batch.send().await.unwrap();
+        assert_eq!(responses.len(), 6);
+        assert!(Success::try_from(responses[begin].clone()).is_ok());
+        assert!(Success::try_from(responses[first_run].response().clone()).is_ok());
+        assert_eq!(responses[first_pull].records().len(), 10);
+        assert!(Success::try_from(responses[first_pull].response().clone()).is_ok());
+        assert!(Success::try_from(responses[second_run].response().clone()).is_ok());
+        assert_eq!(
+            responses[second_pull].records()[0].fields(),
+            &[Value::from(1)]
+        );
+        assert!(Success::try_from(responses[commit].clone()).is_ok());
+        assert_eq!(client.server_state(), Ready);
+
+        // A PULL isn't valid before a RUN, so nothing is sent
+        let mut batch = client.batch();
+        batch.pull(pull_all());
+        let result = batch.send().await;
+        assert!(matches!(
+            result,
+            Err(CommunicationError::InvalidState { state: Ready, .. })
+        ));
+        assert_eq!(client.server_state(), Ready);
+    }
+
+    #[tokio::test]
+    async fn query_builder() {
+        let client = get_initialized_client(V4_4).await;
+        skip_if_handshake_failed!(client);
+        let mut client = client.unwrap();
+
+        let (run, pull) = client
+            .query("UNWIND range(1, $count) AS n RETURN n;")
+            .param("count", 5)
+            .database("neo4j")
+            .mode(AccessMode::Read)
+            .tx_metadata("test", "query_builder")
+            .fetch_size(2)
+            .fetch()
+            .await
+            .unwrap();
+        assert!(Success::try_from(run.into_response()).is_ok());
+        assert_eq!(pull.records().len(), 2);
+        let success = Success::try_from(pull.response().clone()).unwrap();
+        assert_eq!(success.metadata().get("has_more"), Some(&Value::from(true)));
+        assert_eq!(client.server_state(), Streaming);
+
+        let (records, response) = client.fetch(-1).await.unwrap();
+        assert!(Success::try_from(response).is_ok());
+        assert_eq!(records.len(), 3);
+        assert_eq!(client.server_state(), Ready);
+
+        let response = client
+            .transaction()
+            .database("neo4j")
+            .timeout(std::time::Duration::from_secs(10))
+            .begin()
+            .await
+            .unwrap();
+        assert!(Success::try_from(response).is_ok());
+        assert_eq!(client.server_state(), TxReady);
+        let (_, pull) = client.query("RETURN 1 AS num;").fetch().await.unwrap();
+        assert_eq!(pull.records()[0].fields(), &[Value::from(1)]);
+        let response = client.commit().await.unwrap();
+        assert!(Success::try_from(response).is_ok());
+        assert_eq!(client.server_state(), Ready);
+    }
+
     #[tokio::test]
     async fn discard() {
         let client = get_initialized_client(V4_4).await;
@@ -221,11 +378,11 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.4-commit'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.4-commit'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         client.pipeline(messages).await.unwrap();
         assert_eq!(client.server_state(), TxReady);
@@ -239,7 +396,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
             )),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 "n".to_string(),
                 Value::from(1),
             )]))),
@@ -292,11 +449,11 @@ mod tests {
             Message::RunWithMetadata(RunWithMetadata::new(
                 "MATCH (n {test: 'v4.4-rollback'}) DETACH DELETE n;".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
             Message::RunWithMetadata(RunWithMetadata::new(
                 "CREATE (:Database {name: 'neo4j', v1_release: date('2010-02-16'), test: 'v4.4-rollback'});".to_string(),
                 Default::default(), Default::default())),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![("n".to_string(), Value::from(1))]))),
         ];
         client.pipeline(messages).await.unwrap();
         assert_eq!(client.server_state(), TxReady);
@@ -310,7 +467,7 @@ mod tests {
                 Default::default(),
                 Default::default(),
             )),
-            Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 "n".to_string(),
                 Value::from(1),
             )]))),
@@ -377,7 +534,7 @@ mod tests {
             }
         }
 
-        assert_eq!(client.open_tx_streams, NUM_STREAMS);
+        assert_eq!(client.state.open_tx_streams, NUM_STREAMS);
 
         for (n, qid) in qids {
             assert_eq!(client.server_state(), TxStreaming);
@@ -393,7 +550,7 @@ mod tests {
         }
 
         assert_eq!(client.server_state(), TxReady);
-        assert_eq!(client.open_tx_streams, 0);
+        assert_eq!(client.state.open_tx_streams, 0);
     }
 
     #[tokio::test]
@@ -409,7 +566,7 @@ mod tests {
                     Default::default(),
                     Default::default(),
                 )),
-                Message::Pull(Pull::new(HashMap::from_iter(vec![(
+                Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                     String::from("n"),
                     Value::from(1),
                 )]))),
@@ -418,7 +575,7 @@ mod tests {
                     Default::default(),
                     Default::default(),
                 )),
-                Message::Pull(Pull::new(HashMap::from_iter(vec![(
+                Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                     String::from("n"),
                     Value::from(1),
                 )]))),
@@ -448,7 +605,7 @@ mod tests {
 
         client.run("RETURN 1;", None, None).await.unwrap();
         client
-            .send_message(Message::Pull(Pull::new(HashMap::from_iter(vec![(
+            .send_message(Message::Pull(Pull::new(ValueMap::from_iter(vec![(
                 String::from("n"),
                 Value::from(1),
             )]))))
@@ -515,4 +672,23 @@ mod tests {
         assert!(Failure::try_from(response).is_ok());
         assert_eq!(client.server_state(), Failed);
     }
+
+    #[tokio::test]
+    async fn routing_table_builder() {
+        let client = get_initialized_client(V4_4).await;
+        skip_if_handshake_failed!(client);
+        let mut client = client.unwrap();
+        let response = client
+            .routing_table(RoutingContext::from_iter(vec![(
+                "address",
+                "localhost:7687",
+            )]))
+            .database("neo4j")
+            .impersonate(std::env::var("BOLT_TEST_USERNAME").unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert!(Success::try_from(response).is_ok());
+        assert_eq!(client.server_state(), Ready);
+    }
 }
@@ -6,7 +6,7 @@ macro_rules! define_value_map {
         /// supplementary information to [`Client`] methods.
         #[derive(Debug, Default, Clone)]
         pub struct $T {
-            pub(crate) value: ::std::collections::HashMap<std::string::String, ::bolt_proto::Value>,
+            pub(crate) value: ::bolt_proto::value::ValueMap,
         }
 
         impl<K, V, S> ::std::convert::From<::std::collections::HashMap<K, V, S>> for $T
@@ -29,7 +29,7 @@ macro_rules! define_value_map {
         {
             fn from_iter<T: ::std::iter::IntoIterator<Item = (K, V)>>(iter: T) -> Self {
                 Self {
-                    value: ::std::collections::HashMap::from_iter(
+                    value: ::bolt_proto::value::ValueMap::from_iter(
                         iter.into_iter().map(|(k, v)| (k.into(), v.into())),
                     ),
                 }
@@ -1,6 +1,10 @@
+use std::fmt;
+
 use bolt_proto::{error::Error as ProtocolError, Message, ServerState};
 use thiserror::Error;
 
+use crate::{ClientReader, ClientWriter};
+
 pub type Result<T> = std::result::Result<T, Error>;
 pub type ConnectionResult<T> = std::result::Result<T, ConnectionError>;
 pub type CommunicationResult<T> = std::result::Result<T, CommunicationError>;
@@ -29,6 +33,8 @@ pub enum ConnectionError {
     IoError(#[from] std::io::Error),
 }
 
+// Messages are larger with the indexmap feature, but boxing them would change the public API
+#[cfg_attr(feature = "indexmap", allow(clippy::large_enum_variant))]
 #[derive(Debug, Error)]
 pub enum CommunicationError {
     #[error(
@@ -54,6 +60,18 @@ response: {response:?}"
     IoError(#[from] std::io::Error),
 }
 
+/// Error returned by [`ClientReader::reunite`] when the halves came from different clients. The
+/// halves are returned unchanged.
+#[derive(Error)]
+#[error("tried to reunite halves of different clients")]
+pub struct ReuniteError<S>(pub ClientReader<S>, pub ClientWriter<S>);
+
+impl<S> fmt::Debug for ReuniteError<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ReuniteError").finish()
+    }
+}
+
 fn format_version(version: u32) -> String {
     let (major, minor, range) = (version & 0xff, version >> 8 & 0xff, version >> 16 & 0xff);
     if range > 0 {
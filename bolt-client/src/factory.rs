@@ -0,0 +1,590 @@
+use std::{
+    fmt, io,
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use futures_timer::Delay;
+use tokio::{io::BufStream, net::lookup_host};
+use tokio_util::compat::{Compat, TokioAsyncReadCompatExt};
+
+use bolt_proto::{error::Error as ProtocolError, message::Success, version::*, Message, Value};
+
+use crate::{
+    error::{CommunicationError, ConnectionError, Result},
+    AuthProvider, AuthToken, Client, Metadata, RoutingContext, Stream, Timeouts,
+};
+
+/// The type of client created by a [`ConnectionFactory`].
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+pub type Connection = Client<Compat<BufStream<Stream>>>;
+
+const DEFAULT_PORT: u16 = 7687;
+const DEFAULT_USER_AGENT: &str = concat!("bolt-client/", env!("CARGO_PKG_VERSION"));
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const TOKEN_EXPIRED: &str = "Neo.ClientError.Security.TokenExpired";
+
+#[derive(Debug, Clone)]
+enum Address {
+    // Resolved again for every connection, so DNS changes are picked up
+    Host(String),
+    Resolved(Vec<SocketAddr>),
+}
+
+// Trait objects can't derive Debug
+#[derive(Clone)]
+struct SharedAuthProvider {
+    provider: Arc<dyn AuthProvider>,
+    // The token most recently returned by the provider, which connections are checked against
+    latest: Arc<Mutex<Option<AuthToken>>>,
+}
+
+impl SharedAuthProvider {
+    // The token is replaced in a single assignment, so a panic in another thread can't leave it
+    // in a bad state
+    fn latest(&self) -> MutexGuard<'_, Option<AuthToken>> {
+        self.latest.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for SharedAuthProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AuthProvider")
+    }
+}
+
+/// Opens and checks initialized [`Client`] connections. This holds everything a connection pool
+/// needs to manage Bolt connections, and is shared by the
+/// [bb8-bolt](https://crates.io/crates/bb8-bolt),
+/// [deadpool-bolt](https://crates.io/crates/deadpool-bolt), and
+/// [mobc-bolt](https://crates.io/crates/mobc-bolt) managers.
+///
+/// Connections are initialized with a [`HELLO`](Message::Hello) message built from the
+/// factory's settings:
+/// ```
+/// # use std::{env, time::Duration};
+/// # use bolt_client::*;
+/// # use bolt_proto::{message::*, Value};
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// #     let addr = env::var("BOLT_TEST_ADDR")?;
+/// let factory = ConnectionFactory::new(addr)
+///     .with_basic_auth(env::var("BOLT_TEST_USERNAME")?, env::var("BOLT_TEST_PASSWORD")?)
+///     .with_user_agent("my-client-name/1.0")
+///     .with_connect_timeout(Some(Duration::from_secs(5)))
+///     .with_max_lifetime(Some(Duration::from_secs(3600)));
+/// # let result = factory.connect().await;
+/// # skip_if_handshake_failed!(result, Ok(()));
+/// let mut client = result?;
+/// let (_, pull) = client.query("RETURN 1 AS num;").fetch().await?;
+/// assert_eq!(pull.records()[0].fields(), &[Value::from(1)]);
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+#[derive(Debug, Clone)]
+pub struct ConnectionFactory {
+    address: Address,
+    tls_domain: Option<String>,
+    version_specifiers: [u32; 4],
+    metadata: Metadata,
+    auth_provider: Option<SharedAuthProvider>,
+    user_agent: Option<String>,
+    routing_context: Option<RoutingContext>,
+    connect_timeout: Option<Duration>,
+    timeouts: Timeouts,
+    connect_retries: usize,
+    retry_backoff: Duration,
+    max_lifetime: Option<Duration>,
+    max_idle: Duration,
+}
+
+impl ConnectionFactory {
+    /// Create a factory that connects to the given address, such as `"localhost:7687"`. The
+    /// address is resolved again for each connection, and every resolved address is tried in
+    /// turn until one accepts the connection.
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self::with_address(Address::Host(addr.into()))
+    }
+
+    /// Create a factory that connects to one of the given, already resolved addresses, trying
+    /// each in turn until one accepts the connection.
+    pub fn from_socket_addrs(addrs: impl IntoIterator<Item = SocketAddr>) -> Self {
+        Self::with_address(Address::Resolved(addrs.into_iter().collect()))
+    }
+
+    /// Create a factory from a connection URI like the ones used by Neo4j drivers, such as
+    /// `bolt://localhost:7687` or `neo4j+s://example.com?region=eu`.
+    ///
+    /// - The `bolt` and `neo4j` schemes connect without TLS, and the `bolt+s` and `neo4j+s`
+    ///   schemes connect with TLS, using the host as the domain.
+    /// - The port defaults to 7687.
+    /// - For the `neo4j` schemes, the query parameters are sent to the server as the
+    ///   [routing context](ConnectionFactory::with_routing_context).
+    ///
+    /// Self-signed certificates (the `+ssc` schemes) aren't supported.
+    pub fn from_uri(uri: &str) -> io::Result<Self> {
+        let invalid = |reason: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid URI {:?}: {}", uri, reason),
+            )
+        };
+
+        let (scheme, rest) = uri
+            .split_once("://")
+            .ok_or_else(|| invalid("missing scheme"))?;
+        let (routing, tls) = match scheme {
+            "bolt" => (false, false),
+            "bolt+s" => (false, true),
+            "neo4j" => (true, false),
+            "neo4j+s" => (true, true),
+            "bolt+ssc" | "neo4j+ssc" => {
+                return Err(invalid("self-signed certificates aren't supported"))
+            }
+            _ => return Err(invalid("unknown scheme")),
+        };
+
+        let (authority, query) = match rest.split_once('?') {
+            Some((authority, query)) => (authority, Some(query)),
+            None => (rest, None),
+        };
+        let authority = authority.strip_suffix('/').unwrap_or(authority);
+        if authority.contains('/') {
+            return Err(invalid("paths aren't supported"));
+        }
+
+        // IPv6 addresses are enclosed in brackets, since they contain colons
+        let (host, port) = match authority.strip_prefix('[') {
+            Some(rest) => {
+                let (host, port) = rest
+                    .split_once(']')
+                    .ok_or_else(|| invalid("unclosed '['"))?;
+                (host, port.strip_prefix(':'))
+            }
+            None => match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            },
+        };
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("invalid port"))?,
+            None => DEFAULT_PORT,
+        };
+        let addr = if host.contains(':') {
+            format!("[{}]:{}", host, port)
+        } else {
+            format!("{}:{}", host, port)
+        };
+
+        let mut factory = Self::new(addr);
+        if tls {
+            factory = factory.with_tls_domain(Some(host.to_string()));
+        }
+        match (routing, query) {
+            (true, query) => {
+                let mut context = Vec::new();
+                for pair in query
+                    .unwrap_or_default()
+                    .split('&')
+                    .filter(|p| !p.is_empty())
+                {
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| invalid("query parameters must be key=value pairs"))?;
+                    context.push((key.to_string(), value.to_string()));
+                }
+                factory = factory.with_routing_context(Some(RoutingContext::from_iter(context)));
+            }
+            (false, Some(query)) if !query.is_empty() => {
+                return Err(invalid(
+                    "query parameters are only supported by the neo4j schemes",
+                ))
+            }
+            (false, _) => {}
+        }
+        Ok(factory)
+    }
+
+    fn with_address(address: Address) -> Self {
+        Self {
+            address,
+            tls_domain: None,
+            version_specifiers: [V4, V3_0, 0, 0],
+            metadata: Metadata::default(),
+            auth_provider: None,
+            user_agent: None,
+            routing_context: None,
+            connect_timeout: None,
+            timeouts: Timeouts::default(),
+            connect_retries: 0,
+            retry_backoff: Duration::from_millis(100),
+            max_lifetime: None,
+            max_idle: Duration::ZERO,
+        }
+    }
+
+    /// Create a copy of this factory that connects to another server, such as one listed in a
+    /// routing table. If TLS is enabled, the server's certificate is verified against the host of
+    /// the new address.
+    pub fn for_address(&self, addr: impl Into<String>) -> Self {
+        let addr = addr.into();
+        let mut factory = self.clone();
+        if factory.tls_domain.is_some() {
+            factory.tls_domain = Some(host_of(&addr).to_string());
+        }
+        factory.address = Address::Host(addr);
+        factory
+    }
+
+    /// Connect with TLS, verifying that the server's certificate is valid for the given domain.
+    pub fn with_tls_domain(mut self, domain: Option<String>) -> Self {
+        self.tls_domain = domain;
+        self
+    }
+
+    /// Set the protocol versions to request during the handshake. See [`Client::new`]. Default is
+    /// Bolt v4.0 - v4.4, then Bolt v3.
+    pub fn with_version_specifiers(mut self, version_specifiers: [u32; 4]) -> Self {
+        self.version_specifiers = version_specifiers;
+        self
+    }
+
+    /// Set the entries of the [`HELLO`](Message::Hello) metadata, such as authentication
+    /// details. Entries set by the other methods of this factory take precedence. See
+    /// [`Client::hello`].
+    pub fn with_metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Authenticate with the `basic` scheme, using a username and password.
+    pub fn with_basic_auth(
+        self,
+        principal: impl Into<String>,
+        credentials: impl Into<String>,
+    ) -> Self {
+        self.with_auth(AuthToken::Basic {
+            principal: principal.into(),
+            credentials: credentials.into(),
+            realm: None,
+        })
+    }
+
+    /// Authenticate with a fixed token.
+    pub fn with_auth(self, token: AuthToken) -> Self {
+        self.with_auth_provider(token)
+    }
+
+    /// Authenticate with the tokens supplied by the given provider. Connections that were
+    /// authenticated with an older token than the one the provider most recently returned are
+    /// replaced when they're [checked](ConnectionFactory::check). See [`AuthProvider`].
+    pub fn with_auth_provider(mut self, provider: impl AuthProvider + 'static) -> Self {
+        self.auth_provider = Some(SharedAuthProvider {
+            provider: Arc::new(provider),
+            latest: Arc::new(Mutex::new(None)),
+        });
+        self
+    }
+
+    /// Set the user agent sent to the server. Unless the [metadata](Self::with_metadata) contains
+    /// a user agent, the default is `bolt-client/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Set the routing context sent to the server, which enables server-side routing. The
+    /// `address` entry is filled in with the address the factory connects to.
+    /// _(Bolt v4.1+ only. Ignored for earlier versions.)_
+    pub fn with_routing_context(mut self, routing_context: Option<RoutingContext>) -> Self {
+        self.routing_context = routing_context;
+        self
+    }
+
+    /// Set the time allowed for establishing the connection to each address, including TLS
+    /// negotiation.
+    pub fn with_connect_timeout(mut self, connect_timeout: Option<Duration>) -> Self {
+        self.connect_timeout = connect_timeout;
+        self
+    }
+
+    /// Set the [timeouts](Timeouts) of the created clients.
+    pub fn with_timeouts(mut self, timeouts: Timeouts) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Retry connecting up to `retries` times if the server can't be reached, waiting
+    /// `initial_backoff` before the first retry and twice as long before each retry after that,
+    /// up to 30 seconds. Failed initialization, such as invalid credentials, isn't retried.
+    pub fn with_connect_retries(mut self, retries: usize, initial_backoff: Duration) -> Self {
+        self.connect_retries = retries;
+        self.retry_backoff = initial_backoff;
+        self
+    }
+
+    /// Set how long a connection can be used before it's discarded, no matter how healthy it
+    /// is. By default, connections can be used forever.
+    pub fn with_max_lifetime(mut self, max_lifetime: Option<Duration>) -> Self {
+        self.max_lifetime = max_lifetime;
+        self
+    }
+
+    /// Only check connections with a [`RESET`](Message::Reset) message if they've been idle for
+    /// at least `max_idle`, or aren't ready for use. By default, every check sends a `RESET`. See
+    /// [`Client::reset_if_idle`].
+    pub fn with_max_idle(mut self, max_idle: Duration) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// Open a connection, perform the handshake, and initialize the client with a
+    /// [`HELLO`](Message::Hello) message. If the server responds with anything other than
+    /// [`SUCCESS`](Message::Success), this fails with an [`io::Error`] of kind
+    /// [`ConnectionAborted`](io::ErrorKind::ConnectionAborted).
+    pub async fn connect(&self) -> Result<Connection> {
+        let mut backoff = self.retry_backoff;
+        let mut retries = 0;
+        loop {
+            match self.open().await {
+                Err(ConnectionError::IoError(_)) if retries < self.connect_retries => {
+                    Delay::new(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                    retries += 1;
+                }
+                result => break self.initialize(result?).await,
+            }
+        }
+    }
+
+    async fn open(&self) -> std::result::Result<Connection, ConnectionError> {
+        let addrs = match &self.address {
+            Address::Host(host) => lookup_host(host.as_str()).await?.collect(),
+            Address::Resolved(addrs) => addrs.clone(),
+        };
+
+        let mut last_error = io::Error::from(io::ErrorKind::AddrNotAvailable);
+        for addr in addrs {
+            match Stream::connect_with_timeout(addr, self.tls_domain.as_ref(), self.connect_timeout)
+                .await
+            {
+                Ok(stream) => {
+                    return Client::new_with_timeouts(
+                        BufStream::new(stream).compat(),
+                        &self.version_specifiers,
+                        self.timeouts,
+                    )
+                    .await
+                }
+                Err(error) => last_error = error,
+            }
+        }
+        Err(last_error.into())
+    }
+
+    async fn initialize(&self, mut client: Connection) -> Result<Connection> {
+        let mut metadata = self.metadata.clone();
+        let auth_token = self.auth_token().await?;
+        if let Some(auth_token) = &auth_token {
+            metadata.value.extend(auth_token.to_metadata().value);
+        }
+        match &self.user_agent {
+            Some(user_agent) => {
+                metadata
+                    .value
+                    .insert("user_agent".to_string(), Value::from(user_agent.as_str()));
+            }
+            None => {
+                metadata
+                    .value
+                    .get_or_insert_with("user_agent".to_string(), || {
+                        Value::from(DEFAULT_USER_AGENT)
+                    });
+            }
+        }
+        metadata
+            .value
+            .get_or_insert_with("scheme".to_string(), || Value::from("none"));
+        if let (Some(routing_context), V4_1 | V4_2 | V4_3 | V4_4) =
+            (&self.routing_context, client.version())
+        {
+            let mut routing = routing_context.value.clone();
+            if let Address::Host(host) = &self.address {
+                routing.insert("address".to_string(), Value::from(host.as_str()));
+            }
+            metadata
+                .value
+                .insert("routing".to_string(), Value::from(routing));
+        }
+
+        match client.hello(metadata).await? {
+            Message::Success(_) => {
+                client.set_auth_token(auth_token);
+                Ok(client)
+            }
+            other => {
+                if let (Message::Failure(failure), Some(provider), Some(auth_token)) =
+                    (&other, &self.auth_provider, &auth_token)
+                {
+                    if failure.metadata().get("code") == Some(&Value::from(TOKEN_EXPIRED)) {
+                        provider.provider.token_expired(auth_token);
+                    }
+                }
+                Err(CommunicationError::from(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    format!("server responded with {:?}", other),
+                ))
+                .into())
+            }
+        }
+    }
+
+    /// Ask the [`AuthProvider`] for its current token, which connections are then
+    /// [checked](ConnectionFactory::has_current_auth) against.
+    pub(crate) async fn auth_token(&self) -> Result<Option<AuthToken>> {
+        match &self.auth_provider {
+            Some(provider) => {
+                let auth_token = provider
+                    .provider
+                    .token()
+                    .await
+                    .map_err(ConnectionError::from)?;
+                *provider.latest() = Some(auth_token.clone());
+                Ok(Some(auth_token))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Return whether a connection was authenticated with the token most recently returned by the
+    /// [`AuthProvider`], without asking the provider again. Always true if the factory has no
+    /// provider.
+    pub(crate) fn has_current_auth(&self, conn: &Connection) -> bool {
+        match &self.auth_provider {
+            Some(provider) => conn.auth_token() == provider.latest().as_ref(),
+            None => true,
+        }
+    }
+
+    /// Check that a connection can be reused, sending a [`RESET`](Message::Reset) message if
+    /// needed (see [`ConnectionFactory::with_max_idle`]). Fails if the connection is
+    /// [broken](ConnectionFactory::has_broken), if it was authenticated with an older token than
+    /// the one the [`AuthProvider`] most recently returned, or if the server doesn't respond to
+    /// the `RESET` with [`SUCCESS`](Message::Success). The provider isn't asked for a token here,
+    /// so a new token is noticed once the factory next opens a connection.
+    pub async fn check(&self, conn: &mut Connection) -> Result<()> {
+        if self.is_expired(conn) {
+            return Err(CommunicationError::from(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "connection exceeded its maximum lifetime",
+            ))
+            .into());
+        }
+        if !self.has_current_auth(conn) {
+            return Err(CommunicationError::from(io::Error::new(
+                io::ErrorKind::ConnectionAborted,
+                "connection was authenticated with an outdated token",
+            ))
+            .into());
+        }
+
+        if let Some(response) = conn.reset_if_idle(self.max_idle).await? {
+            Success::try_from(response).map_err(ProtocolError::from)?;
+        }
+        Ok(())
+    }
+
+    /// Return whether a connection should be discarded without checking it, because it's
+    /// [`Defunct`](bolt_proto::ServerState::Defunct) or has exceeded its maximum lifetime.
+    pub fn has_broken(&self, conn: &Connection) -> bool {
+        conn.server_state() == bolt_proto::ServerState::Defunct || self.is_expired(conn)
+    }
+
+    fn is_expired(&self, conn: &Connection) -> bool {
+        self.max_lifetime
+            .is_some_and(|max_lifetime| conn.age() >= max_lifetime)
+    }
+}
+
+// Strip the port, and the brackets around an IPv6 address
+fn host_of(addr: &str) -> &str {
+    match addr.strip_prefix('[') {
+        Some(rest) => rest.split_once(']').map_or(rest, |(host, _)| host),
+        None => addr.rsplit_once(':').map_or(addr, |(host, _)| host),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host(factory: &ConnectionFactory) -> &str {
+        match &factory.address {
+            Address::Host(host) => host,
+            Address::Resolved(_) => panic!("address should be unresolved"),
+        }
+    }
+
+    #[test]
+    fn from_uri() {
+        let factory = ConnectionFactory::from_uri("bolt://localhost").unwrap();
+        assert_eq!(host(&factory), "localhost:7687");
+        assert_eq!(factory.tls_domain, None);
+        assert!(factory.routing_context.is_none());
+
+        let factory = ConnectionFactory::from_uri("bolt+s://example.com:7688/").unwrap();
+        assert_eq!(host(&factory), "example.com:7688");
+        assert_eq!(factory.tls_domain.as_deref(), Some("example.com"));
+
+        let factory = ConnectionFactory::from_uri("neo4j://[::1]:7000?region=eu&policy=x").unwrap();
+        assert_eq!(host(&factory), "[::1]:7000");
+        assert_eq!(
+            factory.routing_context.unwrap().value,
+            RoutingContext::from_iter(vec![("region", "eu"), ("policy", "x")]).value
+        );
+
+        let factory = ConnectionFactory::from_uri("neo4j+s://example.com").unwrap();
+        assert_eq!(factory.tls_domain.as_deref(), Some("example.com"));
+        assert!(factory.routing_context.unwrap().value.is_empty());
+    }
+
+    #[test]
+    fn for_address() {
+        let factory = ConnectionFactory::from_uri("neo4j+s://example.com").unwrap();
+        let other = factory.for_address("core-2.example.com:7687");
+        assert_eq!(host(&other), "core-2.example.com:7687");
+        assert_eq!(other.tls_domain.as_deref(), Some("core-2.example.com"));
+        assert_eq!(
+            other.for_address("[::1]:7687").tls_domain.as_deref(),
+            Some("::1")
+        );
+
+        let factory = ConnectionFactory::new("localhost:7687").for_address("localhost:7688");
+        assert_eq!(host(&factory), "localhost:7688");
+        assert_eq!(factory.tls_domain, None);
+    }
+
+    #[test]
+    fn invalid_uri() {
+        for uri in [
+            "localhost:7687",
+            "http://localhost",
+            "bolt+ssc://localhost",
+            "bolt://",
+            "bolt://localhost:port",
+            "bolt://localhost/db",
+            "bolt://localhost?region=eu",
+            "neo4j://localhost?region",
+            "bolt://[::1",
+        ] {
+            let error = ConnectionFactory::from_uri(uri).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidInput, "{}", uri);
+        }
+    }
+}
@@ -9,7 +9,8 @@
 //! [`AsyncRead`](futures_util::AsyncRead) and [`AsyncWrite`](futures_util::AsyncWrite).
 //!
 //! If you want to connect to a Bolt-compatible server from your application, you probably want to
-//! use a connection pool - see [bb8-bolt](https://crates.io/crates/bb8-bolt),
+//! use a connection pool - either the Bolt-aware `Pool` provided by the `tokio-stream` feature,
+//! or one of [bb8-bolt](https://crates.io/crates/bb8-bolt),
 //! [deadpool-bolt](https://crates.io/crates/deadpool-bolt), or
 //! [mobc-bolt](https://crates.io/crates/mobc-bolt).
 //!
@@ -17,7 +18,10 @@
 //! available, if you're using the [tokio](https://tokio.rs/) runtime.
 //!
 //! # Features
-//! - `tokio-stream` - enables the [`Stream`] type
+//! - `indexmap` - enables the `indexmap` feature of [`bolt_proto`], which keeps the entries of
+//!   maps, metadata, and [`Params`] in insertion order
+//! - `tokio-stream` - enables the [`Stream`] type, the [`ConnectionFactory`] used by connection
+//!   pools, and the [`Pool`] type
 //!
 //! # Example
 //! The below example demonstrates how to communicate with a Neo4j server using Bolt protocol
@@ -222,24 +226,47 @@
 //! #     Ok(())
 //! # }
 //! ```
+//! Instead of writing [`Metadata`] and [`Params`] by hand, queries, transactions, and routing
+//! table requests can also be built with [`Client::query`], [`Client::transaction`], and
+//! [`Client::routing_table`], which produce the right metadata for the negotiated protocol
+//! version.
+//!
 //! See the documentation of the [`Client`] struct for information on transaction management, error
 //! handling, and more.
 #[doc(inline)]
-pub use self::client::Client;
+pub use self::client::{
+    AccessMode, Batch, BatchResponse, BatchResponses, Client, ClientReader, ClientWriter,
+    PullHandle, PullResult, Query, ResponseHandle, RoutingTableRequest, RunHandle, RunResult,
+    Timeouts, Transaction,
+};
+pub use auth::AuthToken;
 
+mod auth;
 mod client;
 mod define_value_map;
 pub mod error;
 
 pub use bolt_proto;
 
+#[cfg(feature = "tokio-stream")]
+mod factory;
+#[cfg(feature = "tokio-stream")]
+mod pool;
 #[cfg(feature = "tokio-stream")]
 mod stream;
 
+#[cfg(feature = "tokio-stream")]
+pub use auth::AuthProvider;
+#[cfg(feature = "tokio-stream")]
+pub use factory::{Connection, ConnectionFactory};
+#[cfg(feature = "tokio-stream")]
+pub use pool::{AcquireOptions, Pool, PoolBuilder, PooledConnection};
 #[cfg(feature = "tokio-stream")]
 pub use stream::Stream;
 
-// TODO: Convert Client methods to return a builder-type object so we don't need these anymore
+// These are still accepted by the Client methods that mirror Bolt messages, but the builders
+// produce the metadata for the negotiated protocol version. The routing context itself has no
+// version-specific keys, so the routing table builder still takes a RoutingContext.
 define_value_map!(Metadata);
 define_value_map!(Params);
 define_value_map!(RoutingContext);
@@ -0,0 +1,790 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, PoisonError, Weak},
+    time::Duration,
+};
+
+use futures_timer::Delay;
+use tokio::{
+    runtime::Handle,
+    sync::{OwnedSemaphorePermit, Semaphore},
+};
+
+use bolt_proto::Message;
+
+use crate::{
+    client::timeout,
+    error::{ConnectionError, Result},
+    Connection, ConnectionFactory,
+};
+
+const DEFAULT_MAX_SIZE: usize = 100;
+const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+// A connection that takes longer than this to reset is discarded, so it stops counting towards
+// the size of the pool
+const RESET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A connection pool for Bolt servers, built on a [`ConnectionFactory`].
+///
+/// Unlike the generic pools supported by [bb8-bolt](https://crates.io/crates/bb8-bolt),
+/// [deadpool-bolt](https://crates.io/crates/deadpool-bolt), and
+/// [mobc-bolt](https://crates.io/crates/mobc-bolt), this pool knows about Bolt:
+/// - It keeps a separate sub-pool, with its own size limit, for each server address, so it can
+///   hold connections to every member of a cluster.
+/// - Connections are [reset](crate::Client::reset) in the background when they're returned to
+///   the pool, rather than when they're taken out. Connections that take longer than 30 seconds
+///   to reset are discarded.
+/// - Idle connections that were last used for the requested database and user are preferred.
+/// - Idle connections are [kept alive](crate::Client::keep_alive) in the background, so the
+///   server doesn't close them for exceeding its receive timeout.
+/// - Idle connections that were authenticated with an outdated token from the factory's
+///   [`AuthProvider`](crate::AuthProvider) are replaced.
+/// - When a server leaves the routing table, its connections can be
+///   [drained](Pool::retain_addresses).
+///
+/// Cloning a pool is cheap, and the clones share the same connections. Since connections are
+/// reset in the background, the pool must be used within a [tokio](https://tokio.rs) runtime.
+/// ```
+/// # use std::env;
+/// # use bolt_client::*;
+/// # use bolt_proto::Value;
+/// #
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// #     let addr = env::var("BOLT_TEST_ADDR")?;
+/// let factory = ConnectionFactory::new(addr)
+///     .with_basic_auth(env::var("BOLT_TEST_USERNAME")?, env::var("BOLT_TEST_PASSWORD")?);
+/// let pool = Pool::builder(factory).with_max_size(20).build();
+///
+/// # let result = pool.get().await;
+/// # skip_if_handshake_failed!(result, Ok(()));
+/// let mut conn = result?;
+/// let (_, pull) = conn.query("RETURN 1 AS num;").fetch().await?;
+/// assert_eq!(pull.records()[0].fields(), &[Value::from(1)]);
+/// #     Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+#[derive(Debug, Clone)]
+pub struct Pool {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+struct Shared {
+    factory: ConnectionFactory,
+    max_size: usize,
+    acquire_timeout: Option<Duration>,
+    default: Arc<SubPool>,
+    routed: Mutex<HashMap<String, Arc<SubPool>>>,
+}
+
+/// Configures a [`Pool`].
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+#[derive(Debug, Clone)]
+pub struct PoolBuilder {
+    factory: ConnectionFactory,
+    max_size: usize,
+    acquire_timeout: Option<Duration>,
+    keep_alive_interval: Option<Duration>,
+}
+
+impl PoolBuilder {
+    /// Set the maximum number of connections to each server, including connections that are
+    /// being reset. Default is 100.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the time allowed for getting a connection from the pool, including waiting for a
+    /// connection to become available and opening a new one. Default is 60 seconds.
+    pub fn with_acquire_timeout(mut self, acquire_timeout: Option<Duration>) -> Self {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    /// Set how often idle connections are checked in the background. Connections that have been
+    /// idle for at least half of the server's [receive timeout](crate::Client::server_recv_timeout)
+    /// are sent a [no-op chunk](crate::Client::keep_alive), so this should be shorter than half of
+    /// the receive timeout. Default is 10 seconds. If `None`, idle connections aren't kept alive.
+    pub fn with_keep_alive_interval(mut self, keep_alive_interval: Option<Duration>) -> Self {
+        self.keep_alive_interval = keep_alive_interval;
+        self
+    }
+
+    /// Create the pool. If this is called within a tokio runtime, a task that keeps idle
+    /// connections alive is spawned, and it stops once every clone of the pool is dropped.
+    pub fn build(self) -> Pool {
+        let shared = Arc::new(Shared {
+            default: Arc::new(SubPool::new(self.factory.clone(), self.max_size)),
+            factory: self.factory,
+            max_size: self.max_size,
+            acquire_timeout: self.acquire_timeout,
+            routed: Mutex::new(HashMap::new()),
+        });
+        if let (Some(interval), Ok(handle)) = (self.keep_alive_interval, Handle::try_current()) {
+            handle.spawn(keep_alive(Arc::downgrade(&shared), interval));
+        }
+        Pool { shared }
+    }
+}
+
+async fn keep_alive(shared: Weak<Shared>, interval: Duration) {
+    loop {
+        Delay::new(interval).await;
+        let sub_pools = match shared.upgrade() {
+            Some(shared) => shared.sub_pools(),
+            None => return,
+        };
+        for sub_pool in sub_pools {
+            sub_pool.keep_alive().await;
+        }
+    }
+}
+
+impl Shared {
+    fn sub_pools(&self) -> Vec<Arc<SubPool>> {
+        let routed = self.routed.lock().unwrap();
+        std::iter::once(Arc::clone(&self.default))
+            .chain(routed.values().cloned())
+            .collect()
+    }
+}
+
+/// Describes the connection wanted from a [`Pool`].
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct AcquireOptions {
+    address: Option<String>,
+    database: Option<String>,
+    user: Option<String>,
+}
+
+impl AcquireOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Connect to the given server, such as one listed in a routing table, instead of the
+    /// address of the pool's [`ConnectionFactory`].
+    pub fn with_address(mut self, address: Option<String>) -> Self {
+        self.address = address;
+        self
+    }
+
+    pub fn address(&self) -> Option<&str> {
+        self.address.as_deref()
+    }
+
+    /// Prefer idle connections that were last used for the given database.
+    pub fn with_database(mut self, database: Option<String>) -> Self {
+        self.database = database;
+        self
+    }
+
+    pub fn database(&self) -> Option<&str> {
+        self.database.as_deref()
+    }
+
+    /// Prefer idle connections that were last used for the given (impersonated) user.
+    pub fn with_user(mut self, user: Option<String>) -> Self {
+        self.user = user;
+        self
+    }
+
+    pub fn user(&self) -> Option<&str> {
+        self.user.as_deref()
+    }
+}
+
+impl Pool {
+    pub fn builder(factory: ConnectionFactory) -> PoolBuilder {
+        PoolBuilder {
+            factory,
+            max_size: DEFAULT_MAX_SIZE,
+            acquire_timeout: Some(DEFAULT_ACQUIRE_TIMEOUT),
+            keep_alive_interval: Some(DEFAULT_KEEP_ALIVE_INTERVAL),
+        }
+    }
+
+    /// Get a connection to the address of the pool's [`ConnectionFactory`].
+    pub async fn get(&self) -> Result<PooledConnection> {
+        self.get_with(AcquireOptions::default()).await
+    }
+
+    /// Get a connection matching the given options. An idle connection is reused if there is
+    /// one, otherwise a new connection is opened, waiting for a connection to be returned if the
+    /// server's sub-pool is full. Fails with an [`io::Error`] of kind
+    /// [`TimedOut`](io::ErrorKind::TimedOut) if this takes longer than the
+    /// [acquire timeout](PoolBuilder::with_acquire_timeout).
+    pub async fn get_with(&self, options: AcquireOptions) -> Result<PooledConnection> {
+        let sub_pool = match options.address() {
+            Some(address) => self.sub_pool(address),
+            None => Arc::clone(&self.shared.default),
+        };
+
+        timeout(self.shared.acquire_timeout, sub_pool.acquire(options))
+            .await
+            .map_err(ConnectionError::from)?
+    }
+
+    fn sub_pool(&self, address: &str) -> Arc<SubPool> {
+        let mut routed = self.shared.routed.lock().unwrap();
+        let sub_pool = routed.entry(address.to_string()).or_insert_with(|| {
+            Arc::new(SubPool::new(
+                self.shared.factory.for_address(address),
+                self.shared.max_size,
+            ))
+        });
+        Arc::clone(sub_pool)
+    }
+
+    /// Close the idle connections to the given server, and discard the connections that are in
+    /// use when they're returned. Connections that are requested for this address afterwards
+    /// use a new sub-pool.
+    pub fn drain(&self, address: &str) {
+        let removed = self.shared.routed.lock().unwrap().remove(address);
+        if let Some(sub_pool) = removed {
+            sub_pool.drain();
+        }
+    }
+
+    /// [Drain](Pool::drain) every server that isn't in the given list of addresses. Call this
+    /// with the servers of each new routing table, so that connections to servers that have left
+    /// the cluster are closed.
+    pub fn retain_addresses<I, A>(&self, addresses: I)
+    where
+        I: IntoIterator<Item = A>,
+        A: AsRef<str>,
+    {
+        let addresses: HashSet<String> = addresses
+            .into_iter()
+            .map(|address| address.as_ref().to_string())
+            .collect();
+        let removed: Vec<Arc<SubPool>> = {
+            let mut routed = self.shared.routed.lock().unwrap();
+            let stale: Vec<String> = routed
+                .keys()
+                .filter(|address| !addresses.contains(*address))
+                .cloned()
+                .collect();
+            stale
+                .into_iter()
+                .filter_map(|address| routed.remove(&address))
+                .collect()
+        };
+        for sub_pool in removed {
+            sub_pool.drain();
+        }
+    }
+
+    /// Get the number of idle connections to the given server, or to the address of the pool's
+    /// [`ConnectionFactory`] if no address is given.
+    pub fn idle_connections(&self, address: Option<&str>) -> usize {
+        let sub_pool = match address {
+            Some(address) => self.shared.routed.lock().unwrap().get(address).cloned(),
+            None => Some(Arc::clone(&self.shared.default)),
+        };
+        sub_pool.map_or(0, |sub_pool| {
+            sub_pool.idle.lock().unwrap().connections.len()
+        })
+    }
+}
+
+#[derive(Debug)]
+struct SubPool {
+    factory: ConnectionFactory,
+    // Each permit is a connection, whether it's idle, in use, or being reset
+    permits: Arc<Semaphore>,
+    idle: Mutex<Idle>,
+}
+
+#[derive(Debug, Default)]
+struct Idle {
+    // Most recently returned connections are last
+    connections: Vec<IdleConnection>,
+    draining: bool,
+}
+
+#[derive(Debug)]
+struct IdleConnection {
+    conn: Connection,
+    database: Option<String>,
+    user: Option<String>,
+    permit: OwnedSemaphorePermit,
+}
+
+impl SubPool {
+    fn new(factory: ConnectionFactory, max_size: usize) -> Self {
+        Self {
+            factory,
+            permits: Arc::new(Semaphore::new(max_size)),
+            idle: Mutex::new(Idle::default()),
+        }
+    }
+
+    async fn acquire(self: Arc<Self>, options: AcquireOptions) -> Result<PooledConnection> {
+        let mut asked_provider = false;
+        if let Some(idle) = self.take_current(&options, &mut asked_provider).await? {
+            return Ok(PooledConnection::new(self, idle.conn, idle.permit, options));
+        }
+
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .map_err(|_| ConnectionError::from(drained()))?;
+        // A connection may have been returned while we were waiting
+        if let Some(idle) = self.take_current(&options, &mut asked_provider).await? {
+            return Ok(PooledConnection::new(self, idle.conn, idle.permit, options));
+        }
+
+        let conn = self.factory.connect().await?;
+        Ok(PooledConnection::new(self, conn, permit, options))
+    }
+
+    // Connections authenticated with an outdated token are replaced, since re-authenticating
+    // them requires Bolt v5.1. The provider is asked for its token at most once per checkout,
+    // rather than once for each idle connection. It's asked before a connection is taken, so an
+    // error from the provider can't lose an idle connection.
+    async fn take_current(
+        &self,
+        options: &AcquireOptions,
+        asked_provider: &mut bool,
+    ) -> Result<Option<IdleConnection>> {
+        if !*asked_provider && !self.idle.lock().unwrap().connections.is_empty() {
+            self.factory.auth_token().await?;
+            *asked_provider = true;
+        }
+        while let Some(idle) = self.take_idle(options) {
+            if self.factory.has_current_auth(&idle.conn) {
+                return Ok(Some(idle));
+            }
+        }
+        Ok(None)
+    }
+
+    fn take_idle(&self, options: &AcquireOptions) -> Option<IdleConnection> {
+        let mut idle = self.idle.lock().unwrap();
+        loop {
+            let index = idle
+                .connections
+                .iter()
+                .rposition(|idle| {
+                    idle.database.as_deref() == options.database()
+                        && idle.user.as_deref() == options.user()
+                })
+                .or_else(|| idle.connections.len().checked_sub(1))?;
+            let candidate = idle.connections.remove(index);
+            if self.is_usable(&candidate.conn) {
+                return Some(candidate);
+            }
+        }
+    }
+
+    fn is_usable(&self, conn: &Connection) -> bool {
+        // The server closes connections that are idle for longer than its receive timeout
+        let timed_out = conn
+            .server_recv_timeout()
+            .is_some_and(|recv_timeout| conn.idle_time() >= recv_timeout);
+        !timed_out && !self.factory.has_broken(conn)
+    }
+
+    // Connections are taken out of the pool while they're sent a no-op chunk, and put back
+    // afterwards unless that failed
+    async fn keep_alive(&self) {
+        let stale: Vec<IdleConnection> = {
+            let mut idle = self.idle.lock().unwrap();
+            let (stale, fresh) =
+                std::mem::take(&mut idle.connections)
+                    .into_iter()
+                    .partition(|idle| {
+                        idle.conn
+                            .server_recv_timeout()
+                            .is_some_and(|recv_timeout| idle.conn.idle_time() >= recv_timeout / 2)
+                    });
+            idle.connections = fresh;
+            stale
+        };
+
+        for mut stale in stale {
+            if stale.conn.keep_alive().await.is_err() || !self.is_usable(&stale.conn) {
+                continue;
+            }
+            let mut idle = self.idle.lock().unwrap();
+            if !idle.draining {
+                idle.connections.push(stale);
+            }
+        }
+    }
+
+    fn drain(&self) {
+        let connections = {
+            let mut idle = self.idle.lock().unwrap();
+            idle.draining = true;
+            std::mem::take(&mut idle.connections)
+        };
+        // Wake up anyone waiting for a connection to this server
+        self.permits.close();
+        drop(connections);
+    }
+
+    async fn release(
+        self: Arc<Self>,
+        mut conn: Connection,
+        database: Option<String>,
+        user: Option<String>,
+        permit: OwnedSemaphorePermit,
+    ) {
+        let reset = timeout(Some(RESET_TIMEOUT), conn.reset()).await;
+        if !matches!(reset, Ok(Ok(Message::Success(_)))) || !self.is_usable(&conn) {
+            return;
+        }
+
+        let mut idle = self.idle.lock().unwrap();
+        if !idle.draining {
+            idle.connections.push(IdleConnection {
+                conn,
+                database,
+                user,
+                permit,
+            });
+        }
+    }
+}
+
+fn drained() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        "server was removed from the pool",
+    )
+}
+
+/// A connection taken from a [`Pool`], which dereferences to a [`Connection`]. The connection is
+/// returned to the pool when this is dropped.
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+#[derive(Debug)]
+pub struct PooledConnection {
+    conn: Option<Connection>,
+    database: Option<String>,
+    user: Option<String>,
+    sub_pool: Arc<SubPool>,
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+impl PooledConnection {
+    fn new(
+        sub_pool: Arc<SubPool>,
+        conn: Connection,
+        permit: OwnedSemaphorePermit,
+        options: AcquireOptions,
+    ) -> Self {
+        Self {
+            conn: Some(conn),
+            database: options.database,
+            user: options.user,
+            sub_pool,
+            permit: Some(permit),
+        }
+    }
+
+    /// Remove the connection from the pool, making room for a new one.
+    pub fn detach(mut self) -> Connection {
+        self.conn.take().unwrap()
+    }
+}
+
+impl Deref for PooledConnection {
+    type Target = Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.conn.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.conn.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledConnection {
+    fn drop(&mut self) {
+        let (conn, permit) = match (self.conn.take(), self.permit.take()) {
+            (Some(conn), Some(permit)) => (conn, permit),
+            _ => return,
+        };
+        let draining = self
+            .sub_pool
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .draining;
+        if draining || self.sub_pool.factory.has_broken(&conn) {
+            return;
+        }
+
+        // The permit is held until the reset finishes, so resetting connections count towards
+        // the size of the pool. Without a runtime, the connection is discarded instead.
+        if let Ok(handle) = Handle::try_current() {
+            handle.spawn(Arc::clone(&self.sub_pool).release(
+                conn,
+                self.database.take(),
+                self.user.take(),
+                permit,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use bolt_proto::{message::Success, value::ValueMap, version::*, Value};
+    use futures_timer::Delay;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+    use tokio_util::compat::*;
+
+    use super::*;
+    use crate::{AuthProvider, AuthToken};
+
+    // A server that accepts any number of connections and responds to every message with
+    // SUCCESS. Returns its address and the number of connections it has accepted.
+    async fn serve() -> (String, Arc<AtomicUsize>) {
+        serve_with_metadata(ValueMap::new()).await
+    }
+
+    async fn serve_with_metadata(metadata: ValueMap) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&accepted);
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+                let metadata = metadata.clone();
+                tokio::spawn(async move {
+                    stream.read_exact(&mut [0; 20]).await?;
+                    stream.write_all(&V4_4.to_be_bytes()).await?;
+                    let mut stream = stream.compat();
+                    while Message::from_stream(&mut stream).await.is_ok() {
+                        let success = Message::Success(Success::new(metadata.clone()));
+                        for chunk in success.into_chunks().unwrap() {
+                            futures_util::AsyncWriteExt::write_all(&mut stream, &chunk).await?;
+                        }
+                    }
+                    io::Result::Ok(())
+                });
+            }
+        });
+        (addr, accepted)
+    }
+
+    fn pool(addr: &str, max_size: usize) -> Pool {
+        Pool::builder(ConnectionFactory::new(addr).with_version_specifiers([V4_4, 0, 0, 0]))
+            .with_max_size(max_size)
+            .with_acquire_timeout(Some(Duration::from_millis(200)))
+            .build()
+    }
+
+    // Connections are reset in the background after they're returned
+    async fn wait_for_idle(pool: &Pool, address: Option<&str>, count: usize) {
+        for _ in 0..100 {
+            if pool.idle_connections(address) == count {
+                return;
+            }
+            Delay::new(Duration::from_millis(10)).await;
+        }
+        panic!("expected {} idle connections", count);
+    }
+
+    #[tokio::test]
+    async fn reuse() {
+        let (addr, accepted) = serve().await;
+        let pool = pool(&addr, 2);
+
+        let conn = pool.get().await.unwrap();
+        drop(conn);
+        wait_for_idle(&pool, None, 1).await;
+
+        let conn = pool.get().await.unwrap();
+        assert_eq!(pool.idle_connections(None), 0);
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        drop(conn);
+        wait_for_idle(&pool, None, 1).await;
+    }
+
+    #[tokio::test]
+    async fn keep_alive() {
+        let hints = ValueMap::from([(
+            String::from("connection.recv_timeout_seconds"),
+            Value::from(1),
+        )]);
+        let (addr, accepted) = serve_with_metadata(ValueMap::from([(
+            String::from("hints"),
+            Value::from(hints),
+        )]))
+        .await;
+        let pool =
+            Pool::builder(ConnectionFactory::new(&addr).with_version_specifiers([V4_4, 0, 0, 0]))
+                .with_keep_alive_interval(Some(Duration::from_millis(100)))
+                .build();
+
+        drop(pool.get().await.unwrap());
+        wait_for_idle(&pool, None, 1).await;
+
+        // Without the keepalive, the connection would be discarded for exceeding the timeout
+        Delay::new(Duration::from_millis(1500)).await;
+        let conn = pool.get().await.unwrap();
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        assert!(conn.idle_time() < Duration::from_millis(600));
+    }
+
+    #[tokio::test]
+    async fn acquire_timeout() {
+        let (addr, accepted) = serve().await;
+        let pool = pool(&addr, 1);
+
+        let conn = pool.get().await.unwrap();
+        match pool.get().await {
+            Err(crate::error::Error::ConnectionError(ConnectionError::IoError(error))) => {
+                assert_eq!(error.kind(), io::ErrorKind::TimedOut)
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+
+        // A detached connection no longer counts towards the size of the pool
+        let _detached = conn.detach();
+        pool.get().await.unwrap();
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn prefers_matching_database() {
+        let (addr, _) = serve().await;
+        let pool = pool(&addr, 2);
+        let options = |database: &str| {
+            AcquireOptions::new()
+                .with_address(Some(addr.clone()))
+                .with_database(Some(database.to_string()))
+        };
+
+        let older = pool.get_with(options("a")).await.unwrap();
+        Delay::new(Duration::from_millis(5)).await;
+        let newer = pool.get_with(options("b")).await.unwrap();
+        drop(older);
+        drop(newer);
+        wait_for_idle(&pool, Some(&addr), 2).await;
+
+        // The most recently returned connection isn't used for database "a"
+        let a = pool.get_with(options("a")).await.unwrap();
+        let b = pool.get_with(options("b")).await.unwrap();
+        assert!(a.age() > b.age());
+    }
+
+    #[tokio::test]
+    async fn outdated_auth_token() {
+        // Counts the number of times it's asked for a token
+        #[derive(Default)]
+        struct RotatingProvider(Mutex<usize>, AtomicUsize);
+
+        #[async_trait::async_trait]
+        impl AuthProvider for RotatingProvider {
+            async fn token(&self) -> io::Result<AuthToken> {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                Ok(AuthToken::Bearer {
+                    credentials: self.0.lock().unwrap().to_string(),
+                })
+            }
+        }
+
+        let (addr, accepted) = serve().await;
+        let provider = Arc::new(RotatingProvider::default());
+        let factory = ConnectionFactory::new(&addr)
+            .with_version_specifiers([V4_4, 0, 0, 0])
+            .with_auth_provider(Arc::clone(&provider));
+        let pool = Pool::builder(factory).build();
+
+        let first = pool.get().await.unwrap();
+        let second = pool.get().await.unwrap();
+        drop(first);
+        drop(second);
+        wait_for_idle(&pool, None, 2).await;
+        *provider.0.lock().unwrap() += 1;
+        provider.1.store(0, Ordering::SeqCst);
+
+        // The provider is asked once for the checkout, not for each idle connection, and once for
+        // the new connection
+        let conn = pool.get().await.unwrap();
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+        assert_eq!(provider.1.load(Ordering::SeqCst), 2);
+        assert_eq!(conn.auth_token(), Some(&provider.token().await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn auth_provider_error() {
+        #[derive(Default)]
+        struct FailingProvider(AtomicBool);
+
+        #[async_trait::async_trait]
+        impl AuthProvider for FailingProvider {
+            async fn token(&self) -> io::Result<AuthToken> {
+                if self.0.load(Ordering::SeqCst) {
+                    return Err(io::Error::other("identity provider is unavailable"));
+                }
+                Ok(AuthToken::None)
+            }
+        }
+
+        let (addr, _) = serve().await;
+        let provider = Arc::new(FailingProvider::default());
+        let factory = ConnectionFactory::new(&addr)
+            .with_version_specifiers([V4_4, 0, 0, 0])
+            .with_auth_provider(Arc::clone(&provider));
+        let pool = Pool::builder(factory).build();
+
+        drop(pool.get().await.unwrap());
+        wait_for_idle(&pool, None, 1).await;
+
+        // The idle connection stays in the pool while the provider is failing
+        provider.0.store(true, Ordering::SeqCst);
+        assert!(pool.get().await.is_err());
+        assert_eq!(pool.idle_connections(None), 1);
+        provider.0.store(false, Ordering::SeqCst);
+        pool.get().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn drain_removed_servers() {
+        let (addr, accepted) = serve().await;
+        let (other_addr, _) = serve().await;
+        let pool = pool(&addr, 2);
+        let options = AcquireOptions::new().with_address(Some(addr.clone()));
+        let other_options = AcquireOptions::new().with_address(Some(other_addr.clone()));
+
+        drop(pool.get_with(options.clone()).await.unwrap());
+        drop(pool.get_with(other_options).await.unwrap());
+        wait_for_idle(&pool, Some(&addr), 1).await;
+        wait_for_idle(&pool, Some(&other_addr), 1).await;
+        let in_use = pool.get_with(options.clone()).await.unwrap();
+
+        pool.retain_addresses([&other_addr]);
+        assert_eq!(pool.idle_connections(Some(&addr)), 0);
+        assert_eq!(pool.idle_connections(Some(&other_addr)), 1);
+
+        // Connections to drained servers aren't returned to the pool
+        drop(in_use);
+        let conn = pool.get_with(options).await.unwrap();
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+        drop(conn);
+        wait_for_idle(&pool, Some(&addr), 1).await;
+    }
+}
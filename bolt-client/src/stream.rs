@@ -4,6 +4,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use pin_project::pin_project;
@@ -17,6 +18,8 @@ use tokio_rustls::{
     TlsConnector,
 };
 
+use crate::client::timeout;
+
 /// A convenient wrapper around a [`TcpStream`](tokio::net::TcpStream) or a
 /// [`TlsStream`](tokio_rustls::client::TlsStream).
 #[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
@@ -38,15 +41,15 @@ impl Stream {
         match domain {
             Some(domain) => {
                 let mut root_cert_store = RootCertStore::empty();
-                root_cert_store.add_server_trust_anchors(
-                    webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|anchor| {
+                root_cert_store.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(
+                    |anchor| {
                         OwnedTrustAnchor::from_subject_spki_name_constraints(
                             anchor.subject,
                             anchor.spki,
                             anchor.name_constraints,
                         )
-                    }),
-                );
+                    },
+                ));
 
                 let config = ClientConfig::builder()
                     .with_safe_defaults()
@@ -67,6 +70,18 @@ impl Stream {
             None => Ok(Stream::Tcp(TcpStream::connect(addr).await?)),
         }
     }
+
+    /// Like [`Stream::connect`], but fail with an [`io::Error`] of kind
+    /// [`TimedOut`](io::ErrorKind::TimedOut) if the connection, including TLS negotiation, isn't
+    /// established within the given time.
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio-stream")))]
+    pub async fn connect_with_timeout(
+        addr: impl ToSocketAddrs,
+        domain: Option<impl AsRef<str>>,
+        connect_timeout: Option<Duration>,
+    ) -> io::Result<Self> {
+        timeout(connect_timeout, Self::connect(addr, domain)).await?
+    }
 }
 
 impl AsyncRead for Stream {
@@ -47,12 +47,12 @@ pub fn bolt_structure(attr_args: TokenStream, item: TokenStream) -> TokenStream
     let byte_var_defs = byte_var_names.iter()
         .zip(field_names.iter())
         .map(|(var_name, field_name)| {
-            quote!(let #var_name = crate::Value::from(self.#field_name).serialize()?;)
+            quote!(let #var_name = crate::Value::from(self.#field_name).serialize_with(options)?;)
         });
 
     let deserialize_var_defs = field_names.iter().map(|name| {
         quote!(
-            let (#name, remaining) = crate::Value::deserialize(bytes)?;
+            let (#name, remaining) = crate::Value::deserialize_with(bytes, options)?;
             bytes = remaining;
         )
     });
@@ -71,7 +71,10 @@ pub fn bolt_structure(attr_args: TokenStream, item: TokenStream) -> TokenStream
                 Ok(#marker)
             }
 
-            fn serialize(self) -> crate::error::SerializeResult<::bytes::Bytes> {
+            fn serialize_with(
+                self,
+                options: &crate::serialization::SerializeOptions,
+            ) -> crate::error::SerializeResult<::bytes::Bytes> {
                 use ::bytes::BufMut;
                 use crate::serialization::{BoltStructure, BoltValue};
 
@@ -89,7 +92,10 @@ pub fn bolt_structure(attr_args: TokenStream, item: TokenStream) -> TokenStream
                 Ok(result_bytes_mut.freeze())
             }
 
-            fn deserialize<B>(mut bytes: B) -> crate::error::DeserializeResult<(Self, B)>
+            fn deserialize_with<B>(
+                mut bytes: B,
+                options: &crate::serialization::DeserializeOptions,
+            ) -> crate::error::DeserializeResult<(Self, B)>
             where B: ::bytes::Buf + ::std::panic::UnwindSafe
             {
                 #(#deserialize_var_defs)*
@@ -0,0 +1,8 @@
+#![no_main]
+
+use bolt_proto::Message;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Message::deserialize(data);
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use bolt_proto::{DeserializeOptions, Message};
+use futures_util::{io::Cursor, FutureExt};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // Reading from a cursor never waits, so the future completes on the first poll
+    let options = DeserializeOptions::default().with_max_message_size(Some(1 << 20));
+    let _ = Message::from_stream_with(Cursor::new(data), &options)
+        .now_or_never()
+        .expect("reading from a cursor should not block");
+});
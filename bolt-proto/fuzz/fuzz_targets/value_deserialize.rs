@@ -0,0 +1,8 @@
+#![no_main]
+
+use bolt_proto::Value;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Value::deserialize(data);
+});
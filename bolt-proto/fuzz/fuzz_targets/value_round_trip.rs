@@ -0,0 +1,30 @@
+#![no_main]
+
+use bolt_proto::{SerializeOptions, Value};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok((value, _)) = Value::deserialize(data) else {
+        return;
+    };
+
+    // Compare bytes rather than values, since NaN is not equal to itself. Canonical mode makes
+    // the map entry order deterministic, and the newest encodings avoid the lossy legacy
+    // date-time structures.
+    let options = SerializeOptions::default()
+        .with_utc_date_times(true)
+        .with_vectors(true)
+        .with_canonical(true);
+    let bytes = value
+        .clone()
+        .serialize_with(&options)
+        .expect("deserialized value should serialize");
+    let (round_tripped, remaining) =
+        Value::deserialize(bytes.clone()).expect("serialized value should deserialize");
+    assert!(remaining.is_empty());
+    assert_eq!(
+        round_tripped.serialize_with(&options).unwrap(),
+        bytes,
+        "round trip changed {value:?}"
+    );
+});
@@ -0,0 +1,543 @@
+//! [`Arbitrary`] implementations for [`Value`]s, [`Message`]s, and the types they contain, for
+//! property-based testing with [`proptest`](mod@proptest).
+//!
+//! Generate values with [`any`]:
+//! ```
+//! use bolt_proto::{SerializeOptions, Value};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(value in any::<Value>())| {
+//!     let options = SerializeOptions::new()
+//!         .with_utc_date_times(true)
+//!         .with_vectors(true);
+//!     let bytes = value.clone().serialize_with(&options).unwrap();
+//!     prop_assert_eq!(Value::deserialize(bytes).unwrap().0, value);
+//! });
+//! ```
+//!
+//! Generated floats are never NaN, so generated values can be compared with `==`. Generated
+//! [`Value::Structure`]s never use the signature of a built-in structure, and generated
+//! [`Path`]s are always valid.
+
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use proptest::{collection::vec, prelude::*, sample::select};
+
+use crate::{message::*, value::*, Message, Value};
+
+const MAX_DEPTH: u32 = 3;
+const MAX_SIZE: usize = 6;
+
+// The boundaries between the integer markers
+const INTEGER_BOUNDARIES: [i64; 16] = [
+    i64::MIN,
+    i32::MIN as i64 - 1,
+    i32::MIN as i64,
+    i16::MIN as i64 - 1,
+    i16::MIN as i64,
+    -129,
+    -128,
+    -17,
+    -16,
+    127,
+    128,
+    i16::MAX as i64,
+    i16::MAX as i64 + 1,
+    i32::MAX as i64,
+    i32::MAX as i64 + 1,
+    i64::MAX,
+];
+
+fn integer() -> impl Strategy<Value = i64> {
+    prop_oneof![any::<i64>(), -16_i64..=127, select(&INTEGER_BOUNDARIES[..])]
+}
+
+fn float() -> impl Strategy<Value = f64> {
+    use proptest::num::f64::*;
+    POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO | INFINITE
+}
+
+fn float_32() -> impl Strategy<Value = f32> {
+    use proptest::num::f32::*;
+    POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO | INFINITE
+}
+
+fn map(values: impl Strategy<Value = Value>) -> impl Strategy<Value = ValueMap> {
+    vec((any::<String>(), values), 0..MAX_SIZE).prop_map(|entries| entries.into_iter().collect())
+}
+
+pub(crate) fn date() -> impl Strategy<Value = NaiveDate> {
+    (NaiveDate::MIN.num_days_from_ce()..=NaiveDate::MAX.num_days_from_ce())
+        .prop_map(|days| NaiveDate::from_num_days_from_ce_opt(days).unwrap())
+}
+
+pub(crate) fn time() -> impl Strategy<Value = NaiveTime> {
+    (0..86_400_u32, 0..1_000_000_000_u32).prop_map(|(seconds, nanos)| {
+        NaiveTime::from_num_seconds_from_midnight_opt(seconds, nanos).unwrap()
+    })
+}
+
+pub(crate) fn offset() -> impl Strategy<Value = FixedOffset> {
+    (-86_399..=86_399).prop_map(|seconds| FixedOffset::east_opt(seconds).unwrap())
+}
+
+// Leave room for any offset, so the local date-time is in range too
+pub(crate) fn date_time() -> impl Strategy<Value = NaiveDateTime> {
+    let min = NaiveDateTime::MIN.and_utc().timestamp() + 86_400;
+    let max = NaiveDateTime::MAX.and_utc().timestamp() - 86_400;
+    (min..=max, 0..1_000_000_000_u32).prop_map(|(seconds, nanos)| {
+        DateTime::from_timestamp(seconds, nanos)
+            .unwrap()
+            .naive_utc()
+    })
+}
+
+pub(crate) fn date_time_offset() -> impl Strategy<Value = DateTime<FixedOffset>> {
+    (date_time(), offset()).prop_map(|(utc, offset)| offset.from_utc_datetime(&utc))
+}
+
+pub(crate) fn date_time_zoned() -> impl Strategy<Value = DateTime<Tz>> {
+    (date_time(), select(&TZ_VARIANTS[..]))
+        .prop_map(|(utc, timezone)| timezone.from_utc_datetime(&utc))
+}
+
+fn structure_signature() -> impl Strategy<Value = u8> {
+    any::<u8>().prop_filter("signature is used by a built-in structure", |signature| {
+        !BUILT_IN_SIGNATURES.contains(signature)
+    })
+}
+
+// Values that don't contain other values
+fn scalar() -> impl Strategy<Value = Value> {
+    prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::Boolean),
+        integer().prop_map(Value::Integer),
+        float().prop_map(Value::Float),
+        vec(any::<u8>(), 0..300).prop_map(Value::Bytes),
+        any::<String>().prop_map(Value::String),
+        date().prop_map(Value::Date),
+        (time(), offset()).prop_map(|(time, offset)| Value::Time(time, offset)),
+        date_time_offset().prop_map(Value::DateTimeOffset),
+        date_time_zoned().prop_map(Value::DateTimeZoned),
+        time().prop_map(Value::LocalTime),
+        date_time().prop_map(Value::LocalDateTime),
+        any::<Duration>().prop_map(Value::Duration),
+        any::<Point2D>().prop_map(Value::Point2D),
+        any::<Point3D>().prop_map(Value::Point3D),
+        any::<Vector>().prop_map(Value::Vector),
+    ]
+}
+
+impl Arbitrary for Value {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        scalar()
+            .prop_recursive(MAX_DEPTH, 32, MAX_SIZE as u32, |inner| {
+                prop_oneof![
+                    vec(inner.clone(), 0..MAX_SIZE).prop_map(Value::List),
+                    map(inner.clone()).prop_map(Value::Map),
+                    any::<Node>().prop_map(Value::Node),
+                    any::<Relationship>().prop_map(Value::Relationship),
+                    any::<UnboundRelationship>().prop_map(Value::UnboundRelationship),
+                    any::<Path>().prop_map(Value::Path),
+                    (structure_signature(), vec(inner, 0..MAX_SIZE))
+                        .prop_map(|(signature, fields)| Value::Structure { signature, fields }),
+                ]
+            })
+            .boxed()
+    }
+}
+
+// Properties only hold scalars, so that generating a Value doesn't recurse forever
+impl Arbitrary for Node {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<i64>(), vec(any::<String>(), 0..3), map(scalar()))
+            .prop_map(|(node_identity, labels, properties)| Node {
+                node_identity,
+                labels,
+                properties,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Relationship {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            any::<i64>(),
+            any::<i64>(),
+            any::<i64>(),
+            any::<String>(),
+            map(scalar()),
+        )
+            .prop_map(
+                |(rel_identity, start_node_identity, end_node_identity, rel_type, properties)| {
+                    Relationship {
+                        rel_identity,
+                        start_node_identity,
+                        end_node_identity,
+                        rel_type,
+                        properties,
+                    }
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for UnboundRelationship {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<i64>(), any::<String>(), map(scalar()))
+            .prop_map(|(rel_identity, rel_type, properties)| UnboundRelationship {
+                rel_identity,
+                rel_type,
+                properties,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Path {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            vec(any::<Node>(), 1..4),
+            vec(any::<UnboundRelationship>(), 1..4),
+        )
+            .prop_flat_map(|(nodes, relationships)| {
+                // Each step is a 1-based relationship index, negative if the relationship is
+                // traversed backwards, followed by a node index
+                let steps = vec(
+                    (
+                        1..=relationships.len() as i64,
+                        any::<bool>(),
+                        0..nodes.len() as i64,
+                    ),
+                    0..4,
+                );
+                (Just(nodes), Just(relationships), steps)
+            })
+            .prop_map(|(nodes, relationships, steps)| Path {
+                nodes,
+                relationships,
+                sequence: steps
+                    .into_iter()
+                    .flat_map(|(rel, forward, node)| [if forward { rel } else { -rel }, node])
+                    .collect(),
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Duration {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (integer(), integer(), integer(), any::<i32>())
+            .prop_map(|(months, days, seconds, nanos)| Duration::new(months, days, seconds, nanos))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Point2D {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<i32>(), float(), float())
+            .prop_map(|(srid, x, y)| Point2D::new(srid, x, y))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Point3D {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<i32>(), float(), float(), float())
+            .prop_map(|(srid, x, y, z)| Point3D::new(srid, x, y, z))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Vector {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        let len = 0..20;
+        prop_oneof![
+            vec(any::<i8>(), len.clone()).prop_map(Vector::Int8),
+            vec(any::<i16>(), len.clone()).prop_map(Vector::Int16),
+            vec(any::<i32>(), len.clone()).prop_map(Vector::Int32),
+            vec(any::<i64>(), len.clone()).prop_map(Vector::Int64),
+            vec(float_32(), len.clone()).prop_map(Vector::Float32),
+            vec(float(), len).prop_map(Vector::Float64),
+        ]
+        .boxed()
+    }
+}
+
+macro_rules! impl_arbitrary_with_metadata {
+    ($($T:ident),+) => {
+        $(
+            impl Arbitrary for $T {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+                    map(any::<Value>()).prop_map($T::new).boxed()
+                }
+            }
+        )+
+    };
+}
+
+impl_arbitrary_with_metadata!(Begin, Discard, Failure, Hello, Pull, Success);
+
+impl Arbitrary for Init {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), map(any::<Value>()))
+            .prop_map(|(user_agent, auth_token)| Init::new(user_agent, auth_token))
+            .boxed()
+    }
+}
+
+impl Arbitrary for Run {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), map(any::<Value>()))
+            .prop_map(|(query, parameters)| Run::new(query, parameters))
+            .boxed()
+    }
+}
+
+impl Arbitrary for RunWithMetadata {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (any::<String>(), map(any::<Value>()), map(any::<Value>()))
+            .prop_map(|(statement, parameters, metadata)| {
+                RunWithMetadata::new(statement, parameters, metadata)
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Record {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        vec(any::<Value>(), 0..MAX_SIZE)
+            .prop_map(Record::new)
+            .boxed()
+    }
+}
+
+impl Arbitrary for Route {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            map(any::<Value>()),
+            vec(any::<String>(), 0..3),
+            any::<Option<String>>(),
+        )
+            .prop_map(|(context, bookmarks, database)| Route::new(context, bookmarks, database))
+            .boxed()
+    }
+}
+
+impl Arbitrary for RouteWithMetadata {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            map(any::<Value>()),
+            vec(any::<String>(), 0..3),
+            map(any::<Value>()),
+        )
+            .prop_map(|(context, bookmarks, metadata)| {
+                RouteWithMetadata::new(context, bookmarks, metadata)
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for Message {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            any::<Init>().prop_map(Message::Init),
+            any::<Run>().prop_map(Message::Run),
+            Just(Message::DiscardAll),
+            Just(Message::PullAll),
+            Just(Message::AckFailure),
+            Just(Message::Reset),
+            any::<Record>().prop_map(Message::Record),
+            any::<Success>().prop_map(Message::Success),
+            any::<Failure>().prop_map(Message::Failure),
+            Just(Message::Ignored),
+            any::<Hello>().prop_map(Message::Hello),
+            Just(Message::Goodbye),
+            any::<RunWithMetadata>().prop_map(Message::RunWithMetadata),
+            any::<Begin>().prop_map(Message::Begin),
+            Just(Message::Commit),
+            Just(Message::Rollback),
+            any::<Discard>().prop_map(Message::Discard),
+            any::<Pull>().prop_map(Message::Pull),
+            any::<Route>().prop_map(Message::Route),
+            any::<RouteWithMetadata>().prop_map(Message::RouteWithMetadata),
+        ]
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{io::Cursor, FutureExt};
+
+    use crate::{error::DeserializeResult, serialization::BoltValue, SerializeOptions};
+
+    use super::*;
+
+    // The boundaries between the tiny, small, medium, and large markers
+    const SIZES: [usize; 7] = [0, 15, 16, 255, 256, 65_535, 65_536];
+
+    fn options() -> SerializeOptions {
+        SerializeOptions::new()
+            .with_utc_date_times(true)
+            .with_vectors(true)
+    }
+
+    fn round_trip(value: Value, options: &SerializeOptions) -> DeserializeResult<Value> {
+        let bytes = value.serialize_with(options).unwrap();
+        let (value, remaining) = Value::deserialize(bytes)?;
+        assert_eq!(remaining.len(), 0);
+        Ok(value)
+    }
+
+    fn size_marker(size: usize, tiny: Option<u8>, small: u8, medium: u8, large: u8) -> u8 {
+        match size {
+            0..=15 if tiny.is_some() => tiny.unwrap() | size as u8,
+            0..=255 => small,
+            256..=65_535 => medium,
+            _ => large,
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn value_round_trip(value in any::<Value>()) {
+            prop_assert_eq!(round_trip(value.clone(), &options())?, value);
+        }
+
+        #[test]
+        fn message_round_trip(message in any::<Message>()) {
+            let chunks = message.clone().into_chunks_with(&options()).unwrap();
+            let stream = Cursor::new(chunks.concat());
+            let deserialized = Message::from_stream(stream).now_or_never().unwrap()?;
+            prop_assert_eq!(deserialized, message);
+        }
+
+        #[test]
+        fn legacy_date_time_round_trip(
+            date_time_offset in date_time_offset(),
+            date_time_zoned in date_time_zoned(),
+        ) {
+            let options = SerializeOptions::new();
+            prop_assert_eq!(
+                round_trip(Value::DateTimeOffset(date_time_offset), &options)?,
+                Value::DateTimeOffset(date_time_offset)
+            );
+            // Only the local date-time is sent, so the earlier instant is picked for overlaps
+            let earliest = date_time_zoned
+                .timezone()
+                .from_local_datetime(&date_time_zoned.naive_local())
+                .earliest()
+                .unwrap();
+            prop_assert_eq!(
+                round_trip(Value::DateTimeZoned(date_time_zoned), &options)?,
+                Value::DateTimeZoned(earliest)
+            );
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(16))]
+
+        #[test]
+        fn boundary_sizes(size in select(&SIZES[..])) {
+            let string = Value::String("a".repeat(size));
+            let marker = size_marker(
+                size,
+                Some(MARKER_TINY_STRING),
+                MARKER_SMALL_STRING,
+                MARKER_MEDIUM_STRING,
+                MARKER_LARGE_STRING,
+            );
+            prop_assert_eq!(string.marker().unwrap(), marker);
+            prop_assert_eq!(round_trip(string.clone(), &options())?, string);
+
+            let bytes = Value::Bytes(vec![0; size]);
+            let marker = size_marker(
+                size,
+                None,
+                MARKER_SMALL_BYTES,
+                MARKER_MEDIUM_BYTES,
+                MARKER_LARGE_BYTES,
+            );
+            prop_assert_eq!(bytes.marker().unwrap(), marker);
+            prop_assert_eq!(round_trip(bytes.clone(), &options())?, bytes);
+
+            let list = Value::List(vec![Value::Null; size]);
+            let marker = size_marker(
+                size,
+                Some(MARKER_TINY_LIST),
+                MARKER_SMALL_LIST,
+                MARKER_MEDIUM_LIST,
+                MARKER_LARGE_LIST,
+            );
+            prop_assert_eq!(list.marker().unwrap(), marker);
+            prop_assert_eq!(round_trip(list.clone(), &options())?, list);
+
+            let map = Value::Map((0..size).map(|i| (i.to_string(), Value::Null)).collect());
+            let marker = size_marker(
+                size,
+                Some(MARKER_TINY_MAP),
+                MARKER_SMALL_MAP,
+                MARKER_MEDIUM_MAP,
+                MARKER_LARGE_MAP,
+            );
+            prop_assert_eq!(map.marker().unwrap(), marker);
+            prop_assert_eq!(round_trip(map.clone(), &options())?, map);
+        }
+    }
+}
@@ -0,0 +1,768 @@
+//! Conversion of query results into Arrow [`RecordBatch`]es.
+//!
+//! Each entry in the `fields` list returned in the `SUCCESS` response to a `RUN` message becomes a
+//! column, and each [`Record`] becomes a row. The Arrow schema is inferred from the [`Value`]
+//! variants found in each column, unifying types across all rows:
+//!
+//! | [`Value`] variant | Arrow type |
+//! |-------------------|------------|
+//! | [`Null`](Value::Null) | [`Null`](DataType::Null), or a null in any other column type |
+//! | [`Boolean`](Value::Boolean) | [`Boolean`](DataType::Boolean) |
+//! | [`Integer`](Value::Integer) | [`Int64`](DataType::Int64), or [`Float64`](DataType::Float64) if mixed with floats |
+//! | [`Float`](Value::Float) | [`Float64`](DataType::Float64) |
+//! | [`Bytes`](Value::Bytes) | [`Binary`](DataType::Binary) |
+//! | [`String`](Value::String) | [`Utf8`](DataType::Utf8) |
+//! | [`List`](Value::List) | [`List`](DataType::List) of the unified element type |
+//! | [`Map`](Value::Map) | [`Struct`](DataType::Struct) with one field per key, sorted by name |
+//! | [`Node`](Value::Node) | `Struct<id, labels, properties>` |
+//! | [`Relationship`](Value::Relationship) | `Struct<id, start_node_id, end_node_id, type, properties>` |
+//! | [`UnboundRelationship`](Value::UnboundRelationship) | `Struct<id, type, properties>` |
+//! | [`Path`](Value::Path) | `Struct<nodes, relationships, sequence>` |
+//! | [`Date`](Value::Date) | [`Date32`](DataType::Date32) |
+//! | [`Time`](Value::Time) | `Struct<time: Time64(ns), offset_seconds: Int64>` |
+//! | [`DateTimeOffset`](Value::DateTimeOffset) | [`Timestamp`](DataType::Timestamp) (ns) with the offset as time zone |
+//! | [`DateTimeZoned`](Value::DateTimeZoned) | [`Timestamp`](DataType::Timestamp) (ns) with the time zone ID |
+//! | [`LocalTime`](Value::LocalTime) | [`Time64`](DataType::Time64) (ns) |
+//! | [`LocalDateTime`](Value::LocalDateTime) | [`Timestamp`](DataType::Timestamp) (ns) without time zone |
+//! | [`Duration`](Value::Duration) | [`Interval`](DataType::Interval) (month-day-nano) |
+//! | [`Point2D`](Value::Point2D) | `Struct<srid, x, y>` |
+//! | [`Point3D`](Value::Point3D) | `Struct<srid, x, y, z>` |
+//! | [`Vector`](Value::Vector) | [`List`](DataType::List) of [`Int64`](DataType::Int64) or [`Float64`](DataType::Float64) |
+//! | [`Structure`](Value::Structure) | `Struct<signature, fields>` |
+//!
+//! Durations map to month-day-nano intervals rather than Arrow durations, since months and days
+//! have no fixed length in seconds. Timestamps with differing offsets or time zones in the same
+//! column are stored as UTC. Columns holding types that cannot be unified (e.g. strings and
+//! integers) produce an error.
+//!
+//! # Example
+//! ```
+//! use bolt_proto::{arrow::RecordBatchBuilder, message::Record, Value};
+//!
+//! let mut builder = RecordBatchBuilder::new(vec!["name", "age"]);
+//! builder.push(Record::new(vec![Value::from("Alice"), Value::from(33)]))?;
+//! builder.push(Record::new(vec![Value::from("Bob"), Value::Null]))?;
+//!
+//! let batch = builder.finish()?;
+//! assert_eq!(batch.num_rows(), 2);
+//! assert_eq!(batch.num_columns(), 2);
+//! # Ok::<(), ::arrow::error::ArrowError>(())
+//! ```
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use ::arrow::{
+    array::*,
+    buffer::{NullBuffer, OffsetBuffer},
+    datatypes::{
+        DataType, Field, FieldRef, Fields, IntervalMonthDayNano, IntervalUnit, Schema, SchemaRef,
+        TimeUnit,
+    },
+    error::{ArrowError, Result as ArrowResult},
+    record_batch::{RecordBatch, RecordBatchOptions},
+};
+use chrono::{NaiveDate, Timelike};
+
+use crate::{message::Record, value::ValueMap, Value};
+
+const UTC_OFFSET: &str = "+00:00";
+
+static NULL: Value = Value::Null;
+
+/// Accumulates [`Record`]s and converts them into [`RecordBatch`]es with a schema inferred from
+/// all accumulated rows.
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+#[derive(Debug, Clone)]
+pub struct RecordBatchBuilder {
+    fields: Vec<String>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl RecordBatchBuilder {
+    /// Create a new builder for the given result columns, typically taken from the `fields` entry
+    /// of the `SUCCESS` response to a `RUN` message.
+    pub fn new(fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            fields: fields.into_iter().map(Into::into).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row to the builder. Fails if the record does not have one value per column.
+    pub fn push(&mut self, record: Record) -> ArrowResult<()> {
+        if record.fields.len() != self.fields.len() {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "record has {} values, but {} columns were provided",
+                record.fields.len(),
+                self.fields.len()
+            )));
+        }
+
+        self.rows
+            .push(record.fields.into_iter().map(normalize).collect());
+        Ok(())
+    }
+
+    /// Get the number of rows added so far.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if no rows have been added.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Infer an Arrow schema from the rows added so far. Columns containing only nulls (or no rows
+    /// at all) have the [`Null`](DataType::Null) type.
+    pub fn schema(&self) -> ArrowResult<Schema> {
+        let mut columns = Vec::with_capacity(self.fields.len());
+        for (index, name) in self.fields.iter().enumerate() {
+            let mut data_type = DataType::Null;
+            for row in &self.rows {
+                data_type = unify(data_type, infer_type(&row[index])?)
+                    .map_err(|err| ArrowError::SchemaError(format!("column `{name}`: {err}")))?;
+            }
+            columns.push(Field::new(name, data_type, true));
+        }
+        Ok(Schema::new(columns))
+    }
+
+    /// Convert all added rows into a single [`RecordBatch`].
+    pub fn finish(self) -> ArrowResult<RecordBatch> {
+        let schema = Arc::new(self.schema()?);
+        build_record_batch(schema, &self.rows)
+    }
+
+    /// Convert all added rows into [`RecordBatch`]es of at most `batch_size` rows each. All
+    /// batches share the same schema.
+    pub fn finish_batches(self, batch_size: usize) -> ArrowResult<Vec<RecordBatch>> {
+        if batch_size == 0 {
+            return Err(ArrowError::InvalidArgumentError(String::from(
+                "batch size must be greater than zero",
+            )));
+        }
+
+        let schema = Arc::new(self.schema()?);
+        self.rows
+            .chunks(batch_size)
+            .map(|rows| build_record_batch(schema.clone(), rows))
+            .collect()
+    }
+}
+
+impl Extend<Record> for RecordBatchBuilder {
+    /// Add rows to the builder.
+    ///
+    /// # Panics
+    /// Panics if a record does not have one value per column. Use [`RecordBatchBuilder::push`] to
+    /// handle this case gracefully.
+    fn extend<T: IntoIterator<Item = Record>>(&mut self, records: T) {
+        for record in records {
+            self.push(record).unwrap();
+        }
+    }
+}
+
+/// Convert the given records into [`RecordBatch`]es of at most `batch_size` rows each. See
+/// [`RecordBatchBuilder`] for details.
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub fn record_batches(
+    fields: impl IntoIterator<Item = impl Into<String>>,
+    records: impl IntoIterator<Item = Record>,
+    batch_size: usize,
+) -> ArrowResult<Vec<RecordBatch>> {
+    let mut builder = RecordBatchBuilder::new(fields);
+    for record in records {
+        builder.push(record)?;
+    }
+    builder.finish_batches(batch_size)
+}
+
+fn build_record_batch(schema: SchemaRef, rows: &[Vec<Value>]) -> ArrowResult<RecordBatch> {
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let column: Vec<&Value> = rows.iter().map(|row| &row[index]).collect();
+            build_array(field.data_type(), &column)
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    RecordBatch::try_new_with_options(
+        schema,
+        columns,
+        &RecordBatchOptions::new().with_row_count(Some(rows.len())),
+    )
+}
+
+fn structure(entries: Vec<(&str, Value)>) -> Value {
+    Value::Map(
+        entries
+            .into_iter()
+            .map(|(key, value)| (String::from(key), value))
+            .collect(),
+    )
+}
+
+fn normalize_map(map: ValueMap) -> Value {
+    Value::Map(map.into_iter().map(|(k, v)| (k, normalize(v))).collect())
+}
+
+/// Rewrite graph structures, points, offset times, and other structures as maps, and vectors as
+/// lists, so the rest of the conversion only
+/// has to deal with lists, maps, and scalar values.
+fn normalize(value: Value) -> Value {
+    match value {
+        Value::List(list) => Value::List(list.into_iter().map(normalize).collect()),
+        Value::Map(map) => normalize_map(map),
+        Value::Vector(vector) => Value::List(vector.to_values()),
+        Value::Structure { signature, fields } => structure(vec![
+            ("signature", Value::Integer(signature.into())),
+            ("fields", normalize(Value::List(fields))),
+        ]),
+        Value::Node(node) => structure(vec![
+            ("id", Value::Integer(node.node_identity)),
+            ("labels", Value::from(node.labels)),
+            ("properties", normalize_map(node.properties)),
+        ]),
+        Value::Relationship(rel) => structure(vec![
+            ("id", Value::Integer(rel.rel_identity)),
+            ("start_node_id", Value::Integer(rel.start_node_identity)),
+            ("end_node_id", Value::Integer(rel.end_node_identity)),
+            ("type", Value::String(rel.rel_type)),
+            ("properties", normalize_map(rel.properties)),
+        ]),
+        Value::UnboundRelationship(rel) => structure(vec![
+            ("id", Value::Integer(rel.rel_identity)),
+            ("type", Value::String(rel.rel_type)),
+            ("properties", normalize_map(rel.properties)),
+        ]),
+        Value::Path(path) => structure(vec![
+            (
+                "nodes",
+                normalize(Value::List(
+                    path.nodes.into_iter().map(Value::Node).collect(),
+                )),
+            ),
+            (
+                "relationships",
+                normalize(Value::List(
+                    path.relationships
+                        .into_iter()
+                        .map(Value::UnboundRelationship)
+                        .collect(),
+                )),
+            ),
+            ("sequence", Value::from(path.sequence)),
+        ]),
+        Value::Time(time, offset) => structure(vec![
+            ("time", Value::LocalTime(time)),
+            ("offset_seconds", Value::from(offset.local_minus_utc())),
+        ]),
+        Value::Point2D(point) => structure(vec![
+            ("srid", Value::from(point.srid)),
+            ("x", Value::Float(point.x)),
+            ("y", Value::Float(point.y)),
+        ]),
+        Value::Point3D(point) => structure(vec![
+            ("srid", Value::from(point.srid)),
+            ("x", Value::Float(point.x)),
+            ("y", Value::Float(point.y)),
+            ("z", Value::Float(point.z)),
+        ]),
+        other => other,
+    }
+}
+
+fn list_type(item: DataType) -> DataType {
+    DataType::List(Arc::new(Field::new("item", item, true)))
+}
+
+fn infer_type(value: &Value) -> ArrowResult<DataType> {
+    let data_type = match value {
+        Value::Null => DataType::Null,
+        Value::Boolean(_) => DataType::Boolean,
+        Value::Integer(_) => DataType::Int64,
+        Value::Float(_) => DataType::Float64,
+        Value::Bytes(_) => DataType::Binary,
+        Value::String(_) => DataType::Utf8,
+        Value::List(list) => {
+            let mut item = DataType::Null;
+            for value in list {
+                item = unify(item, infer_type(value)?).map_err(ArrowError::SchemaError)?;
+            }
+            list_type(item)
+        }
+        Value::Map(map) => {
+            let fields: BTreeMap<&String, DataType> = map
+                .iter()
+                .map(|(key, value)| Ok((key, infer_type(value)?)))
+                .collect::<ArrowResult<_>>()?;
+            DataType::Struct(
+                fields
+                    .into_iter()
+                    .map(|(key, data_type)| Field::new(key, data_type, true))
+                    .collect(),
+            )
+        }
+        Value::Date(_) => DataType::Date32,
+        Value::LocalTime(_) => DataType::Time64(TimeUnit::Nanosecond),
+        Value::LocalDateTime(_) => DataType::Timestamp(TimeUnit::Nanosecond, None),
+        Value::DateTimeOffset(date_time) => DataType::Timestamp(
+            TimeUnit::Nanosecond,
+            Some(date_time.offset().to_string().into()),
+        ),
+        Value::DateTimeZoned(date_time) => DataType::Timestamp(
+            TimeUnit::Nanosecond,
+            Some(date_time.timezone().name().into()),
+        ),
+        Value::Duration(_) => DataType::Interval(IntervalUnit::MonthDayNano),
+        // Removed by normalize()
+        Value::Node(_)
+        | Value::Relationship(_)
+        | Value::Path(_)
+        | Value::UnboundRelationship(_)
+        | Value::Time(_, _)
+        | Value::Point2D(_)
+        | Value::Point3D(_)
+        | Value::Vector(_)
+        | Value::Structure { .. } => unreachable!(),
+    };
+    Ok(data_type)
+}
+
+/// Find a type that can hold values of both given types.
+fn unify(left: DataType, right: DataType) -> Result<DataType, String> {
+    match (left, right) {
+        (DataType::Null, other) | (other, DataType::Null) => Ok(other),
+        (left, right) if left == right => Ok(left),
+        (DataType::Int64, DataType::Float64) | (DataType::Float64, DataType::Int64) => {
+            Ok(DataType::Float64)
+        }
+        (DataType::Timestamp(unit, Some(_)), DataType::Timestamp(_, Some(_))) => {
+            Ok(DataType::Timestamp(unit, Some(UTC_OFFSET.into())))
+        }
+        (DataType::List(left), DataType::List(right)) => Ok(list_type(unify(
+            left.data_type().clone(),
+            right.data_type().clone(),
+        )?)),
+        (DataType::Struct(left), DataType::Struct(right)) => {
+            let mut fields: BTreeMap<String, DataType> = left
+                .iter()
+                .map(|field| (field.name().clone(), field.data_type().clone()))
+                .collect();
+            for field in right.iter() {
+                let data_type = match fields.remove(field.name()) {
+                    Some(existing) => unify(existing, field.data_type().clone())?,
+                    None => field.data_type().clone(),
+                };
+                fields.insert(field.name().clone(), data_type);
+            }
+            Ok(DataType::Struct(
+                fields
+                    .into_iter()
+                    .map(|(name, data_type)| Field::new(name, data_type, true))
+                    .collect(),
+            ))
+        }
+        (left, right) => Err(format!("cannot unify types {left} and {right}")),
+    }
+}
+
+fn mismatch(data_type: &DataType, value: &Value) -> ArrowError {
+    ArrowError::CastError(format!("cannot store {value:?} as {data_type}"))
+}
+
+fn out_of_range(data_type: &DataType, value: &Value) -> ArrowError {
+    ArrowError::CastError(format!("{value:?} is out of range for {data_type}"))
+}
+
+fn null_buffer(validity: Vec<bool>) -> Option<NullBuffer> {
+    if validity.iter().all(|&valid| valid) {
+        None
+    } else {
+        Some(NullBuffer::from(validity))
+    }
+}
+
+/// Convert non-null values with the provided function, which returns `None` if a value has the
+/// wrong type.
+fn convert<'a, T, A: FromIterator<Option<T>>>(
+    data_type: &DataType,
+    values: &[&'a Value],
+    convert: impl Fn(&'a Value) -> Option<ArrowResult<T>>,
+) -> ArrowResult<A> {
+    values
+        .iter()
+        .map(|&value| match value {
+            Value::Null => Ok(None),
+            value => match convert(value) {
+                Some(result) => result.map(Some),
+                None => Err(mismatch(data_type, value)),
+            },
+        })
+        .collect()
+}
+
+fn build_array(data_type: &DataType, values: &[&Value]) -> ArrowResult<ArrayRef> {
+    let array: ArrayRef = match data_type {
+        DataType::Null => Arc::new(NullArray::new(values.len())),
+        DataType::Boolean => Arc::new(convert::<_, BooleanArray>(
+            data_type,
+            values,
+            |v| match v {
+                Value::Boolean(boolean) => Some(Ok(*boolean)),
+                _ => None,
+            },
+        )?),
+        DataType::Int64 => Arc::new(convert::<_, Int64Array>(data_type, values, |v| match v {
+            Value::Integer(integer) => Some(Ok(*integer)),
+            _ => None,
+        })?),
+        DataType::Float64 => Arc::new(convert::<_, Float64Array>(
+            data_type,
+            values,
+            |v| match v {
+                Value::Integer(integer) => Some(Ok(*integer as f64)),
+                Value::Float(float) => Some(Ok(*float)),
+                _ => None,
+            },
+        )?),
+        DataType::Binary => Arc::new(convert::<_, BinaryArray>(data_type, values, |v| match v {
+            Value::Bytes(bytes) => Some(Ok(bytes.as_slice())),
+            _ => None,
+        })?),
+        DataType::Utf8 => Arc::new(convert::<_, StringArray>(data_type, values, |v| match v {
+            Value::String(string) => Some(Ok(string.as_str())),
+            _ => None,
+        })?),
+        DataType::Date32 => Arc::new(convert::<_, Date32Array>(data_type, values, |v| match v {
+            Value::Date(date) => Some(
+                i32::try_from(
+                    date.signed_duration_since(NaiveDate::from_ymd_opt(1970, 1, 1).unwrap())
+                        .num_days(),
+                )
+                .map_err(|_| out_of_range(data_type, v)),
+            ),
+            _ => None,
+        })?),
+        DataType::Time64(TimeUnit::Nanosecond) => Arc::new(convert::<_, Time64NanosecondArray>(
+            data_type,
+            values,
+            |v| match v {
+                Value::LocalTime(time) => Some(Ok(i64::from(time.num_seconds_from_midnight())
+                    * 1_000_000_000
+                    + i64::from(time.nanosecond()))),
+                _ => None,
+            },
+        )?),
+        DataType::Timestamp(TimeUnit::Nanosecond, timezone) => {
+            let nanos = |value: &Value, nanos: Option<i64>| {
+                Some(nanos.ok_or_else(|| out_of_range(data_type, value)))
+            };
+            let array = convert::<_, TimestampNanosecondArray>(data_type, values, |v| {
+                match (v, timezone) {
+                    (Value::LocalDateTime(date_time), None) => {
+                        nanos(v, date_time.and_utc().timestamp_nanos_opt())
+                    }
+                    (Value::DateTimeOffset(date_time), Some(_)) => {
+                        nanos(v, date_time.timestamp_nanos_opt())
+                    }
+                    (Value::DateTimeZoned(date_time), Some(_)) => {
+                        nanos(v, date_time.timestamp_nanos_opt())
+                    }
+                    _ => None,
+                }
+            })?;
+            Arc::new(array.with_timezone_opt(timezone.clone()))
+        }
+        DataType::Interval(IntervalUnit::MonthDayNano) => {
+            Arc::new(convert::<_, IntervalMonthDayNanoArray>(
+                data_type,
+                values,
+                |v| match v {
+                    Value::Duration(duration) => Some(
+                        (|| {
+                            Some(IntervalMonthDayNano::new(
+                                i32::try_from(duration.months).ok()?,
+                                i32::try_from(duration.days).ok()?,
+                                duration
+                                    .seconds
+                                    .checked_mul(1_000_000_000)?
+                                    .checked_add(i64::from(duration.nanos))?,
+                            ))
+                        })()
+                        .ok_or_else(|| out_of_range(data_type, v)),
+                    ),
+                    _ => None,
+                },
+            )?)
+        }
+        DataType::List(field) => Arc::new(build_list(data_type, field, values)?),
+        DataType::Struct(fields) => Arc::new(build_struct(data_type, fields, values)?),
+        other => {
+            return Err(ArrowError::NotYetImplemented(format!(
+                "conversion to {other} is not supported"
+            )))
+        }
+    };
+    Ok(array)
+}
+
+fn build_list(data_type: &DataType, field: &FieldRef, values: &[&Value]) -> ArrowResult<ListArray> {
+    let mut items = Vec::new();
+    let mut lengths = Vec::with_capacity(values.len());
+    let mut validity = Vec::with_capacity(values.len());
+    for &value in values {
+        match value {
+            Value::List(list) => {
+                items.extend(list.iter());
+                lengths.push(list.len());
+                validity.push(true);
+            }
+            Value::Null => {
+                lengths.push(0);
+                validity.push(false);
+            }
+            other => return Err(mismatch(data_type, other)),
+        }
+    }
+
+    ListArray::try_new(
+        field.clone(),
+        OffsetBuffer::from_lengths(lengths),
+        build_array(field.data_type(), &items)?,
+        null_buffer(validity),
+    )
+}
+
+fn build_struct(
+    data_type: &DataType,
+    fields: &Fields,
+    values: &[&Value],
+) -> ArrowResult<StructArray> {
+    let validity = values
+        .iter()
+        .map(|&value| match value {
+            Value::Map(_) => Ok(true),
+            Value::Null => Ok(false),
+            other => Err(mismatch(data_type, other)),
+        })
+        .collect::<ArrowResult<Vec<bool>>>()?;
+
+    let children = fields
+        .iter()
+        .map(|field| {
+            let column: Vec<&Value> = values
+                .iter()
+                .map(|&value| match value {
+                    Value::Map(map) => map.get(field.name()).unwrap_or(&NULL),
+                    _ => &NULL,
+                })
+                .collect();
+            build_array(field.data_type(), &column)
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    StructArray::try_new_with_length(
+        fields.clone(),
+        children,
+        null_buffer(validity),
+        values.len(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{FixedOffset, NaiveTime, TimeZone};
+
+    use crate::value::*;
+
+    use super::*;
+
+    #[test]
+    fn scalar_columns() {
+        let batch = record_batches(
+            vec!["int", "float", "string", "empty"],
+            vec![
+                Record::new(vec![
+                    Value::from(1),
+                    Value::from(1),
+                    Value::from("a"),
+                    Value::Null,
+                ]),
+                Record::new(vec![
+                    Value::Null,
+                    Value::from(2.5),
+                    Value::from("b"),
+                    Value::Null,
+                ]),
+            ],
+            10,
+        )
+        .unwrap()
+        .remove(0);
+
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Int64);
+        assert_eq!(schema.field(1).data_type(), &DataType::Float64);
+        assert_eq!(schema.field(2).data_type(), &DataType::Utf8);
+        assert_eq!(schema.field(3).data_type(), &DataType::Null);
+
+        let ints = batch
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int64Type>();
+        assert_eq!(ints.value(0), 1);
+        assert!(ints.is_null(1));
+        let floats = batch
+            .column(1)
+            .as_primitive::<arrow::datatypes::Float64Type>();
+        assert_eq!(floats.values(), &[1.0, 2.5]);
+    }
+
+    #[test]
+    fn nested_columns() {
+        let node = Node::new(
+            1,
+            vec![String::from("Person")],
+            HashMap::from([(String::from("name"), Value::from("Alice"))]),
+        );
+        let other_node = Node::new(
+            2,
+            vec![],
+            HashMap::from([(String::from("age"), Value::from(40))]),
+        );
+        let mut builder = RecordBatchBuilder::new(vec!["n", "list"]);
+        builder
+            .push(Record::new(vec![
+                Value::from(node),
+                Value::from(vec![Value::from(1), Value::Null]),
+            ]))
+            .unwrap();
+        builder
+            .push(Record::new(vec![Value::from(other_node), Value::Null]))
+            .unwrap();
+
+        let schema = builder.schema().unwrap();
+        let DataType::Struct(node_fields) = schema.field(0).data_type() else {
+            panic!("expected struct, got {}", schema.field(0).data_type());
+        };
+        let names: Vec<&str> = node_fields.iter().map(|f| f.name().as_str()).collect();
+        assert_eq!(names, vec!["id", "labels", "properties"]);
+        let DataType::Struct(properties) = node_fields[2].data_type() else {
+            panic!("expected struct properties");
+        };
+        assert_eq!(properties.len(), 2);
+        assert_eq!(schema.field(1).data_type(), &list_type(DataType::Int64));
+
+        let batch = builder.finish().unwrap();
+        let nodes = batch.column(0).as_struct();
+        let ids = nodes
+            .column(0)
+            .as_primitive::<arrow::datatypes::Int64Type>();
+        assert_eq!(ids.values(), &[1, 2]);
+        let lists = batch.column(1).as_list::<i32>();
+        assert_eq!(lists.value_length(0), 2);
+        assert!(lists.is_null(1));
+    }
+
+    #[test]
+    fn temporal_columns() {
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let date_time = offset.with_ymd_and_hms(2020, 1, 1, 12, 0, 0).unwrap();
+        let batch = record_batches(
+            vec!["date", "time", "offset", "zoned", "duration"],
+            vec![Record::new(vec![
+                Value::from(NaiveDate::from_ymd_opt(1970, 1, 11).unwrap()),
+                Value::from(NaiveTime::from_hms_opt(0, 0, 1).unwrap()),
+                Value::from(date_time),
+                Value::from((date_time.naive_utc(), chrono_tz::Europe::Paris)),
+                Value::from(Duration::new(1, 2, 3, 4)),
+            ])],
+            1,
+        )
+        .unwrap()
+        .remove(0);
+
+        let schema = batch.schema();
+        assert_eq!(schema.field(0).data_type(), &DataType::Date32);
+        assert_eq!(
+            schema.field(2).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, Some("+01:00".into()))
+        );
+        assert_eq!(
+            schema.field(3).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, Some("Europe/Paris".into()))
+        );
+
+        let dates = batch
+            .column(0)
+            .as_primitive::<arrow::datatypes::Date32Type>();
+        assert_eq!(dates.value(0), 10);
+        let times = batch
+            .column(1)
+            .as_primitive::<arrow::datatypes::Time64NanosecondType>();
+        assert_eq!(times.value(0), 1_000_000_000);
+        let timestamps = batch
+            .column(2)
+            .as_primitive::<arrow::datatypes::TimestampNanosecondType>();
+        assert_eq!(
+            timestamps.value(0),
+            date_time.timestamp_nanos_opt().unwrap()
+        );
+        let durations = batch
+            .column(4)
+            .as_primitive::<arrow::datatypes::IntervalMonthDayNanoType>();
+        assert_eq!(
+            durations.value(0),
+            IntervalMonthDayNano::new(1, 2, 3_000_000_004)
+        );
+    }
+
+    #[test]
+    fn mixed_offsets_become_utc() {
+        let first = FixedOffset::east_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2020, 1, 1, 0, 0, 0)
+            .unwrap();
+        let second = FixedOffset::west_opt(3600)
+            .unwrap()
+            .with_ymd_and_hms(2020, 1, 1, 0, 0, 0)
+            .unwrap();
+        let mut builder = RecordBatchBuilder::new(vec!["t"]);
+        builder.extend(vec![
+            Record::new(vec![Value::from(first)]),
+            Record::new(vec![Value::from(second)]),
+        ]);
+        assert_eq!(
+            builder.schema().unwrap().field(0).data_type(),
+            &DataType::Timestamp(TimeUnit::Nanosecond, Some(UTC_OFFSET.into()))
+        );
+    }
+
+    #[test]
+    fn incompatible_types_fail() {
+        let mut builder = RecordBatchBuilder::new(vec!["x"]);
+        builder.push(Record::new(vec![Value::from(1)])).unwrap();
+        builder.push(Record::new(vec![Value::from("1")])).unwrap();
+        assert!(matches!(builder.schema(), Err(ArrowError::SchemaError(_))));
+
+        let mut builder = RecordBatchBuilder::new(vec!["x", "y"]);
+        assert!(builder.push(Record::new(vec![Value::from(1)])).is_err());
+    }
+
+    #[test]
+    fn batch_sizes() {
+        let records = (0..5).map(|i| Record::new(vec![Value::from(i)]));
+        let batches = record_batches(vec!["i"], records, 2).unwrap();
+        let sizes: Vec<usize> = batches.iter().map(RecordBatch::num_rows).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+        assert!(batches.iter().all(|b| b.schema() == batches[0].schema()));
+
+        assert!(record_batches(Vec::<String>::new(), vec![], 0).is_err());
+    }
+}
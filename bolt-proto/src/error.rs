@@ -1,11 +1,13 @@
 use thiserror::Error;
 
-use crate::{Message, Value};
+use crate::{value::Duration, Message, Value};
 
 pub type Result<T> = std::result::Result<T, Error>;
 pub type ConversionResult<T> = std::result::Result<T, ConversionError>;
 pub type SerializeResult<T> = std::result::Result<T, SerializationError>;
 pub type DeserializeResult<T> = std::result::Result<T, DeserializationError>;
+pub type PathResult<T> = std::result::Result<T, PathError>;
+pub type ParseResult<T> = std::result::Result<T, ParseError>;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -15,6 +17,10 @@ pub enum Error {
     SerializationError(#[from] SerializationError),
     #[error(transparent)]
     DeserializationError(#[from] DeserializationError),
+    #[error(transparent)]
+    PathError(#[from] PathError),
+    #[error(transparent)]
+    ParseError(#[from] ParseError),
 }
 
 #[derive(Debug, Error)]
@@ -23,16 +29,52 @@ pub enum ConversionError {
     FromValue(Value),
     #[error("invalid conversion from message {0:?}")]
     FromMessage(Message),
+    #[error("cannot convert a duration with {0} months to a fixed-length duration")]
+    DurationHasMonths(i64),
+    #[error("duration out of range: {0}")]
+    DurationOutOfRange(Duration),
+    #[error("cannot convert GeoJSON geometry to a point: {0}")]
+    UnsupportedGeometry(String),
     #[error(transparent)]
     TryFromIntError(#[from] std::num::TryFromIntError),
     #[error(transparent)]
     Infallible(#[from] std::convert::Infallible),
 }
 
+#[derive(Debug, Error)]
+pub enum PathError {
+    #[error("path has no nodes")]
+    NoNodes,
+    #[error("path sequence has odd length {0}")]
+    OddSequenceLength(usize),
+    #[error("path sequence refers to invalid node index {0}")]
+    InvalidNodeIndex(i64),
+    #[error("path sequence refers to invalid relationship index {0}")]
+    InvalidRelationshipIndex(i64),
+}
+
+#[derive(Debug, Error)]
+pub enum ParseError {
+    #[error("unexpected end of input")]
+    UnexpectedEnd,
+    #[error("unexpected character {found:?} at position {position}")]
+    UnexpectedCharacter { found: char, position: usize },
+    #[error("invalid {kind} literal: {literal}")]
+    InvalidLiteral { kind: &'static str, literal: String },
+    #[error("unknown function: {0}")]
+    UnknownFunction(String),
+    #[error("literal exceeds the maximum nesting depth of {0}")]
+    MaxDepthExceeded(usize),
+}
+
 #[derive(Debug, Error)]
 pub enum SerializationError {
     #[error("value too large (size: {0})")]
     ValueTooLarge(usize),
+    #[error("vectors are not supported by this protocol version")]
+    VectorsNotSupported,
+    #[error("structure signature {0:x} is reserved for a built-in value type")]
+    ReservedSignature(u8),
 }
 
 #[derive(Debug, Error)]
@@ -45,11 +87,25 @@ pub enum DeserializationError {
     InvalidSignatureByte(u8),
     #[error("invalid size ({size} fields) for signature byte {signature:x}")]
     InvalidSize { size: usize, signature: u8 },
+    #[error("invalid vector: {0}")]
+    InvalidVector(&'static str),
+    #[error("invalid temporal value: {0}")]
+    InvalidTemporal(&'static str),
+    #[error("invalid timezone: {0}")]
+    InvalidTimezone(String),
+    #[error("message has {0} unexpected trailing bytes")]
+    TrailingBytes(usize),
+    #[error("message exceeds the maximum size of {0} bytes")]
+    MessageTooLarge(usize),
+    #[error("value exceeds the maximum nesting depth of {0}")]
+    MaxDepthExceeded(usize),
     #[error("string deserialization failed: {0}")]
     InvalidUTF8(#[from] std::string::FromUtf8Error),
     #[error(transparent)]
     ConversionError(#[from] ConversionError),
     #[error(transparent)]
+    UnexpectedEnd(#[from] bytes::TryGetError),
+    #[error(transparent)]
     IoError(#[from] std::io::Error),
     #[error(transparent)]
     Infallible(#[from] std::convert::Infallible),
@@ -0,0 +1,346 @@
+//! Conversion of graph structures in query results into [`petgraph`] graphs.
+//!
+//! A [`GraphCollector`] gathers [`Node`]s, [`Relationship`]s, and [`Path`]s from [`Value`]s or
+//! whole [`Record`]s, deduplicating them by identity, and converts them into a [`Graph`] or
+//! [`StableGraph`]. Node weights are [`Node`]s and edge weights are [`Relationship`]s, so edges
+//! carry the relationship type and properties. Relationships from paths are bound to their start
+//! and end nodes using [`Path::segments`].
+//!
+//! # Example
+//! ```
+//! use std::collections::HashMap;
+//!
+//! use bolt_proto::{graph::GraphCollector, message::Record, value::*};
+//! # use bolt_proto::error::PathError;
+//!
+//! let alice = Node::new(1, vec![String::from("Person")], HashMap::<String, Value>::new());
+//! let bob = Node::new(2, vec![String::from("Person")], HashMap::<String, Value>::new());
+//! let knows = Relationship::new(3, 1, 2, String::from("KNOWS"), HashMap::<String, Value>::new());
+//!
+//! let mut collector = GraphCollector::new();
+//! collector.add_record(Record::new(vec![Value::from(alice.clone()), Value::from(knows)]))?;
+//! collector.add_record(Record::new(vec![Value::from(alice), Value::from(bob)]))?;
+//!
+//! let graph = collector.into_graph();
+//! assert_eq!(graph.node_count(), 2);
+//! assert_eq!(graph.edge_count(), 1);
+//! # Ok::<(), PathError>(())
+//! ```
+
+use std::collections::HashMap;
+
+use petgraph::{
+    graph::{EdgeIndex, Graph, NodeIndex},
+    stable_graph::StableGraph,
+};
+
+use crate::{
+    error::{PathError, PathResult},
+    message::Record,
+    value::{Node, Path, Relationship},
+    Value,
+};
+
+/// Collects graph structures from query results, deduplicated by identity.
+///
+/// When the same node or relationship is added more than once, the first occurrence is kept.
+/// Relationships whose start or end node was never added get a placeholder node with no labels or
+/// properties.
+#[cfg_attr(docsrs, doc(cfg(feature = "petgraph")))]
+#[derive(Debug, Clone, Default)]
+pub struct GraphCollector {
+    nodes: Vec<Node>,
+    node_indices: HashMap<i64, usize>,
+    relationships: Vec<Relationship>,
+    relationship_indices: HashMap<i64, usize>,
+}
+
+impl GraphCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node, unless a node with the same identity was already added.
+    pub fn add_node(&mut self, node: Node) {
+        if !self.node_indices.contains_key(&node.node_identity) {
+            self.node_indices
+                .insert(node.node_identity, self.nodes.len());
+            self.nodes.push(node);
+        }
+    }
+
+    /// Add a relationship, unless a relationship with the same identity was already added.
+    pub fn add_relationship(&mut self, relationship: Relationship) {
+        if !self
+            .relationship_indices
+            .contains_key(&relationship.rel_identity)
+        {
+            self.relationship_indices
+                .insert(relationship.rel_identity, self.relationships.len());
+            self.relationships.push(relationship);
+        }
+    }
+
+    /// Add all nodes and relationships in a path. Fails if the path's sequence refers to nodes or
+    /// relationships that are not in the path.
+    pub fn add_path(&mut self, path: Path) -> PathResult<()> {
+        let relationships: Vec<Relationship> = path
+            .segments()?
+            .into_iter()
+            .map(|(_, relationship, _)| relationship)
+            .collect();
+
+        for node in path.nodes {
+            self.add_node(node);
+        }
+        for relationship in relationships {
+            self.add_relationship(relationship);
+        }
+        Ok(())
+    }
+
+    /// Add all nodes, relationships, and paths found in a value, including those nested in lists
+    /// and maps. Other values are ignored.
+    pub fn add_value(&mut self, value: Value) -> PathResult<()> {
+        match value {
+            Value::Node(node) => self.add_node(node),
+            Value::Relationship(relationship) => self.add_relationship(relationship),
+            Value::Path(path) => self.add_path(path)?,
+            Value::List(list) => {
+                for value in list {
+                    self.add_value(value)?;
+                }
+            }
+            Value::Map(map) => {
+                for value in map.into_values() {
+                    self.add_value(value)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Add all nodes, relationships, and paths found in a record's fields.
+    pub fn add_record(&mut self, record: Record) -> PathResult<()> {
+        for value in record.fields {
+            self.add_value(value)?;
+        }
+        Ok(())
+    }
+
+    /// Get the index that the node with the given identity will have in the graphs produced by
+    /// this collector, if it was added.
+    pub fn node_index(&self, node_identity: i64) -> Option<NodeIndex> {
+        self.node_indices
+            .get(&node_identity)
+            .map(|&index| NodeIndex::new(index))
+    }
+
+    /// Get the index that the relationship with the given identity will have in the graphs
+    /// produced by this collector, if it was added.
+    pub fn edge_index(&self, rel_identity: i64) -> Option<EdgeIndex> {
+        self.relationship_indices
+            .get(&rel_identity)
+            .map(|&index| EdgeIndex::new(index))
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn relationships(&self) -> &[Relationship] {
+        &self.relationships
+    }
+
+    /// Convert the collected nodes and relationships into a [`Graph`]. Nodes and edges are
+    /// inserted in the order they were first added.
+    pub fn into_graph(self) -> Graph<Node, Relationship> {
+        let (nodes, edges) = self.into_parts();
+        let mut graph = Graph::with_capacity(nodes.len(), edges.len());
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for (start, end, relationship) in edges {
+            graph.add_edge(start, end, relationship);
+        }
+        graph
+    }
+
+    /// Convert the collected nodes and relationships into a [`StableGraph`]. Nodes and edges are
+    /// inserted in the order they were first added.
+    pub fn into_stable_graph(self) -> StableGraph<Node, Relationship> {
+        let (nodes, edges) = self.into_parts();
+        let mut graph = StableGraph::with_capacity(nodes.len(), edges.len());
+        for node in nodes {
+            graph.add_node(node);
+        }
+        for (start, end, relationship) in edges {
+            graph.add_edge(start, end, relationship);
+        }
+        graph
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn into_parts(mut self) -> (Vec<Node>, Vec<(NodeIndex, NodeIndex, Relationship)>) {
+        // Make sure all relationship endpoints exist before resolving node indices
+        let endpoints: Vec<i64> = self
+            .relationships
+            .iter()
+            .flat_map(|rel| [rel.start_node_identity, rel.end_node_identity])
+            .collect();
+        for identity in endpoints {
+            self.add_node(Node::new(identity, vec![], HashMap::<String, Value>::new()));
+        }
+
+        let edges = self
+            .relationships
+            .into_iter()
+            .map(|relationship| {
+                let start = NodeIndex::new(self.node_indices[&relationship.start_node_identity]);
+                let end = NodeIndex::new(self.node_indices[&relationship.end_node_identity]);
+                (start, end, relationship)
+            })
+            .collect();
+        (self.nodes, edges)
+    }
+}
+
+impl TryFrom<Path> for Graph<Node, Relationship> {
+    type Error = PathError;
+
+    fn try_from(path: Path) -> PathResult<Self> {
+        let mut collector = GraphCollector::new();
+        collector.add_path(path)?;
+        Ok(collector.into_graph())
+    }
+}
+
+impl TryFrom<Path> for StableGraph<Node, Relationship> {
+    type Error = PathError;
+
+    fn try_from(path: Path) -> PathResult<Self> {
+        let mut collector = GraphCollector::new();
+        collector.add_path(path)?;
+        Ok(collector.into_stable_graph())
+    }
+}
+
+/// Collect all nodes, relationships, and paths in the given records into a [`Graph`].
+#[cfg_attr(docsrs, doc(cfg(feature = "petgraph")))]
+pub fn collect_graph(
+    records: impl IntoIterator<Item = Record>,
+) -> PathResult<Graph<Node, Relationship>> {
+    let mut collector = GraphCollector::new();
+    for record in records {
+        collector.add_record(record)?;
+    }
+    Ok(collector.into_graph())
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::visit::EdgeRef;
+
+    use crate::value::UnboundRelationship;
+
+    use super::*;
+
+    fn node(identity: i64) -> Node {
+        Node::new(identity, vec![], HashMap::<String, Value>::new())
+    }
+
+    fn path() -> Path {
+        // (1)-[10:A]->(2)<-[11:B]-(3)
+        Path::new(
+            vec![node(1), node(2), node(3)],
+            vec![
+                UnboundRelationship::new(10, String::from("A"), HashMap::<String, Value>::new()),
+                UnboundRelationship::new(
+                    11,
+                    String::from("B"),
+                    HashMap::from([(String::from("weight"), Value::from(2))]),
+                ),
+            ],
+            vec![1, 1, -2, 2],
+        )
+    }
+
+    #[test]
+    fn path_to_graph() {
+        let graph = Graph::try_from(path()).unwrap();
+        assert_eq!(graph.node_count(), 3);
+        let edges: Vec<(i64, i64, &str)> = graph
+            .edge_references()
+            .map(|edge| {
+                (
+                    graph[edge.source()].node_identity(),
+                    graph[edge.target()].node_identity(),
+                    edge.weight().rel_type(),
+                )
+            })
+            .collect();
+        assert_eq!(edges, vec![(1, 2, "A"), (3, 2, "B")]);
+        assert_eq!(
+            graph[EdgeIndex::new(1)].properties().get("weight"),
+            Some(&Value::from(2))
+        );
+
+        let stable = StableGraph::try_from(path()).unwrap();
+        assert_eq!(stable.edge_count(), 2);
+    }
+
+    #[test]
+    fn malformed_path() {
+        let mut bad = path();
+        bad.sequence = vec![3, 1];
+        assert!(matches!(
+            Graph::try_from(bad),
+            Err(PathError::InvalidRelationshipIndex(3))
+        ));
+
+        let mut bad = path();
+        bad.sequence = vec![1];
+        assert!(matches!(
+            GraphCollector::new().add_path(bad),
+            Err(PathError::OddSequenceLength(1))
+        ));
+    }
+
+    #[test]
+    fn deduplicate_result_set() {
+        let records = vec![
+            Record::new(vec![Value::from(path()), Value::from(node(1))]),
+            Record::new(vec![Value::from(vec![
+                Value::from(node(4)),
+                Value::from(Relationship::new(
+                    10,
+                    1,
+                    2,
+                    String::from("A"),
+                    HashMap::<String, Value>::new(),
+                )),
+                Value::from(Relationship::new(
+                    12,
+                    4,
+                    5,
+                    String::from("C"),
+                    HashMap::<String, Value>::new(),
+                )),
+            ])]),
+        ];
+
+        let mut collector = GraphCollector::new();
+        for record in records.clone() {
+            collector.add_record(record).unwrap();
+        }
+        assert_eq!(collector.node_index(4), Some(NodeIndex::new(3)));
+        assert_eq!(collector.edge_index(12), Some(EdgeIndex::new(2)));
+
+        let graph = collect_graph(records).unwrap();
+        // Node 5 is a placeholder for the end of relationship 12
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph[NodeIndex::new(4)], node(5));
+    }
+}
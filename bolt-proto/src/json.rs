@@ -0,0 +1,668 @@
+//! Conversion between [`Value`]s and JSON, and a writer for records in the
+//! [JSON Lines](https://jsonlines.org) format.
+//!
+//! Values are converted to JSON in a format similar to the one used by the Neo4j HTTP API:
+//!
+//! | [`Value`] variant | JSON |
+//! |-------------------|------|
+//! | [`Null`](Value::Null) | `null` |
+//! | [`Boolean`](Value::Boolean) | `true` / `false` |
+//! | [`Integer`](Value::Integer) | number |
+//! | [`Float`](Value::Float) | number, or `"NaN"` / `"Infinity"` / `"-Infinity"` |
+//! | [`Bytes`](Value::Bytes) | base64-encoded string (standard alphabet, padded) |
+//! | [`String`](Value::String) | string |
+//! | [`List`](Value::List) | array |
+//! | [`Map`](Value::Map) | object |
+//! | [`Node`](Value::Node) | `{"id": 1, "labels": [...], "properties": {...}}` |
+//! | [`Relationship`](Value::Relationship) | `{"id": 1, "type": "...", "startNode": 2, "endNode": 3, "properties": {...}}` |
+//! | [`UnboundRelationship`](Value::UnboundRelationship) | `{"id": 1, "type": "...", "properties": {...}}` |
+//! | [`Path`](Value::Path) | `{"nodes": [...], "relationships": [...], "sequence": [...]}` |
+//! | [`Date`](Value::Date) | `"2020-01-31"` |
+//! | [`Time`](Value::Time) | `"12:34:56.789+01:00"` |
+//! | [`DateTimeOffset`](Value::DateTimeOffset) | `"2020-01-31T12:34:56.789+01:00"` |
+//! | [`DateTimeZoned`](Value::DateTimeZoned) | `"2020-01-31T12:34:56.789+01:00[Europe/Paris]"` |
+//! | [`LocalTime`](Value::LocalTime) | `"12:34:56.789"` |
+//! | [`LocalDateTime`](Value::LocalDateTime) | `"2020-01-31T12:34:56.789"` |
+//! | [`Duration`](Value::Duration) | ISO-8601 duration, e.g. `"P1Y2M3DT4H5M6.000000007S"` |
+//! | [`Point2D`](Value::Point2D) | `{"type": "Point", "coordinates": [x, y], "crs": {"srid": 4326}}` |
+//! | [`Point3D`](Value::Point3D) | `{"type": "Point", "coordinates": [x, y, z], "crs": {"srid": 4979}}` |
+//! | [`Vector`](Value::Vector) | array of numbers |
+//! | [`Structure`](Value::Structure) | `{"signature": 88, "fields": [...]}` |
+//!
+//! Temporal strings use the ISO-8601 formats accepted by Cypher's temporal functions, with
+//! fractional seconds only present when non-zero.
+//!
+//! JSON has no way to distinguish these types, so the [`From<serde_json::Value>`] conversion is
+//! lossy: `null`, booleans, strings, arrays and objects map to the corresponding [`Value`]
+//! variants, and numbers become [`Integer`](Value::Integer)s if they fit in an `i64`, or
+//! [`Float`](Value::Float)s otherwise. Converting a value built only from those variants to JSON
+//! and back yields the original value.
+//!
+//! [`typed_value_from_json`] instead recognizes the shapes in the table above, and recovers
+//! every variant except [`Bytes`](Value::Bytes) and [`Vector`](Value::Vector), which cannot be
+//! told apart from strings and lists.
+
+use std::io;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Map, Number};
+
+use crate::{
+    message::Record,
+    value::{
+        literal::{
+            parse_date, parse_date_time, parse_local_date_time, parse_local_time, parse_time,
+            temporal_string,
+        },
+        Node, Path, Point2D, Point3D, Relationship, UnboundRelationship, ValueMap,
+    },
+    Value,
+};
+
+impl From<&Value> for serde_json::Value {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Boolean(boolean) => serde_json::Value::Bool(*boolean),
+            Value::Integer(integer) => serde_json::Value::from(*integer),
+            Value::Float(float) => match Number::from_f64(*float) {
+                Some(number) => serde_json::Value::Number(number),
+                None if float.is_nan() => json!("NaN"),
+                None if float.is_sign_positive() => json!("Infinity"),
+                None => json!("-Infinity"),
+            },
+            Value::Bytes(bytes) => serde_json::Value::String(BASE64.encode(bytes)),
+            Value::List(list) => serde_json::Value::Array(list.iter().map(Self::from).collect()),
+            Value::Vector(vector) => {
+                serde_json::Value::Array(vector.to_values().iter().map(Self::from).collect())
+            }
+            Value::Map(map) => properties(map),
+            Value::Null => serde_json::Value::Null,
+            Value::String(string) => serde_json::Value::String(string.clone()),
+            Value::Node(node) => node_json(node),
+            Value::Relationship(rel) => json!({
+                "id": rel.rel_identity,
+                "type": rel.rel_type,
+                "startNode": rel.start_node_identity,
+                "endNode": rel.end_node_identity,
+                "properties": properties(&rel.properties),
+            }),
+            Value::Path(path) => json!({
+                "nodes": path.nodes.iter().map(node_json).collect::<Vec<_>>(),
+                "relationships": path
+                    .relationships
+                    .iter()
+                    .map(unbound_relationship_json)
+                    .collect::<Vec<_>>(),
+                "sequence": path.sequence,
+            }),
+            Value::UnboundRelationship(rel) => unbound_relationship_json(rel),
+            Value::Date(_)
+            | Value::Time(_, _)
+            | Value::DateTimeOffset(_)
+            | Value::DateTimeZoned(_)
+            | Value::LocalTime(_)
+            | Value::LocalDateTime(_) => serde_json::Value::String(temporal_string(value).unwrap()),
+            Value::Duration(duration) => serde_json::Value::String(duration.to_string()),
+            Value::Point2D(point) => json!({
+                "type": "Point",
+                "coordinates": [point.x, point.y],
+                "crs": { "srid": point.srid },
+            }),
+            Value::Point3D(point) => json!({
+                "type": "Point",
+                "coordinates": [point.x, point.y, point.z],
+                "crs": { "srid": point.srid },
+            }),
+            Value::Structure { signature, fields } => json!({
+                "signature": signature,
+                "fields": fields.iter().map(Self::from).collect::<Vec<_>>(),
+            }),
+        }
+    }
+}
+
+impl From<Value> for serde_json::Value {
+    fn from(value: Value) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<serde_json::Value> for Value {
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Value::Null,
+            serde_json::Value::Bool(boolean) => Value::Boolean(boolean),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(integer) => Value::Integer(integer),
+                // Every JSON number can be represented as an f64, possibly with loss of precision
+                None => Value::Float(number.as_f64().unwrap_or(f64::NAN)),
+            },
+            serde_json::Value::String(string) => Value::String(string),
+            serde_json::Value::Array(array) => {
+                Value::List(array.into_iter().map(Value::from).collect())
+            }
+            serde_json::Value::Object(object) => Value::Map(
+                object
+                    .into_iter()
+                    .map(|(key, value)| (key, Value::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Convert JSON back into a [`Value`], recovering the variants listed in the
+/// [module documentation](self) from their JSON representation: strings in one of the temporal
+/// or duration formats become temporal values, `"NaN"` and `"Infinity"` become floats, and
+/// objects with exactly the keys of a node, relationship, path, point or structure become that
+/// value. Anything else is converted like [`From<serde_json::Value>`] does.
+///
+/// Since the shapes are only recognized by their content, a string or map which happens to look
+/// like one of them is converted too, e.g. the string `"2020-01-31"` becomes a
+/// [`Date`](Value::Date).
+///
+/// # Example
+/// ```
+/// use bolt_proto::{json::typed_value_from_json, value::Duration, Value};
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     typed_value_from_json(json!(["P1DT2S", "text"])),
+///     Value::from(vec![Value::from(Duration::new(0, 1, 2, 0)), Value::from("text")])
+/// );
+/// ```
+pub fn typed_value_from_json(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::String(string) => typed_string(string),
+        serde_json::Value::Array(array) => {
+            Value::List(array.into_iter().map(typed_value_from_json).collect())
+        }
+        serde_json::Value::Object(object) => typed_map(
+            object
+                .into_iter()
+                .map(|(key, value)| (key, typed_value_from_json(value)))
+                .collect(),
+        ),
+        json => Value::from(json),
+    }
+}
+
+fn typed_string(string: String) -> Value {
+    let value = match string.as_str() {
+        "NaN" => Some(Value::Float(f64::NAN)),
+        "Infinity" => Some(Value::Float(f64::INFINITY)),
+        "-Infinity" => Some(Value::Float(f64::NEG_INFINITY)),
+        s if s.starts_with('P') => s.parse().ok().map(Value::Duration),
+        s if s.contains('T') => {
+            parse_date_time(s).or_else(|| parse_local_date_time(s).map(Value::LocalDateTime))
+        }
+        s if s.contains(':') => parse_time(s).or_else(|| parse_local_time(s).map(Value::LocalTime)),
+        s => parse_date(s),
+    };
+    value.unwrap_or(Value::String(string))
+}
+
+fn typed_map(map: ValueMap) -> Value {
+    let mut keys: Vec<&str> = map.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    let value = match keys.as_slice() {
+        ["id", "labels", "properties"] => typed_node(&map).map(Value::Node),
+        ["endNode", "id", "properties", "startNode", "type"] => {
+            typed_relationship(&map).map(Value::Relationship)
+        }
+        ["id", "properties", "type"] => {
+            typed_unbound_relationship(&map).map(Value::UnboundRelationship)
+        }
+        ["nodes", "relationships", "sequence"] => typed_path(&map).map(Value::Path),
+        ["coordinates", "crs", "type"] => typed_point(&map),
+        ["fields", "signature"] => match (map.get("signature"), map.get("fields")) {
+            (Some(Value::Integer(signature)), Some(Value::List(fields))) => {
+                u8::try_from(*signature)
+                    .ok()
+                    .map(|signature| Value::Structure {
+                        signature,
+                        fields: fields.clone(),
+                    })
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+    value.unwrap_or(Value::Map(map))
+}
+
+fn typed_node(map: &ValueMap) -> Option<Node> {
+    let labels = match map.get("labels")? {
+        Value::List(labels) => labels
+            .iter()
+            .map(|label| match label {
+                Value::String(label) => Some(label.clone()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    Some(Node {
+        node_identity: integer(map, "id")?,
+        labels,
+        properties: properties_of(map)?,
+    })
+}
+
+fn typed_relationship(map: &ValueMap) -> Option<Relationship> {
+    Some(Relationship {
+        rel_identity: integer(map, "id")?,
+        start_node_identity: integer(map, "startNode")?,
+        end_node_identity: integer(map, "endNode")?,
+        rel_type: string(map, "type")?,
+        properties: properties_of(map)?,
+    })
+}
+
+fn typed_unbound_relationship(map: &ValueMap) -> Option<UnboundRelationship> {
+    Some(UnboundRelationship {
+        rel_identity: integer(map, "id")?,
+        rel_type: string(map, "type")?,
+        properties: properties_of(map)?,
+    })
+}
+
+fn typed_path(map: &ValueMap) -> Option<Path> {
+    let list = |key| match map.get(key) {
+        Some(Value::List(list)) => Some(list),
+        _ => None,
+    };
+    Some(Path {
+        nodes: list("nodes")?
+            .iter()
+            .map(|node| match node {
+                Value::Node(node) => Some(node.clone()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        relationships: list("relationships")?
+            .iter()
+            .map(|rel| match rel {
+                Value::UnboundRelationship(rel) => Some(rel.clone()),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        sequence: list("sequence")?
+            .iter()
+            .map(|index| match index {
+                Value::Integer(index) => Some(*index),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+    })
+}
+
+fn typed_point(map: &ValueMap) -> Option<Value> {
+    if string(map, "type")? != "Point" {
+        return None;
+    }
+    let srid = match map.get("crs")? {
+        Value::Map(crs) if crs.len() == 1 => i32::try_from(integer(crs, "srid")?).ok()?,
+        _ => return None,
+    };
+    let coordinates = match map.get("coordinates")? {
+        Value::List(coordinates) => coordinates
+            .iter()
+            .map(|coordinate| match coordinate {
+                Value::Float(float) => Some(*float),
+                Value::Integer(integer) => Some(*integer as f64),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+    match coordinates[..] {
+        [x, y] => Some(Value::Point2D(Point2D::new(srid, x, y))),
+        [x, y, z] => Some(Value::Point3D(Point3D::new(srid, x, y, z))),
+        _ => None,
+    }
+}
+
+fn integer(map: &ValueMap, key: &str) -> Option<i64> {
+    match map.get(key)? {
+        Value::Integer(integer) => Some(*integer),
+        _ => None,
+    }
+}
+
+fn string(map: &ValueMap, key: &str) -> Option<String> {
+    match map.get(key)? {
+        Value::String(string) => Some(string.clone()),
+        _ => None,
+    }
+}
+
+fn properties_of(map: &ValueMap) -> Option<ValueMap> {
+    match map.get("properties")? {
+        Value::Map(properties) => Some(properties.clone()),
+        _ => None,
+    }
+}
+
+fn properties(map: &ValueMap) -> serde_json::Value {
+    serde_json::Value::Object(
+        map.iter()
+            .map(|(key, value)| (key.clone(), serde_json::Value::from(value)))
+            .collect(),
+    )
+}
+
+fn node_json(node: &Node) -> serde_json::Value {
+    json!({
+        "id": node.node_identity,
+        "labels": node.labels,
+        "properties": properties(&node.properties),
+    })
+}
+
+fn unbound_relationship_json(rel: &UnboundRelationship) -> serde_json::Value {
+    json!({
+        "id": rel.rel_identity,
+        "type": rel.rel_type,
+        "properties": properties(&rel.properties),
+    })
+}
+
+/// Convert a [`Record`] into a JSON object, using the given field names as keys. The field names
+/// are typically taken from the `fields` entry of the `SUCCESS` response to a `RUN` message.
+pub fn record_to_object(fields: &[String], record: &Record) -> Map<String, serde_json::Value> {
+    fields
+        .iter()
+        .cloned()
+        .zip(record.fields.iter().map(serde_json::Value::from))
+        .collect()
+}
+
+/// Writes [`Record`]s as [JSON Lines](https://jsonlines.org), one JSON object per record, keyed
+/// by field name.
+///
+/// # Example
+/// ```
+/// use bolt_proto::{json::JsonLinesWriter, message::Record, Value};
+///
+/// let mut writer = JsonLinesWriter::new(Vec::new(), vec![String::from("n")]);
+/// writer.write_record(&Record::new(vec![Value::from(1)]))?;
+/// writer.write_record(&Record::new(vec![Value::from("two")]))?;
+///
+/// let output = String::from_utf8(writer.into_inner()).unwrap();
+/// assert_eq!(output, "{\"n\":1}\n{\"n\":\"two\"}\n");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[derive(Debug)]
+pub struct JsonLinesWriter<W> {
+    writer: W,
+    fields: Vec<String>,
+}
+
+impl<W: io::Write> JsonLinesWriter<W> {
+    /// Create a new writer for records with the given field names.
+    pub fn new(writer: W, fields: Vec<String>) -> Self {
+        Self { writer, fields }
+    }
+
+    /// Get the field names used as keys for each record.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    /// Write a single record, followed by a newline. Fails with
+    /// [`InvalidInput`](io::ErrorKind::InvalidInput) if the record does not have one value per
+    /// field.
+    pub fn write_record(&mut self, record: &Record) -> io::Result<()> {
+        if record.fields.len() != self.fields.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "record has {} values, but {} fields were provided",
+                    record.fields.len(),
+                    self.fields.len()
+                ),
+            ));
+        }
+
+        serde_json::to_writer(&mut self.writer, &record_to_object(&self.fields, record))?;
+        self.writer.write_all(b"\n")
+    }
+
+    /// Write each of the given records on its own line.
+    pub fn write_records<'a>(
+        &mut self,
+        records: impl IntoIterator<Item = &'a Record>,
+    ) -> io::Result<()> {
+        for record in records {
+            self.write_record(record)?;
+        }
+        Ok(())
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Get the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{FixedOffset, NaiveDate, NaiveTime};
+
+    use crate::value::*;
+
+    use super::*;
+
+    #[test]
+    fn to_json() {
+        let node = Node::new(
+            1,
+            vec![String::from("Person")],
+            HashMap::from([(String::from("name"), Value::from("Alice"))]),
+        );
+        assert_eq!(
+            serde_json::Value::from(Value::from(node)),
+            json!({"id": 1, "labels": ["Person"], "properties": {"name": "Alice"}})
+        );
+
+        let rel = Relationship::new(
+            5,
+            1,
+            2,
+            String::from("KNOWS"),
+            HashMap::<String, Value>::new(),
+        );
+        assert_eq!(
+            serde_json::Value::from(Value::from(rel)),
+            json!({"id": 5, "type": "KNOWS", "startNode": 1, "endNode": 2, "properties": {}})
+        );
+
+        assert_eq!(
+            serde_json::Value::from(Value::Bytes(vec![1, 2, 3])),
+            json!("AQID")
+        );
+        assert_eq!(serde_json::Value::from(Value::from(f64::NAN)), json!("NaN"));
+        assert_eq!(
+            serde_json::Value::from(Value::from(f64::NEG_INFINITY)),
+            json!("-Infinity")
+        );
+        assert_eq!(
+            serde_json::Value::from(Value::from(Point2D::new(4326, 1.5, 2.0))),
+            json!({"type": "Point", "coordinates": [1.5, 2.0], "crs": {"srid": 4326}})
+        );
+    }
+
+    #[test]
+    fn temporal_to_json() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+        let time = NaiveTime::from_hms_milli_opt(12, 34, 56, 789).unwrap();
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let cases = vec![
+            (Value::from(date), "2020-01-31"),
+            (Value::from((time, offset)), "12:34:56.789+01:00"),
+            (Value::from(time), "12:34:56.789"),
+            (Value::from(date.and_time(time)), "2020-01-31T12:34:56.789"),
+            (
+                Value::from(date.and_time(time).and_local_timezone(offset).unwrap()),
+                "2020-01-31T12:34:56.789+01:00",
+            ),
+            (
+                Value::from((date.and_time(time), chrono_tz::Europe::Paris)),
+                "2020-01-31T13:34:56.789+01:00[Europe/Paris]",
+            ),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(serde_json::Value::from(value), json!(expected));
+        }
+    }
+
+    #[test]
+    fn duration_to_json() {
+        let cases = vec![
+            (Duration::new(0, 0, 0, 0), "PT0S"),
+            (Duration::new(14, 3, 0, 0), "P1Y2M3D"),
+            (Duration::new(0, 0, 3723, 7), "PT1H2M3.000000007S"),
+            (Duration::new(0, 1, 0, 500_000_000), "P1DT0.5S"),
+            (Duration::new(0, 0, -1, 500_000_000), "PT-0.5S"),
+            (Duration::new(-1, 0, -60, 0), "P-1MT-1M"),
+        ];
+        for (duration, expected) in cases {
+            assert_eq!(
+                serde_json::Value::from(Value::from(duration)),
+                json!(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn from_json() {
+        let json = json!({
+            "int": 1,
+            "big": u64::MAX,
+            "float": 1.5,
+            "list": [null, true, "s"],
+        });
+        let value = Value::from(json.clone());
+        assert_eq!(
+            value,
+            Value::from(HashMap::from([
+                ("int", Value::from(1)),
+                ("big", Value::from(u64::MAX as f64)),
+                ("float", Value::from(1.5)),
+                (
+                    "list",
+                    Value::from(vec![Value::Null, Value::from(true), Value::from("s")])
+                ),
+            ]))
+        );
+        assert_eq!(serde_json::Value::from(value.clone()), {
+            let mut json = json;
+            json["big"] = json!(u64::MAX as f64);
+            json
+        });
+    }
+
+    #[test]
+    fn typed_from_json() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+        let time = NaiveTime::from_hms_milli_opt(12, 34, 56, 789).unwrap();
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        let node = Node::new(
+            1,
+            vec![String::from("Person")],
+            HashMap::from([(String::from("born"), Value::from(date))]),
+        );
+        let rel = UnboundRelationship::new(
+            5,
+            String::from("KNOWS"),
+            HashMap::from([(String::from("since"), Value::from(time))]),
+        );
+        let values = vec![
+            Value::Null,
+            Value::from(true),
+            Value::from(-1),
+            Value::from(1.5),
+            Value::from(f64::INFINITY),
+            Value::from("text"),
+            Value::from(date),
+            Value::from((time, offset)),
+            Value::from(time),
+            Value::from(date.and_time(time)),
+            Value::from(date.and_time(time).and_local_timezone(offset).unwrap()),
+            Value::from((date.and_time(time), chrono_tz::Europe::Paris)),
+            Value::from(Duration::new(14, 3, -60, 7)),
+            Value::from(Point2D::new(4326, 1.5, 2.0)),
+            Value::from(Point3D::new(4979, 1.0, 2.0, 3.0)),
+            Value::from(node.clone()),
+            Value::from(Relationship::new(
+                5,
+                1,
+                2,
+                String::from("KNOWS"),
+                HashMap::<String, Value>::new(),
+            )),
+            Value::from(rel.clone()),
+            Value::from(Path::new(vec![node.clone(), node], vec![rel], vec![1, 1])),
+            Value::Structure {
+                signature: 0x70,
+                fields: vec![Value::from(date)],
+            },
+            Value::from(HashMap::from([(
+                String::from("id"),
+                Value::from(vec![Value::from(Duration::new(0, 1, 0, 0))]),
+            )])),
+        ];
+        for value in values {
+            assert_eq!(
+                typed_value_from_json(serde_json::Value::from(&value)),
+                value
+            );
+        }
+
+        assert!(matches!(
+            typed_value_from_json(json!("NaN")),
+            Value::Float(float) if float.is_nan()
+        ));
+        // Shapes which are not quite right are left alone
+        assert_eq!(
+            typed_value_from_json(json!({"id": "1", "labels": [], "properties": {}})),
+            Value::from(json!({"id": "1", "labels": [], "properties": {}}))
+        );
+        assert_eq!(
+            typed_value_from_json(json!(["Paris", "12", "2020-13-01", "AQID"])),
+            Value::from(vec!["Paris", "12", "2020-13-01", "AQID"])
+        );
+    }
+
+    #[test]
+    fn json_lines() {
+        let mut writer =
+            JsonLinesWriter::new(Vec::new(), vec![String::from("a"), String::from("b")]);
+        writer
+            .write_records(&[
+                Record::new(vec![Value::from(1), Value::Null]),
+                Record::new(vec![Value::from("x"), Value::from(vec![1, 2])]),
+            ])
+            .unwrap();
+        assert!(writer.write_record(&Record::new(vec![])).is_err());
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            lines,
+            vec![json!({"a": 1, "b": null}), json!({"a": "x", "b": [1, 2]})]
+        );
+    }
+}
@@ -1,14 +1,55 @@
 #![warn(rust_2018_idioms)]
+// Deserialization errors can hold a Value, but boxing them would change the public API
+#![allow(clippy::result_large_err)]
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
 //! This crate contains the primitives used in the [Bolt](https://neo4j.com/docs/bolt/current)
 //! protocol. The [`Message`] and [`Value`] enums are of particular importance, and are the primary
 //! units of information sent and consumed by Bolt clients/servers.
+//!
+//! # Features
+//! This crate has the following features, which are disabled by default:
+//! - `arrow`: Enables the [`arrow`](crate::arrow) module for converting query results into Arrow
+//!   [`RecordBatch`](::arrow::record_batch::RecordBatch)es.
+//! - `geo-types`: Enables conversions between [`Point2D`](value::Point2D)s and
+//!   [`geo_types::Point`]s.
+//! - `geojson`: Enables conversions between geographic points and [`geojson`] `Point` geometries.
+//! - `indexmap`: Backs [`ValueMap`](value::ValueMap) with an insertion-ordered
+//!   [`IndexMap`](indexmap::IndexMap) instead of a [`HashMap`](std::collections::HashMap), so maps,
+//!   properties, and message metadata keep the order of their entries when deserialized and are
+//!   serialized deterministically. The API of `ValueMap` is the same either way. Also enables
+//!   conversions between [`Value`]s and `IndexMap`s.
+//! - `jiff`: Enables conversions between [`Value`]s and the date, time, timestamp, zoned
+//!   date-time, and duration types of the [`jiff`] crate. Zoned date-times keep their time zone
+//!   IDs.
+//! - `json`: Enables the [`json`](crate::json) module for converting [`Value`]s to and from
+//!   [`serde_json::Value`]s and writing records as JSON Lines.
+//! - `petgraph`: Enables the [`graph`](crate::graph) module for collecting nodes, relationships,
+//!   and paths into [`petgraph`] graphs.
+//! - `proptest`: Enables the [`arbitrary`](crate::arbitrary) module, which implements
+//!   [`proptest::arbitrary::Arbitrary`] for [`Value`], [`Message`], and the types they contain, for
+//!   property-based testing.
+//! - `time`: Enables conversions between [`Value`]s and the date, time, offset date-time, and
+//!   duration types of the [`time`] crate.
 
 pub use message::Message;
+pub use serialization::{DeserializeOptions, SerializeOptions};
 pub use server_state::ServerState;
 pub use value::Value;
 
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub mod arbitrary;
+#[cfg(feature = "arrow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "arrow")))]
+pub mod arrow;
 pub mod error;
+#[cfg(feature = "petgraph")]
+#[cfg_attr(docsrs, doc(cfg(feature = "petgraph")))]
+pub mod graph;
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod json;
 pub mod message;
 mod serialization;
 mod server_state;
@@ -20,15 +61,11 @@ pub mod version;
 macro_rules! impl_message_with_metadata {
     ($T:path) => {
         impl $T {
-            pub fn new(
-                metadata: ::std::collections::HashMap<::std::string::String, $crate::value::Value>,
-            ) -> Self {
+            pub fn new(metadata: $crate::value::ValueMap) -> Self {
                 Self { metadata }
             }
 
-            pub fn metadata(
-                &self,
-            ) -> &::std::collections::HashMap<::std::string::String, $crate::value::Value> {
+            pub fn metadata(&self) -> &$crate::value::ValueMap {
                 &self.metadata
             }
         }
@@ -55,9 +55,9 @@ pub(crate) const SIGNATURE_PULL: u8 = 0x3F;
 pub(crate) const SIGNATURE_ROUTE: u8 = 0x66;
 
 // This is the default maximum chunk size in the official driver, minus header length
-const CHUNK_SIZE: usize = 16383 - mem::size_of::<u16>();
+pub(crate) const DEFAULT_MAX_CHUNK_SIZE: usize = 16383 - mem::size_of::<u16>();
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Message {
     // v1-compatible message types
     Init(Init),
@@ -91,7 +91,16 @@ pub enum Message {
 }
 
 impl Message {
-    pub async fn from_stream(mut stream: impl AsyncRead + Unpin) -> DeserializeResult<Message> {
+    pub async fn from_stream(stream: impl AsyncRead + Unpin) -> DeserializeResult<Message> {
+        Self::from_stream_with(stream, &DeserializeOptions::default()).await
+    }
+
+    /// Read a message from the given stream, enforcing the limits in the given
+    /// [`DeserializeOptions`].
+    pub async fn from_stream_with(
+        mut stream: impl AsyncRead + Unpin,
+        options: &DeserializeOptions,
+    ) -> DeserializeResult<Message> {
         let mut bytes = BytesMut::new();
         let mut chunk_len = 0;
         // Ignore any no-op messages
@@ -102,6 +111,11 @@ impl Message {
         }
         // Messages end in a 0_u16
         while chunk_len > 0 {
+            if let Some(max_message_size) = options.max_message_size {
+                if bytes.len() + chunk_len as usize > max_message_size {
+                    return Err(DeserializationError::MessageTooLarge(max_message_size));
+                }
+            }
             let mut buf = vec![0; chunk_len as usize];
             stream.read_exact(&mut buf).await?;
             bytes.put_slice(&buf);
@@ -109,21 +123,44 @@ impl Message {
             stream.read_exact(&mut u16_bytes).await?;
             chunk_len = u16::from_be_bytes(u16_bytes);
         }
-        let (message, remaining) = Message::deserialize(bytes)?;
-        debug_assert_eq!(remaining.len(), 0);
+        let (message, remaining) = Message::deserialize_with(bytes, options)?;
+        if !remaining.is_empty() {
+            return Err(DeserializationError::TrailingBytes(remaining.len()));
+        }
 
         Ok(message)
     }
 
+    /// Deserialize a message from its PackStream representation, without the chunking used by
+    /// [`from_stream`](Message::from_stream), returning the message and the remaining bytes.
+    pub fn deserialize<B: Buf + UnwindSafe>(bytes: B) -> DeserializeResult<(Self, B)> {
+        <Self as BoltValue>::deserialize(bytes)
+    }
+
+    /// Deserialize a message from its PackStream representation, enforcing the limits in the
+    /// given [`DeserializeOptions`].
+    pub fn deserialize_with<B: Buf + UnwindSafe>(
+        bytes: B,
+        options: &DeserializeOptions,
+    ) -> DeserializeResult<(Self, B)> {
+        <Self as BoltValue>::deserialize_with(bytes, options)
+    }
+
     pub fn into_chunks(self) -> SerializeResult<Vec<Bytes>> {
-        let bytes = self.serialize()?;
+        self.into_chunks_with(&SerializeOptions::default())
+    }
+
+    /// Serialize this message into chunks using the given [`SerializeOptions`].
+    pub fn into_chunks_with(self, options: &SerializeOptions) -> SerializeResult<Vec<Bytes>> {
+        let bytes = self.serialize_with(options)?;
 
         // Big enough to hold all the chunks, plus a partial chunk, plus the message footer
-        let mut result: Vec<Bytes> = Vec::with_capacity(bytes.len() / CHUNK_SIZE + 2);
-        for slice in bytes.chunks(CHUNK_SIZE) {
+        let chunk_size = options.max_chunk_size;
+        let mut result: Vec<Bytes> = Vec::with_capacity(bytes.len() / chunk_size + 2);
+        for slice in bytes.chunks(chunk_size) {
             // 16-bit size, then the chunk data
             let mut chunk = BytesMut::with_capacity(mem::size_of::<u16>() + slice.len());
-            // Length of slice is at most CHUNK_SIZE, which can fit in a u16
+            // Length of slice is at most the chunk size, which can fit in a u16
             chunk.put_u16(slice.len() as u16);
             chunk.put(slice);
             result.push(chunk.freeze());
@@ -136,8 +173,8 @@ impl Message {
 }
 
 macro_rules! deserialize_struct {
-    ($name:ident, $bytes:ident) => {{
-        let (message, remaining) = $name::deserialize($bytes)?;
+    ($name:ident, $bytes:ident, $options:expr) => {{
+        let (message, remaining) = $name::deserialize_with($bytes, $options)?;
         $bytes = remaining;
         Ok((Message::$name(message), $bytes))
     }};
@@ -162,36 +199,44 @@ impl BoltValue for Message {
         }
     }
 
-    fn serialize(self) -> SerializeResult<Bytes> {
+    fn serialize_with(self, options: &SerializeOptions) -> SerializeResult<Bytes> {
         match self {
-            Message::Init(init) => init.serialize(),
-            Message::Run(run) => run.serialize(),
-            Message::Record(record) => record.serialize(),
-            Message::Success(success) => success.serialize(),
-            Message::Failure(failure) => failure.serialize(),
-            Message::Hello(hello) => hello.serialize(),
-            Message::RunWithMetadata(run_with_metadata) => run_with_metadata.serialize(),
-            Message::Begin(begin) => begin.serialize(),
-            Message::Discard(discard) => discard.serialize(),
-            Message::Pull(pull) => pull.serialize(),
-            Message::Route(route) => route.serialize(),
-            Message::RouteWithMetadata(route_with_metadata) => route_with_metadata.serialize(),
+            Message::Init(init) => init.serialize_with(options),
+            Message::Run(run) => run.serialize_with(options),
+            Message::Record(record) => record.serialize_with(options),
+            Message::Success(success) => success.serialize_with(options),
+            Message::Failure(failure) => failure.serialize_with(options),
+            Message::Hello(hello) => hello.serialize_with(options),
+            Message::RunWithMetadata(run_with_metadata) => {
+                run_with_metadata.serialize_with(options)
+            }
+            Message::Begin(begin) => begin.serialize_with(options),
+            Message::Discard(discard) => discard.serialize_with(options),
+            Message::Pull(pull) => pull.serialize_with(options),
+            Message::Route(route) => route.serialize_with(options),
+            Message::RouteWithMetadata(route_with_metadata) => {
+                route_with_metadata.serialize_with(options)
+            }
             other => Ok(Bytes::from(vec![other.marker()?, other.signature()])),
         }
     }
 
-    fn deserialize<B: Buf + UnwindSafe>(mut bytes: B) -> DeserializeResult<(Self, B)> {
+    fn deserialize_with<B: Buf + UnwindSafe>(
+        mut bytes: B,
+        options: &DeserializeOptions,
+    ) -> DeserializeResult<(Self, B)> {
         catch_unwind(move || {
-            let marker = bytes.get_u8();
+            let marker = bytes.try_get_u8()?;
             let (size, signature) = get_structure_info(marker, &mut bytes)?;
+            let options = options.nested()?;
 
             match signature {
                 SIGNATURE_INIT => {
                     // Conflicting signatures, so we have to check for metadata.
                     // HELLO has 1 field, while INIT has 2.
                     match size {
-                        1 => deserialize_struct!(Hello, bytes),
-                        2 => deserialize_struct!(Init, bytes),
+                        1 => deserialize_struct!(Hello, bytes, &options),
+                        2 => deserialize_struct!(Init, bytes, &options),
                         _ => Err(DeserializationError::InvalidSize { size, signature }),
                     }
                 }
@@ -199,8 +244,8 @@ impl BoltValue for Message {
                     // Conflicting signatures, so we have to check for metadata.
                     // RUN has 2 fields, while RUN_WITH_METADATA has 3.
                     match size {
-                        2 => deserialize_struct!(Run, bytes),
-                        3 => deserialize_struct!(RunWithMetadata, bytes),
+                        2 => deserialize_struct!(Run, bytes, &options),
+                        3 => deserialize_struct!(RunWithMetadata, bytes, &options),
                         _ => Err(DeserializationError::InvalidSize { size, signature }),
                     }
                 }
@@ -209,7 +254,7 @@ impl BoltValue for Message {
                     // DISCARD_ALL has 0 fields, while DISCARD has 1.
                     match size {
                         0 => Ok((Message::DiscardAll, bytes)),
-                        1 => deserialize_struct!(Discard, bytes),
+                        1 => deserialize_struct!(Discard, bytes, &options),
                         _ => Err(DeserializationError::InvalidSize { size, signature }),
                     }
                 }
@@ -218,34 +263,37 @@ impl BoltValue for Message {
                     // PULL_ALL has 0 fields, while PULL has 1.
                     match size {
                         0 => Ok((Message::PullAll, bytes)),
-                        1 => deserialize_struct!(Pull, bytes),
+                        1 => deserialize_struct!(Pull, bytes, &options),
                         _ => Err(DeserializationError::InvalidSize { size, signature }),
                     }
                 }
                 SIGNATURE_ACK_FAILURE => Ok((Message::AckFailure, bytes)),
                 SIGNATURE_RESET => Ok((Message::Reset, bytes)),
-                SIGNATURE_RECORD => deserialize_struct!(Record, bytes),
-                SIGNATURE_SUCCESS => deserialize_struct!(Success, bytes),
-                SIGNATURE_FAILURE => deserialize_struct!(Failure, bytes),
+                SIGNATURE_RECORD => deserialize_struct!(Record, bytes, &options),
+                SIGNATURE_SUCCESS => deserialize_struct!(Success, bytes, &options),
+                SIGNATURE_FAILURE => deserialize_struct!(Failure, bytes, &options),
                 SIGNATURE_IGNORED => Ok((Message::Ignored, bytes)),
                 SIGNATURE_GOODBYE => Ok((Message::Goodbye, bytes)),
-                SIGNATURE_BEGIN => deserialize_struct!(Begin, bytes),
+                SIGNATURE_BEGIN => deserialize_struct!(Begin, bytes, &options),
                 SIGNATURE_COMMIT => Ok((Message::Commit, bytes)),
                 SIGNATURE_ROLLBACK => Ok((Message::Rollback, bytes)),
-                SIGNATURE_ROUTE => match RouteWithMetadata::deserialize(bytes.chunk()) {
-                    Ok(_) => {
-                        // Actually consume the bytes
-                        let (message, remaining) = RouteWithMetadata::deserialize(bytes)?;
-                        bytes = remaining;
-                        Ok((Message::RouteWithMetadata(message), bytes))
-                    }
-                    Err(_) => {
-                        // Fall back to v4.3-compatible ROUTE message
-                        let (message, remaining) = Route::deserialize(bytes)?;
-                        bytes = remaining;
-                        Ok((Message::Route(message), bytes))
+                SIGNATURE_ROUTE => {
+                    match RouteWithMetadata::deserialize_with(bytes.chunk(), &options) {
+                        Ok(_) => {
+                            // Actually consume the bytes
+                            let (message, remaining) =
+                                RouteWithMetadata::deserialize_with(bytes, &options)?;
+                            bytes = remaining;
+                            Ok((Message::RouteWithMetadata(message), bytes))
+                        }
+                        Err(_) => {
+                            // Fall back to v4.3-compatible ROUTE message
+                            let (message, remaining) = Route::deserialize_with(bytes, &options)?;
+                            bytes = remaining;
+                            Ok((Message::Route(message), bytes))
+                        }
                     }
-                },
+                }
                 _ => Err(DeserializationError::InvalidSignatureByte(signature)),
             }
         })
@@ -278,3 +326,79 @@ impl BoltStructure for Message {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{io::Cursor, FutureExt};
+
+    use crate::{
+        value::{ValueMap, MARKER_NULL},
+        Value,
+    };
+
+    use super::*;
+
+    fn success(len: usize) -> Message {
+        let metadata = ValueMap::from_iter([(String::from("data"), Value::Bytes(vec![0; len]))]);
+        Message::Success(Success::new(metadata))
+    }
+
+    fn read(chunks: Vec<Bytes>, options: &DeserializeOptions) -> DeserializeResult<Message> {
+        let stream = Cursor::new(chunks.concat());
+        Message::from_stream_with(stream, options)
+            .now_or_never()
+            .unwrap()
+    }
+
+    #[test]
+    fn chunk_size() {
+        let message = success(100);
+        let options = SerializeOptions::new().with_max_chunk_size(32);
+        let chunks = message.clone().into_chunks_with(&options).unwrap();
+        // 100 bytes of data, plus markers, signature, and key
+        assert_eq!(chunks.len(), 5);
+        for chunk in &chunks[..3] {
+            assert_eq!(chunk.len(), 2 + 32);
+            assert_eq!(chunk[..2], [0, 32]);
+        }
+        assert_eq!(chunks.last().unwrap()[..], [0, 0]);
+        assert_eq!(read(chunks, &DeserializeOptions::new()).unwrap(), message);
+
+        let options = SerializeOptions::new().with_max_chunk_size(0);
+        assert_eq!(options.max_chunk_size(), 1);
+    }
+
+    #[test]
+    fn max_message_size() {
+        let message = success(1000);
+        let chunks = message.clone().into_chunks().unwrap();
+
+        let options = DeserializeOptions::new().with_max_message_size(Some(1000));
+        assert!(matches!(
+            read(chunks.clone(), &options),
+            Err(DeserializationError::MessageTooLarge(1000))
+        ));
+
+        let options = DeserializeOptions::new().with_max_message_size(Some(2000));
+        assert_eq!(read(chunks, &options).unwrap(), message);
+    }
+
+    #[test]
+    fn trailing_bytes() {
+        let chunk = Bytes::from_static(&[0, 3, MARKER_TINY_STRUCT, SIGNATURE_RESET, MARKER_NULL]);
+        let chunks = vec![chunk, Bytes::from_static(&[0, 0])];
+        assert!(matches!(
+            read(chunks, &DeserializeOptions::default()),
+            Err(DeserializationError::TrailingBytes(1))
+        ));
+    }
+
+    #[test]
+    fn truncated_input() {
+        let bytes: &[u8] = &[MARKER_TINY_STRUCT | 1, SIGNATURE_RECORD];
+        assert!(matches!(
+            Message::deserialize(bytes),
+            Err(DeserializationError::UnexpectedEnd(_))
+        ));
+    }
+}
@@ -1,18 +1,16 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_try_from_message, message::SIGNATURE_INIT, Value};
+use crate::{impl_try_from_message, message::SIGNATURE_INIT, value::ValueMap};
 
 #[bolt_structure(SIGNATURE_INIT)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Init {
     pub(crate) user_agent: String,
-    pub(crate) auth_token: HashMap<String, Value>,
+    pub(crate) auth_token: ValueMap,
 }
 
 impl Init {
-    pub fn new(user_agent: String, auth_token: HashMap<String, Value>) -> Self {
+    pub fn new(user_agent: String, auth_token: ValueMap) -> Self {
         Self {
             user_agent,
             auth_token,
@@ -23,7 +21,7 @@ impl Init {
         &self.user_agent
     }
 
-    pub fn auth_token(&self) -> &HashMap<String, Value> {
+    pub fn auth_token(&self) -> &ValueMap {
         &self.auth_token
     }
 }
@@ -1,13 +1,13 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_message_with_metadata, impl_try_from_message, message::SIGNATURE_PULL, Value};
+use crate::{
+    impl_message_with_metadata, impl_try_from_message, message::SIGNATURE_PULL, value::ValueMap,
+};
 
 #[bolt_structure(SIGNATURE_PULL)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Pull {
-    pub(crate) metadata: HashMap<String, Value>,
+    pub(crate) metadata: ValueMap,
 }
 
 impl_message_with_metadata!(Pull);
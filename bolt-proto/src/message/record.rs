@@ -3,7 +3,7 @@ use bolt_proto_derive::*;
 use crate::{impl_try_from_message, message::SIGNATURE_RECORD, Value};
 
 #[bolt_structure(SIGNATURE_RECORD)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Record {
     pub(crate) fields: Vec<Value>,
 }
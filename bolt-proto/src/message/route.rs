@@ -1,23 +1,17 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_try_from_message, message::SIGNATURE_ROUTE, Value};
+use crate::{impl_try_from_message, message::SIGNATURE_ROUTE, value::ValueMap, Value};
 
 #[bolt_structure(SIGNATURE_ROUTE)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Route {
-    pub(crate) context: HashMap<String, Value>,
+    pub(crate) context: ValueMap,
     pub(crate) bookmarks: Vec<String>,
     pub(crate) database: Value,
 }
 
 impl Route {
-    pub fn new(
-        context: HashMap<String, Value>,
-        bookmarks: Vec<String>,
-        database: Option<String>,
-    ) -> Self {
+    pub fn new(context: ValueMap, bookmarks: Vec<String>, database: Option<String>) -> Self {
         Self {
             context,
             bookmarks,
@@ -25,7 +19,7 @@ impl Route {
         }
     }
 
-    pub fn context(&self) -> &HashMap<String, Value> {
+    pub fn context(&self) -> &ValueMap {
         &self.context
     }
 
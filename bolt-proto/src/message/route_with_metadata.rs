@@ -1,23 +1,17 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_try_from_message, message::SIGNATURE_ROUTE, Value};
+use crate::{impl_try_from_message, message::SIGNATURE_ROUTE, value::ValueMap};
 
 #[bolt_structure(SIGNATURE_ROUTE)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RouteWithMetadata {
-    pub(crate) context: HashMap<String, Value>,
+    pub(crate) context: ValueMap,
     pub(crate) bookmarks: Vec<String>,
-    pub(crate) metadata: HashMap<String, Value>,
+    pub(crate) metadata: ValueMap,
 }
 
 impl RouteWithMetadata {
-    pub fn new(
-        context: HashMap<String, Value>,
-        bookmarks: Vec<String>,
-        metadata: HashMap<String, Value>,
-    ) -> Self {
+    pub fn new(context: ValueMap, bookmarks: Vec<String>, metadata: ValueMap) -> Self {
         Self {
             context,
             bookmarks,
@@ -25,7 +19,7 @@ impl RouteWithMetadata {
         }
     }
 
-    pub fn context(&self) -> &HashMap<String, Value> {
+    pub fn context(&self) -> &ValueMap {
         &self.context
     }
 
@@ -33,7 +27,7 @@ impl RouteWithMetadata {
         &self.bookmarks
     }
 
-    pub fn metadata(&self) -> &HashMap<String, Value> {
+    pub fn metadata(&self) -> &ValueMap {
         &self.metadata
     }
 }
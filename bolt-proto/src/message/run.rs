@@ -1,18 +1,16 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_try_from_message, message::SIGNATURE_RUN, Value};
+use crate::{impl_try_from_message, message::SIGNATURE_RUN, value::ValueMap};
 
 #[bolt_structure(SIGNATURE_RUN)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Run {
     pub(crate) query: String,
-    pub(crate) parameters: HashMap<String, Value>,
+    pub(crate) parameters: ValueMap,
 }
 
 impl Run {
-    pub fn new(query: String, parameters: HashMap<String, Value>) -> Self {
+    pub fn new(query: String, parameters: ValueMap) -> Self {
         Self { query, parameters }
     }
 
@@ -20,7 +18,7 @@ impl Run {
         &self.query
     }
 
-    pub fn parameters(&self) -> &HashMap<String, Value> {
+    pub fn parameters(&self) -> &ValueMap {
         &self.parameters
     }
 }
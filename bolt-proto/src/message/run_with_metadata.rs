@@ -1,23 +1,17 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_try_from_message, message::SIGNATURE_RUN_WITH_METADATA, Value};
+use crate::{impl_try_from_message, message::SIGNATURE_RUN_WITH_METADATA, value::ValueMap};
 
 #[bolt_structure(SIGNATURE_RUN_WITH_METADATA)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct RunWithMetadata {
     pub(crate) statement: String,
-    pub(crate) parameters: HashMap<String, Value>,
-    pub(crate) metadata: HashMap<String, Value>,
+    pub(crate) parameters: ValueMap,
+    pub(crate) metadata: ValueMap,
 }
 
 impl RunWithMetadata {
-    pub fn new(
-        statement: String,
-        parameters: HashMap<String, Value>,
-        metadata: HashMap<String, Value>,
-    ) -> Self {
+    pub fn new(statement: String, parameters: ValueMap, metadata: ValueMap) -> Self {
         Self {
             statement,
             parameters,
@@ -29,11 +23,11 @@ impl RunWithMetadata {
         &self.statement
     }
 
-    pub fn parameters(&self) -> &HashMap<String, Value> {
+    pub fn parameters(&self) -> &ValueMap {
         &self.parameters
     }
 
-    pub fn metadata(&self) -> &HashMap<String, Value> {
+    pub fn metadata(&self) -> &ValueMap {
         &self.metadata
     }
 }
@@ -1,13 +1,13 @@
-use std::collections::HashMap;
-
 use bolt_proto_derive::*;
 
-use crate::{impl_message_with_metadata, impl_try_from_message, message::SIGNATURE_SUCCESS, Value};
+use crate::{
+    impl_message_with_metadata, impl_try_from_message, message::SIGNATURE_SUCCESS, value::ValueMap,
+};
 
 #[bolt_structure(SIGNATURE_SUCCESS)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Success {
-    pub(crate) metadata: HashMap<String, Value>,
+    pub(crate) metadata: ValueMap,
 }
 
 impl_message_with_metadata!(Success);
@@ -1,25 +1,220 @@
 use std::panic::UnwindSafe;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, Bytes, TryGetError};
 
 use crate::{
     error::{DeserializationError, DeserializeResult, SerializeResult},
-    value::{MARKER_MEDIUM_STRUCT, MARKER_SMALL_STRUCT, MARKER_TINY_STRUCT},
+    message::DEFAULT_MAX_CHUNK_SIZE,
+    value::{StructureDecoder, MARKER_MEDIUM_STRUCT, MARKER_SMALL_STRUCT, MARKER_TINY_STRUCT},
+    version::{V4_3, V4_4},
 };
 
+// Deep enough for any reasonable value, and shallow enough to avoid overflowing the stack
+const DEFAULT_MAX_DEPTH: usize = 256;
+
+/// Options that control how [`Value`](crate::Value)s and [`Message`](crate::Message)s are
+/// serialized.
+///
+/// The default options produce an encoding that every protocol version understands. Use
+/// [`SerializeOptions::for_version`] to pick the options for a negotiated protocol version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SerializeOptions {
+    pub(crate) utc_date_times: bool,
+    pub(crate) vectors: bool,
+    pub(crate) canonical: bool,
+    pub(crate) max_chunk_size: usize,
+}
+
+impl Default for SerializeOptions {
+    fn default() -> Self {
+        Self {
+            utc_date_times: false,
+            vectors: false,
+            canonical: false,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+        }
+    }
+}
+
+impl SerializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the options for the given protocol version. `utc_patch` indicates whether the server
+    /// accepted the `utc` patch during the `HELLO` exchange, which enables UTC-based date-times
+    /// for Bolt v4.3 and v4.4.
+    pub fn for_version(version: u32, utc_patch: bool) -> Self {
+        let major = version & 0xff;
+        Self {
+            utc_date_times: major >= 5 || (utc_patch && matches!(version, V4_3 | V4_4)),
+            vectors: major >= 6,
+            ..Self::default()
+        }
+    }
+
+    /// Set whether [`Value::DateTimeOffset`](crate::Value::DateTimeOffset) and
+    /// [`Value::DateTimeZoned`](crate::Value::DateTimeZoned) use the UTC-based structures
+    /// introduced in Bolt v5 (and Bolt v4.3+ with the `utc` patch), instead of the legacy
+    /// structures that store seconds in local time.
+    pub fn with_utc_date_times(mut self, utc_date_times: bool) -> Self {
+        self.utc_date_times = utc_date_times;
+        self
+    }
+
+    pub fn utc_date_times(&self) -> bool {
+        self.utc_date_times
+    }
+
+    /// Set whether [`Value::Vector`](crate::Value::Vector)s can be serialized. Vectors were
+    /// introduced in Bolt v6, and serializing them fails with
+    /// [`SerializationError::VectorsNotSupported`](crate::error::SerializationError::VectorsNotSupported)
+    /// unless this is enabled.
+    pub fn with_vectors(mut self, vectors: bool) -> Self {
+        self.vectors = vectors;
+        self
+    }
+
+    pub fn vectors(&self) -> bool {
+        self.vectors
+    }
+
+    /// Set whether to use a canonical encoding, where equal values always serialize to the same
+    /// bytes. Map entries are sorted by key, all NaN floats are written as the same bit pattern,
+    /// and `-0.0` is written as `0.0`. Every value already uses the smallest marker that can hold
+    /// it.
+    ///
+    /// This is useful for hashing or caching encoded messages, and makes no difference to the
+    /// server.
+    pub fn with_canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    pub fn canonical(&self) -> bool {
+        self.canonical
+    }
+
+    /// Set the maximum number of bytes in each chunk of a serialized
+    /// [`Message`](crate::Message), not counting the 2-byte chunk header. The size is clamped to
+    /// the range `1..=65535`, and defaults to 16381.
+    pub fn with_max_chunk_size(mut self, max_chunk_size: usize) -> Self {
+        self.max_chunk_size = max_chunk_size.clamp(1, u16::MAX as usize);
+        self
+    }
+
+    pub fn max_chunk_size(&self) -> usize {
+        self.max_chunk_size
+    }
+}
+
+/// Limits that are enforced when deserializing [`Value`](crate::Value)s and
+/// [`Message`](crate::Message)s, to protect against malicious or broken peers.
+///
+/// By default, message size is unlimited, and lists, maps, and structures can be nested up to 256
+/// levels deep.
+// Options with the same decoder compare equal, unless the compiler duplicated the function
+#[allow(unpredictable_function_pointer_comparisons)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeserializeOptions {
+    pub(crate) max_message_size: Option<usize>,
+    pub(crate) max_depth: usize,
+    pub(crate) structure_decoder: Option<StructureDecoder>,
+    // Depth of the value currently being deserialized
+    pub(crate) depth: usize,
+}
+
+impl Default for DeserializeOptions {
+    fn default() -> Self {
+        Self {
+            max_message_size: None,
+            max_depth: DEFAULT_MAX_DEPTH,
+            structure_decoder: None,
+            depth: 0,
+        }
+    }
+}
+
+impl DeserializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size in bytes of an inbound message, not counting chunk headers. Reading a
+    /// larger message fails with
+    /// [`DeserializationError::MessageTooLarge`](crate::error::DeserializationError::MessageTooLarge)
+    /// before the rest of the message is buffered.
+    pub fn with_max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    pub fn max_message_size(&self) -> Option<usize> {
+        self.max_message_size
+    }
+
+    /// Set how deeply lists, maps, and structures can be nested. Deeper values fail with
+    /// [`DeserializationError::MaxDepthExceeded`](crate::error::DeserializationError::MaxDepthExceeded).
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Set a function that decodes structures whose signatures aren't used by the built-in
+    /// [`Value`](crate::Value) variants. It's called with the signature and the deserialized
+    /// fields of each such structure, and its result replaces the
+    /// [`Value::Structure`](crate::Value::Structure) the structure would otherwise be
+    /// deserialized as. An error fails deserialization of the whole value. See
+    /// [`StructureDecoder`].
+    pub fn with_structure_decoder(mut self, structure_decoder: Option<StructureDecoder>) -> Self {
+        self.structure_decoder = structure_decoder;
+        self
+    }
+
+    pub fn structure_decoder(&self) -> Option<StructureDecoder> {
+        self.structure_decoder
+    }
+
+    /// Get the options for the contents of a list, map, or structure.
+    pub(crate) fn nested(&self) -> DeserializeResult<Self> {
+        if self.depth >= self.max_depth {
+            return Err(DeserializationError::MaxDepthExceeded(self.max_depth));
+        }
+        Ok(Self {
+            depth: self.depth + 1,
+            ..*self
+        })
+    }
+}
+
 pub(crate) trait BoltValue: Sized {
     fn marker(&self) -> SerializeResult<u8>;
 
-    fn serialize(self) -> SerializeResult<Bytes>;
+    fn serialize(self) -> SerializeResult<Bytes> {
+        self.serialize_with(&SerializeOptions::default())
+    }
+
+    fn serialize_with(self, options: &SerializeOptions) -> SerializeResult<Bytes>;
 
-    fn deserialize<B: Buf + UnwindSafe>(bytes: B) -> DeserializeResult<(Self, B)>;
+    fn deserialize<B: Buf + UnwindSafe>(bytes: B) -> DeserializeResult<(Self, B)> {
+        Self::deserialize_with(bytes, &DeserializeOptions::default())
+    }
+
+    fn deserialize_with<B: Buf + UnwindSafe>(
+        bytes: B,
+        options: &DeserializeOptions,
+    ) -> DeserializeResult<(Self, B)>;
 }
 
 pub(crate) trait BoltStructure: BoltValue {
     fn signature(&self) -> u8;
 }
 
-/// Returns size and signature. Might panic - use this inside a catch_unwind block
+/// Returns size and signature.
 pub(crate) fn get_structure_info(
     marker: u8,
     bytes: &mut impl Buf,
@@ -28,10 +223,23 @@ pub(crate) fn get_structure_info(
         marker if (MARKER_TINY_STRUCT..=(MARKER_TINY_STRUCT | 0x0F)).contains(&marker) => {
             0x0F & marker as usize
         }
-        MARKER_SMALL_STRUCT => bytes.get_u8() as usize,
-        MARKER_MEDIUM_STRUCT => bytes.get_u16() as usize,
+        MARKER_SMALL_STRUCT => bytes.try_get_u8()? as usize,
+        MARKER_MEDIUM_STRUCT => bytes.try_get_u16()? as usize,
         _ => return Err(DeserializationError::InvalidMarkerByte(marker)),
     };
-    let signature = bytes.get_u8();
+    let signature = bytes.try_get_u8()?;
     Ok((size, signature))
 }
+
+/// Takes the next `len` bytes, failing if fewer remain.
+pub(crate) fn take_bytes(bytes: &mut impl Buf, len: usize) -> DeserializeResult<Bytes> {
+    let available = bytes.remaining();
+    if available < len {
+        return Err(TryGetError {
+            requested: len,
+            available,
+        }
+        .into());
+    }
+    Ok(bytes.copy_to_bytes(len))
+}
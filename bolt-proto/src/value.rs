@@ -1,5 +1,4 @@
 use std::{
-    collections::HashMap,
     mem,
     panic::{catch_unwind, UnwindSafe},
 };
@@ -12,23 +11,42 @@ use chrono_tz::Tz;
 
 pub use duration::Duration;
 pub use node::Node;
+pub use ordering::ValueKey;
 pub use path::Path;
 pub use point_2d::Point2D;
 pub use point_3d::Point3D;
 pub use relationship::Relationship;
+pub use structure::{StructureConversion, StructureDecoder};
 pub use unbound_relationship::UnboundRelationship;
+pub use value_map::{ValueMap, ValueMapIntoIter, ValueMapIter, ValueMapIterMut};
+pub use vector::Vector;
 
 use crate::error::*;
 use crate::serialization::*;
 
 pub(crate) mod conversions;
 pub(crate) mod duration;
+#[cfg(feature = "geo-types")]
+mod geo_types_conversions;
+#[cfg(feature = "geojson")]
+mod geojson_conversions;
+#[cfg(feature = "indexmap")]
+mod indexmap_conversions;
+#[cfg(feature = "jiff")]
+mod jiff_conversions;
+pub(crate) mod literal;
 pub(crate) mod node;
+pub(crate) mod ordering;
 pub(crate) mod path;
 pub(crate) mod point_2d;
 pub(crate) mod point_3d;
 pub(crate) mod relationship;
+pub(crate) mod structure;
+#[cfg(feature = "time")]
+mod time_conversions;
 pub(crate) mod unbound_relationship;
+pub(crate) mod value_map;
+pub(crate) mod vector;
 
 pub(crate) const MARKER_FALSE: u8 = 0xC2;
 pub(crate) const MARKER_TRUE: u8 = 0xC3;
@@ -65,11 +83,34 @@ pub(crate) const SIGNATURE_DATE: u8 = 0x44;
 pub(crate) const SIGNATURE_TIME: u8 = 0x54;
 pub(crate) const SIGNATURE_DATE_TIME_OFFSET: u8 = 0x46;
 pub(crate) const SIGNATURE_DATE_TIME_ZONED: u8 = 0x66;
+pub(crate) const SIGNATURE_DATE_TIME_OFFSET_UTC: u8 = 0x49;
+pub(crate) const SIGNATURE_DATE_TIME_ZONED_UTC: u8 = 0x69;
 pub(crate) const SIGNATURE_LOCAL_TIME: u8 = 0x74;
 pub(crate) const SIGNATURE_LOCAL_DATE_TIME: u8 = 0x64;
 pub(crate) const SIGNATURE_DURATION: u8 = 0x45;
 pub(crate) const SIGNATURE_POINT_2D: u8 = 0x58;
 pub(crate) const SIGNATURE_POINT_3D: u8 = 0x59;
+pub(crate) const SIGNATURE_VECTOR: u8 = 0x56;
+
+// Structures with these signatures are always deserialized as one of the built-in variants
+pub(crate) const BUILT_IN_SIGNATURES: [u8; 16] = [
+    SIGNATURE_NODE,
+    SIGNATURE_RELATIONSHIP,
+    SIGNATURE_PATH,
+    SIGNATURE_UNBOUND_RELATIONSHIP,
+    SIGNATURE_DATE,
+    SIGNATURE_TIME,
+    SIGNATURE_DATE_TIME_OFFSET,
+    SIGNATURE_DATE_TIME_ZONED,
+    SIGNATURE_DATE_TIME_OFFSET_UTC,
+    SIGNATURE_DATE_TIME_ZONED_UTC,
+    SIGNATURE_LOCAL_TIME,
+    SIGNATURE_LOCAL_DATE_TIME,
+    SIGNATURE_DURATION,
+    SIGNATURE_POINT_2D,
+    SIGNATURE_POINT_3D,
+    SIGNATURE_VECTOR,
+];
 
 /// An enum that can hold values of all Bolt-compatible types.
 ///
@@ -79,6 +120,15 @@ pub(crate) const SIGNATURE_POINT_3D: u8 = 0x59;
 /// such types, conversions are still provided, but may feel a bit clunky (for example, you can
 /// convert a `(`[`NaiveTime`](chrono::NaiveTime)`, impl `[`Offset`](chrono::Offset)`)` tuple into
 /// a [`Value::Time`]).
+///
+/// `Value` only implements [`PartialEq`], since floats and points may contain `NaN`. Use
+/// [`Value::cypher_cmp`] and [`Value::cypher_eq`] for Cypher's comparison semantics, and wrap values
+/// in a [`ValueKey`] to sort them or use them in sets and maps.
+///
+/// Structures with signatures unknown to this crate are kept as a [`Value::Structure`], so they
+/// can be sent back unchanged. See [`StructureConversion`] for converting them to your own types.
+/// Serializing a [`Value::Structure`] with the signature of a built-in variant fails with
+/// [`SerializationError::ReservedSignature`].
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     // V1-compatible value types
@@ -87,7 +137,7 @@ pub enum Value {
     Float(f64),
     Bytes(Vec<u8>),
     List(Vec<Value>),
-    Map(HashMap<String, Value>),
+    Map(ValueMap),
     Null,
     String(String),
     Node(Node),
@@ -105,13 +155,41 @@ pub enum Value {
     Duration(Duration),
     Point2D(Point2D),
     Point3D(Point3D),
+
+    // V6+-compatible value types
+    Vector(Vector),
+
+    // Any other structure, e.g. one specific to a server implementation
+    Structure { signature: u8, fields: Vec<Value> },
 }
 
-impl Eq for Value {
-    fn assert_receiver_is_total_eq(&self) {
-        if let Value::Float(_) | Value::Point2D(_) | Value::Point3D(_) = self {
-            panic!("{:?} does not impl Eq", self)
-        }
+impl Value {
+    /// Serialize this value into its PackStream representation, using the default
+    /// [`SerializeOptions`].
+    pub fn serialize(self) -> SerializeResult<Bytes> {
+        BoltValue::serialize(self)
+    }
+
+    /// Serialize this value into its PackStream representation, using the given
+    /// [`SerializeOptions`]. Use [`SerializeOptions::with_canonical`] to get the same bytes for
+    /// equal values.
+    pub fn serialize_with(self, options: &SerializeOptions) -> SerializeResult<Bytes> {
+        BoltValue::serialize_with(self, options)
+    }
+
+    /// Deserialize a value from its PackStream representation, returning the value and the
+    /// remaining bytes.
+    pub fn deserialize<B: Buf + UnwindSafe>(bytes: B) -> DeserializeResult<(Self, B)> {
+        <Self as BoltValue>::deserialize(bytes)
+    }
+
+    /// Deserialize a value from its PackStream representation, enforcing the limits in the given
+    /// [`DeserializeOptions`].
+    pub fn deserialize_with<B: Buf + UnwindSafe>(
+        bytes: B,
+        options: &DeserializeOptions,
+    ) -> DeserializeResult<(Self, B)> {
+        <Self as BoltValue>::deserialize_with(bytes, options)
     }
 }
 
@@ -170,10 +248,17 @@ impl BoltValue for Value {
             Value::Duration(duration) => duration.marker(),
             Value::Point2D(point_2d) => point_2d.marker(),
             Value::Point3D(point_3d) => point_3d.marker(),
+            Value::Vector(_) => Ok(MARKER_TINY_STRUCT | 2),
+            Value::Structure { fields, .. } => match fields.len() {
+                0..=15 => Ok(MARKER_TINY_STRUCT | fields.len() as u8),
+                16..=255 => Ok(MARKER_SMALL_STRUCT),
+                256..=65_535 => Ok(MARKER_MEDIUM_STRUCT),
+                len => Err(SerializationError::ValueTooLarge(len)),
+            },
         }
     }
 
-    fn serialize(self) -> SerializeResult<Bytes> {
+    fn serialize_with(self, options: &SerializeOptions) -> SerializeResult<Bytes> {
         let marker = self.marker()?;
         match self {
             Value::Boolean(true) => Ok(Bytes::from_static(&[MARKER_TRUE])),
@@ -207,7 +292,14 @@ impl BoltValue for Value {
                 let mut bytes =
                     BytesMut::with_capacity(mem::size_of::<u8>() + mem::size_of::<f64>());
                 bytes.put_u8(marker);
-                bytes.put_f64(f);
+                if options.canonical && f.is_nan() {
+                    bytes.put_f64(f64::NAN);
+                } else if options.canonical && f == 0.0 {
+                    // -0.0 is equal to 0.0, but has a different bit pattern
+                    bytes.put_f64(0.0);
+                } else {
+                    bytes.put_f64(f);
+                }
                 Ok(bytes.freeze())
             }
             Value::Bytes(bytes) => {
@@ -233,7 +325,7 @@ impl BoltValue for Value {
                 let mut value_bytes_vec: Vec<Bytes> = Vec::with_capacity(length);
 
                 for value in list {
-                    let value_bytes = value.serialize()?;
+                    let value_bytes = value.serialize_with(options)?;
                     total_value_bytes += value_bytes.len();
                     value_bytes_vec.push(value_bytes);
                 }
@@ -264,9 +356,14 @@ impl BoltValue for Value {
 
                 let mut total_value_bytes: usize = 0;
                 let mut value_bytes_vec: Vec<Bytes> = Vec::with_capacity(length);
-                for (key, val) in map {
+                let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+                if options.canonical {
+                    // Keys are unique, so an unstable sort is deterministic
+                    entries.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+                }
+                for (key, val) in entries {
                     let key_bytes: Bytes = Value::String(key).serialize()?;
-                    let val_bytes: Bytes = val.serialize()?;
+                    let val_bytes: Bytes = val.serialize_with(options)?;
                     total_value_bytes += key_bytes.len() + val_bytes.len();
                     value_bytes_vec.push(key_bytes);
                     value_bytes_vec.push(val_bytes);
@@ -311,10 +408,10 @@ impl BoltValue for Value {
 
                 Ok(bytes.freeze())
             }
-            Value::Node(node) => node.serialize(),
-            Value::Relationship(rel) => rel.serialize(),
-            Value::Path(path) => path.serialize(),
-            Value::UnboundRelationship(unbound_rel) => unbound_rel.serialize(),
+            Value::Node(node) => node.serialize_with(options),
+            Value::Relationship(rel) => rel.serialize_with(options),
+            Value::Path(path) => path.serialize_with(options),
+            Value::UnboundRelationship(unbound_rel) => unbound_rel.serialize_with(options),
             Value::Date(date) => Ok(vec![marker, SIGNATURE_DATE]
                 .into_iter()
                 .chain(
@@ -339,26 +436,43 @@ impl BoltValue for Value {
                     Value::from(offset.fix().local_minus_utc()).serialize()?,
                 )
                 .collect()),
-            Value::DateTimeOffset(date_time_offset) => Ok(vec![marker, SIGNATURE_DATE_TIME_OFFSET]
-                .into_iter()
-                .chain(
+            Value::DateTimeOffset(date_time_offset) => {
+                let (signature, seconds) = if options.utc_date_times {
                     // Seconds since UNIX epoch
-                    Value::from(date_time_offset.timestamp()).serialize()?,
-                )
-                .chain(
+                    (SIGNATURE_DATE_TIME_OFFSET_UTC, date_time_offset.timestamp())
+                } else {
+                    // Seconds since UNIX epoch in local time
+                    (
+                        SIGNATURE_DATE_TIME_OFFSET,
+                        date_time_offset.naive_local().and_utc().timestamp(),
+                    )
+                };
+                Ok(vec![marker, signature]
+                    .into_iter()
+                    .chain(Value::from(seconds).serialize()?)
                     // Nanoseconds
-                    Value::from(i64::from(date_time_offset.nanosecond())).serialize()?,
-                )
-                .chain(
+                    .chain(Value::from(i64::from(date_time_offset.nanosecond())).serialize()?)
                     // Timezone offset
-                    Value::from(date_time_offset.offset().fix().local_minus_utc()).serialize()?,
-                )
-                .collect()),
+                    .chain(
+                        Value::from(date_time_offset.offset().fix().local_minus_utc())
+                            .serialize()?,
+                    )
+                    .collect())
+            }
             Value::DateTimeZoned(date_time_zoned) => {
-                Ok(vec![marker, SIGNATURE_DATE_TIME_ZONED]
-                    .into_iter()
+                let (signature, seconds) = if options.utc_date_times {
                     // Seconds since UNIX epoch
-                    .chain(Value::from(date_time_zoned.timestamp()).serialize()?)
+                    (SIGNATURE_DATE_TIME_ZONED_UTC, date_time_zoned.timestamp())
+                } else {
+                    // Seconds since UNIX epoch in local time
+                    (
+                        SIGNATURE_DATE_TIME_ZONED,
+                        date_time_zoned.naive_local().and_utc().timestamp(),
+                    )
+                };
+                Ok(vec![marker, signature]
+                    .into_iter()
+                    .chain(Value::from(seconds).serialize()?)
                     // Nanoseconds
                     .chain(Value::from(i64::from(date_time_zoned.nanosecond())).serialize()?)
                     // Timezone ID
@@ -379,19 +493,89 @@ impl BoltValue for Value {
             Value::LocalDateTime(local_date_time) => Ok(vec![marker, SIGNATURE_LOCAL_DATE_TIME]
                 .into_iter()
                 // Seconds since UNIX epoch
-                .chain(Value::from(local_date_time.timestamp()).serialize()?)
+                .chain(Value::from(local_date_time.and_utc().timestamp()).serialize()?)
                 // Nanoseconds
                 .chain(Value::from(i64::from(local_date_time.nanosecond())).serialize()?)
                 .collect()),
-            Value::Duration(duration) => duration.serialize(),
-            Value::Point2D(point_2d) => point_2d.serialize(),
-            Value::Point3D(point_3d) => point_3d.serialize(),
+            Value::Duration(duration) => duration.serialize_with(options),
+            Value::Point2D(point_2d) => point_2d.serialize_with(options),
+            Value::Point3D(point_3d) => point_3d.serialize_with(options),
+            Value::Vector(vector) => {
+                if !options.vectors {
+                    return Err(SerializationError::VectorsNotSupported);
+                }
+                let length = vector.data_len();
+                // Marker and signature, a 1-byte ByteArray with the element type, then a large
+                // ByteArray in the worst case, with marker byte, 32-bit size value, and length
+                let mut bytes = BytesMut::with_capacity(
+                    mem::size_of::<u8>() * 5 + mem::size_of::<u32>() + length,
+                );
+
+                bytes.put_u8(marker);
+                bytes.put_u8(SIGNATURE_VECTOR);
+                bytes.put_slice(&[MARKER_SMALL_BYTES, 1, vector.type_marker()]);
+                match length {
+                    0..=255 => {
+                        bytes.put_u8(MARKER_SMALL_BYTES);
+                        bytes.put_u8(length as u8);
+                    }
+                    256..=65_535 => {
+                        bytes.put_u8(MARKER_MEDIUM_BYTES);
+                        bytes.put_u16(length as u16);
+                    }
+                    65_536..=2_147_483_647 => {
+                        bytes.put_u8(MARKER_LARGE_BYTES);
+                        bytes.put_u32(length as u32);
+                    }
+                    _ => return Err(SerializationError::ValueTooLarge(length)),
+                }
+                vector.put_data(&mut bytes);
+
+                Ok(bytes.freeze())
+            }
+            Value::Structure { signature, fields } => {
+                if BUILT_IN_SIGNATURES.contains(&signature) {
+                    return Err(SerializationError::ReservedSignature(signature));
+                }
+                let length = fields.len();
+
+                let mut total_value_bytes: usize = 0;
+                let mut value_bytes_vec: Vec<Bytes> = Vec::with_capacity(length);
+                for field in fields {
+                    let value_bytes = field.serialize_with(options)?;
+                    total_value_bytes += value_bytes.len();
+                    value_bytes_vec.push(value_bytes);
+                }
+
+                // Worst case is a medium structure, with marker byte, 16-bit size value,
+                // signature, and all the Value bytes
+                let mut bytes = BytesMut::with_capacity(
+                    mem::size_of::<u8>() * 2 + mem::size_of::<u16>() + total_value_bytes,
+                );
+
+                bytes.put_u8(marker);
+                match length {
+                    0..=15 => {} // The marker contains the length
+                    16..=255 => bytes.put_u8(length as u8),
+                    _ => bytes.put_u16(length as u16),
+                }
+                bytes.put_u8(signature);
+
+                for value_bytes in value_bytes_vec {
+                    bytes.put(value_bytes);
+                }
+
+                Ok(bytes.freeze())
+            }
         }
     }
 
-    fn deserialize<B: Buf + UnwindSafe>(mut bytes: B) -> DeserializeResult<(Self, B)> {
+    fn deserialize_with<B: Buf + UnwindSafe>(
+        mut bytes: B,
+        options: &DeserializeOptions,
+    ) -> DeserializeResult<(Self, B)> {
         catch_unwind(move || {
-            let marker = bytes.get_u8();
+            let marker = bytes.try_get_u8()?;
             match marker {
                 // Boolean
                 MARKER_TRUE => Ok((Value::Boolean(true), bytes)),
@@ -401,21 +585,21 @@ impl BoltValue for Value {
                     Ok((Value::Integer(i64::from(marker as i8)), bytes))
                 }
                 // Other int types
-                MARKER_INT_8 => Ok((Value::Integer(i64::from(bytes.get_i8())), bytes)),
-                MARKER_INT_16 => Ok((Value::Integer(i64::from(bytes.get_i16())), bytes)),
-                MARKER_INT_32 => Ok((Value::Integer(i64::from(bytes.get_i32())), bytes)),
-                MARKER_INT_64 => Ok((Value::Integer(bytes.get_i64()), bytes)),
+                MARKER_INT_8 => Ok((Value::Integer(i64::from(bytes.try_get_i8()?)), bytes)),
+                MARKER_INT_16 => Ok((Value::Integer(i64::from(bytes.try_get_i16()?)), bytes)),
+                MARKER_INT_32 => Ok((Value::Integer(i64::from(bytes.try_get_i32()?)), bytes)),
+                MARKER_INT_64 => Ok((Value::Integer(bytes.try_get_i64()?), bytes)),
                 // Float
-                MARKER_FLOAT => Ok((Value::Float(bytes.get_f64()), bytes)),
+                MARKER_FLOAT => Ok((Value::Float(bytes.try_get_f64()?), bytes)),
                 // Byte array
                 MARKER_SMALL_BYTES | MARKER_MEDIUM_BYTES | MARKER_LARGE_BYTES => {
                     let size = match marker {
-                        MARKER_SMALL_BYTES => bytes.get_u8() as usize,
-                        MARKER_MEDIUM_BYTES => bytes.get_u16() as usize,
-                        MARKER_LARGE_BYTES => bytes.get_u32() as usize,
+                        MARKER_SMALL_BYTES => bytes.try_get_u8()? as usize,
+                        MARKER_MEDIUM_BYTES => bytes.try_get_u16()? as usize,
+                        MARKER_LARGE_BYTES => bytes.try_get_u32()? as usize,
                         _ => unreachable!(),
                     };
-                    Ok((Value::Bytes(bytes.copy_to_bytes(size).to_vec()), bytes))
+                    Ok((Value::Bytes(take_bytes(&mut bytes, size)?.to_vec()), bytes))
                 }
                 // List
                 marker
@@ -431,14 +615,16 @@ impl BoltValue for Value {
                         {
                             0x0F & marker as usize
                         }
-                        MARKER_SMALL_LIST => bytes.get_u8() as usize,
-                        MARKER_MEDIUM_LIST => bytes.get_u16() as usize,
-                        MARKER_LARGE_LIST => bytes.get_u32() as usize,
+                        MARKER_SMALL_LIST => bytes.try_get_u8()? as usize,
+                        MARKER_MEDIUM_LIST => bytes.try_get_u16()? as usize,
+                        MARKER_LARGE_LIST => bytes.try_get_u32()? as usize,
                         _ => unreachable!(),
                     };
-                    let mut list: Vec<Value> = Vec::with_capacity(size);
+                    let options = options.nested()?;
+                    // Don't trust the size for allocation, each value takes at least one byte
+                    let mut list: Vec<Value> = Vec::with_capacity(size.min(bytes.remaining()));
                     for _ in 0..size {
-                        let (v, b) = Value::deserialize(bytes)?;
+                        let (v, b) = Value::deserialize_with(bytes, &options)?;
                         bytes = b;
                         list.push(v);
                     }
@@ -458,20 +644,21 @@ impl BoltValue for Value {
                         {
                             0x0F & marker as usize
                         }
-                        MARKER_SMALL_MAP => bytes.get_u8() as usize,
-                        MARKER_MEDIUM_MAP => bytes.get_u16() as usize,
-                        MARKER_LARGE_MAP => bytes.get_u32() as usize,
+                        MARKER_SMALL_MAP => bytes.try_get_u8()? as usize,
+                        MARKER_MEDIUM_MAP => bytes.try_get_u16()? as usize,
+                        MARKER_LARGE_MAP => bytes.try_get_u32()? as usize,
                         _ => unreachable!(),
                     };
 
-                    let mut hash_map: HashMap<std::string::String, Value> =
-                        HashMap::with_capacity(size);
+                    let options = options.nested()?;
+                    // Don't trust the size for allocation, each entry takes at least two bytes
+                    let mut hash_map = ValueMap::with_capacity(size.min(bytes.remaining() / 2));
                     for _ in 0..size {
-                        let (value, remaining) = Value::deserialize(bytes)?;
+                        let (value, remaining) = Value::deserialize_with(bytes, &options)?;
                         bytes = remaining;
                         match value {
                             Value::String(key) => {
-                                let (value, remaining) = Value::deserialize(bytes)?;
+                                let (value, remaining) = Value::deserialize_with(bytes, &options)?;
                                 bytes = remaining;
                                 hash_map.insert(key, value);
                             }
@@ -498,14 +685,14 @@ impl BoltValue for Value {
                         {
                             0x0F & marker as usize
                         }
-                        MARKER_SMALL_STRING => bytes.get_u8() as usize,
-                        MARKER_MEDIUM_STRING => bytes.get_u16() as usize,
-                        MARKER_LARGE_STRING => bytes.get_u32() as usize,
+                        MARKER_SMALL_STRING => bytes.try_get_u8()? as usize,
+                        MARKER_MEDIUM_STRING => bytes.try_get_u16()? as usize,
+                        MARKER_LARGE_STRING => bytes.try_get_u32()? as usize,
                         _ => unreachable!(),
                     };
 
                     Ok((
-                        Value::String(String::from_utf8(bytes.copy_to_bytes(size).to_vec())?),
+                        Value::String(String::from_utf8(take_bytes(&mut bytes, size)?.to_vec())?),
                         bytes,
                     ))
                 }
@@ -514,7 +701,7 @@ impl BoltValue for Value {
                     if (MARKER_TINY_STRUCT..=(MARKER_TINY_STRUCT | 0x0F)).contains(&marker)
                         || matches!(marker, MARKER_SMALL_STRUCT | MARKER_MEDIUM_STRUCT) =>
                 {
-                    deserialize_structure(marker, bytes)
+                    deserialize_structure(marker, bytes, options)
                 }
                 _ => Err(DeserializationError::InvalidMarkerByte(marker)),
             }
@@ -524,16 +711,16 @@ impl BoltValue for Value {
 }
 
 macro_rules! deserialize_struct {
-    ($name:ident, $bytes:ident) => {{
-        let (value, remaining) = $name::deserialize($bytes)?;
+    ($name:ident, $bytes:ident, $options:expr) => {{
+        let (value, remaining) = $name::deserialize_with($bytes, $options)?;
         $bytes = remaining;
         Ok((Value::$name(value), $bytes))
     }};
 }
 
 macro_rules! deserialize_variant {
-    ($name:ident, $bytes:ident) => {{
-        let (value, remaining) = Value::deserialize($bytes)?;
+    ($name:ident, $bytes:ident, $options:expr) => {{
+        let (value, remaining) = Value::deserialize_with($bytes, $options)?;
         $bytes = remaining;
         if let Value::$name(inner) = value {
             inner
@@ -546,92 +733,166 @@ macro_rules! deserialize_variant {
 fn deserialize_structure<B: Buf + UnwindSafe>(
     marker: u8,
     mut bytes: B,
+    options: &DeserializeOptions,
 ) -> DeserializeResult<(Value, B)> {
-    let (_, signature) = get_structure_info(marker, &mut bytes)?;
+    let (size, signature) = get_structure_info(marker, &mut bytes)?;
+    let options = options.nested()?;
 
     match signature {
-        SIGNATURE_NODE => deserialize_struct!(Node, bytes),
-        SIGNATURE_RELATIONSHIP => deserialize_struct!(Relationship, bytes),
-        SIGNATURE_PATH => deserialize_struct!(Path, bytes),
-        SIGNATURE_UNBOUND_RELATIONSHIP => deserialize_struct!(UnboundRelationship, bytes),
+        SIGNATURE_NODE => deserialize_struct!(Node, bytes, &options),
+        SIGNATURE_RELATIONSHIP => deserialize_struct!(Relationship, bytes, &options),
+        SIGNATURE_PATH => deserialize_struct!(Path, bytes, &options),
+        SIGNATURE_UNBOUND_RELATIONSHIP => deserialize_struct!(UnboundRelationship, bytes, &options),
         SIGNATURE_DATE => {
-            let days_since_epoch: i64 = deserialize_variant!(Integer, bytes);
-            Ok((
-                Value::Date(
-                    NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()
-                        + chrono::Duration::days(days_since_epoch),
-                ),
-                bytes,
-            ))
+            let days_since_epoch: i64 = deserialize_variant!(Integer, bytes, &options);
+            let date = chrono::Duration::try_days(days_since_epoch)
+                .and_then(|days| {
+                    NaiveDate::from_ymd_opt(1970, 1, 1)
+                        .unwrap()
+                        .checked_add_signed(days)
+                })
+                .ok_or(DeserializationError::InvalidTemporal("date out of range"))?;
+            Ok((Value::Date(date), bytes))
         }
         SIGNATURE_TIME => {
-            let nanos_since_midnight: i64 = deserialize_variant!(Integer, bytes);
-            let zone_offset: i32 = deserialize_variant!(Integer, bytes) as i32;
+            let nanos_since_midnight: i64 = deserialize_variant!(Integer, bytes, &options);
+            let zone_offset: i32 = deserialize_variant!(Integer, bytes, &options) as i32;
             Ok((
-                Value::Time(
-                    NaiveTime::from_num_seconds_from_midnight_opt(
-                        (nanos_since_midnight / 1_000_000_000) as u32,
-                        (nanos_since_midnight % 1_000_000_000) as u32,
-                    )
-                    .unwrap(),
-                    FixedOffset::east_opt(zone_offset).unwrap(),
-                ),
+                Value::Time(time(nanos_since_midnight)?, offset(zone_offset)?),
                 bytes,
             ))
         }
         SIGNATURE_DATE_TIME_OFFSET => {
-            let epoch_seconds: i64 = deserialize_variant!(Integer, bytes);
-            let nanos: i64 = deserialize_variant!(Integer, bytes);
-            let offset_seconds: i32 = deserialize_variant!(Integer, bytes) as i32;
+            let local_seconds: i64 = deserialize_variant!(Integer, bytes, &options);
+            let nanos: i64 = deserialize_variant!(Integer, bytes, &options);
+            let offset_seconds: i32 = deserialize_variant!(Integer, bytes, &options) as i32;
+            let local = date_time(local_seconds, nanos)?;
+            let date_time_offset = offset(offset_seconds)?
+                .from_local_datetime(&local)
+                .single()
+                .ok_or(DeserializationError::InvalidTemporal(
+                    "date-time out of range",
+                ))?;
+            Ok((Value::DateTimeOffset(date_time_offset), bytes))
+        }
+        SIGNATURE_DATE_TIME_OFFSET_UTC => {
+            let epoch_seconds: i64 = deserialize_variant!(Integer, bytes, &options);
+            let nanos: i64 = deserialize_variant!(Integer, bytes, &options);
+            let offset_seconds: i32 = deserialize_variant!(Integer, bytes, &options) as i32;
+            let utc = date_time(epoch_seconds, nanos)?;
             Ok((
-                Value::DateTimeOffset(DateTime::from_utc(
-                    NaiveDateTime::from_timestamp_opt(epoch_seconds, nanos as u32).unwrap(),
-                    FixedOffset::east_opt(offset_seconds).unwrap(),
-                )),
+                Value::DateTimeOffset(offset(offset_seconds)?.from_utc_datetime(&utc)),
                 bytes,
             ))
         }
         SIGNATURE_DATE_TIME_ZONED => {
-            let epoch_seconds: i64 = deserialize_variant!(Integer, bytes);
-            let nanos: i64 = deserialize_variant!(Integer, bytes);
-            let timezone_id: String = deserialize_variant!(String, bytes);
-            let timezone: Tz = timezone_id.parse().unwrap();
+            let local_seconds: i64 = deserialize_variant!(Integer, bytes, &options);
+            let nanos: i64 = deserialize_variant!(Integer, bytes, &options);
+            let timezone_id: String = deserialize_variant!(String, bytes, &options);
+            let timezone = timezone(timezone_id)?;
+            let local = date_time(local_seconds, nanos)?;
+            // Local times are ambiguous around DST transitions. Like the official drivers, pick
+            // the earlier instant for overlaps, and use the offset from before the transition for
+            // gaps.
+            let date_time_zoned = timezone
+                .from_local_datetime(&local)
+                .earliest()
+                .or_else(|| {
+                    let before = local.checked_sub_signed(chrono::Duration::days(1))?;
+                    let offset = timezone.offset_from_utc_datetime(&before).fix();
+                    Some(timezone.from_utc_datetime(&local.checked_sub_offset(offset)?))
+                })
+                .ok_or(DeserializationError::InvalidTemporal(
+                    "date-time out of range",
+                ))?;
+            Ok((Value::DateTimeZoned(date_time_zoned), bytes))
+        }
+        SIGNATURE_DATE_TIME_ZONED_UTC => {
+            let epoch_seconds: i64 = deserialize_variant!(Integer, bytes, &options);
+            let nanos: i64 = deserialize_variant!(Integer, bytes, &options);
+            let timezone_id: String = deserialize_variant!(String, bytes, &options);
+            let timezone = timezone(timezone_id)?;
+            let utc = date_time(epoch_seconds, nanos)?;
             Ok((
-                Value::DateTimeZoned(timezone.timestamp_opt(epoch_seconds, nanos as u32).unwrap()),
+                Value::DateTimeZoned(timezone.from_utc_datetime(&utc)),
                 bytes,
             ))
         }
         SIGNATURE_LOCAL_TIME => {
-            let nanos_since_midnight: i64 = deserialize_variant!(Integer, bytes);
-            Ok((
-                Value::LocalTime(
-                    NaiveTime::from_num_seconds_from_midnight_opt(
-                        (nanos_since_midnight / 1_000_000_000) as u32,
-                        (nanos_since_midnight % 1_000_000_000) as u32,
-                    )
-                    .unwrap(),
-                ),
-                bytes,
-            ))
+            let nanos_since_midnight: i64 = deserialize_variant!(Integer, bytes, &options);
+            Ok((Value::LocalTime(time(nanos_since_midnight)?), bytes))
         }
         SIGNATURE_LOCAL_DATE_TIME => {
-            let epoch_seconds: i64 = deserialize_variant!(Integer, bytes);
-            let nanos: i64 = deserialize_variant!(Integer, bytes);
+            let epoch_seconds: i64 = deserialize_variant!(Integer, bytes, &options);
+            let nanos: i64 = deserialize_variant!(Integer, bytes, &options);
             Ok((
-                Value::LocalDateTime(
-                    NaiveDateTime::from_timestamp_opt(epoch_seconds, nanos as u32).unwrap(),
-                ),
+                Value::LocalDateTime(date_time(epoch_seconds, nanos)?),
                 bytes,
             ))
         }
-        SIGNATURE_DURATION => deserialize_struct!(Duration, bytes),
-        SIGNATURE_POINT_2D => deserialize_struct!(Point2D, bytes),
-        SIGNATURE_POINT_3D => deserialize_struct!(Point3D, bytes),
-        _ => Err(DeserializationError::InvalidSignatureByte(signature)),
+        SIGNATURE_DURATION => deserialize_struct!(Duration, bytes, &options),
+        SIGNATURE_POINT_2D => deserialize_struct!(Point2D, bytes, &options),
+        SIGNATURE_POINT_3D => deserialize_struct!(Point3D, bytes, &options),
+        SIGNATURE_VECTOR => {
+            let type_marker: Vec<u8> = deserialize_variant!(Bytes, bytes, &options);
+            let data: Vec<u8> = deserialize_variant!(Bytes, bytes, &options);
+            match type_marker[..] {
+                [type_marker] => Ok((Value::Vector(Vector::from_data(type_marker, &data)?), bytes)),
+                _ => Err(DeserializationError::InvalidVector(
+                    "element type must be a single byte",
+                )),
+            }
+        }
+        _ => {
+            let mut fields = Vec::with_capacity(size.min(bytes.remaining()));
+            for _ in 0..size {
+                let (field, remaining) = Value::deserialize_with(bytes, &options)?;
+                bytes = remaining;
+                fields.push(field);
+            }
+            match options.structure_decoder {
+                Some(decode) => Ok((decode(signature, fields)?, bytes)),
+                None => Ok((Value::Structure { signature, fields }, bytes)),
+            }
+        }
     }
 }
 
+fn time(nanos_since_midnight: i64) -> DeserializeResult<NaiveTime> {
+    u32::try_from(nanos_since_midnight / 1_000_000_000)
+        .ok()
+        .and_then(|seconds| {
+            NaiveTime::from_num_seconds_from_midnight_opt(
+                seconds,
+                (nanos_since_midnight % 1_000_000_000) as u32,
+            )
+        })
+        .ok_or(DeserializationError::InvalidTemporal("time out of range"))
+}
+
+fn date_time(seconds: i64, nanos: i64) -> DeserializeResult<NaiveDateTime> {
+    u32::try_from(nanos)
+        .ok()
+        .and_then(|nanos| DateTime::from_timestamp(seconds, nanos))
+        .map(|date_time| date_time.naive_utc())
+        .ok_or(DeserializationError::InvalidTemporal(
+            "date-time out of range",
+        ))
+}
+
+fn offset(offset_seconds: i32) -> DeserializeResult<FixedOffset> {
+    FixedOffset::east_opt(offset_seconds)
+        .ok_or(DeserializationError::InvalidTemporal("offset out of range"))
+}
+
+fn timezone(timezone_id: String) -> DeserializeResult<Tz> {
+    timezone_id
+        .parse()
+        .map_err(|_| DeserializationError::InvalidTimezone(timezone_id))
+}
+
 #[cfg(test)]
+#[allow(clippy::identity_op)]
 mod tests {
     use std::collections::HashMap;
 
@@ -1014,7 +1275,8 @@ mod tests {
         MARKER_TINY_STRUCT | 3,
         &[SIGNATURE_DATE_TIME_OFFSET],
         &[MARKER_INT_64],
-        2556143999_i64.to_be_bytes(),
+        // Seconds in local time
+        2556125999_i64.to_be_bytes(),
         &[10],
         &[MARKER_INT_16],
         (-5 * 3600_i16).to_be_bytes()
@@ -1032,13 +1294,405 @@ mod tests {
         MARKER_TINY_STRUCT | 3,
         &[SIGNATURE_DATE_TIME_ZONED],
         &[MARKER_INT_32],
-        1911969001_i32.to_be_bytes(),
+        // Seconds in local time
+        1911997801_i32.to_be_bytes(),
         &[MARKER_INT_16],
         12345_i16.to_be_bytes(),
         &[MARKER_SMALL_STRING, 16],
         b"Asia/Ulaanbaatar"
     );
 
+    #[test]
+    fn date_time_utc() {
+        let options = SerializeOptions::new().with_utc_date_times(true);
+
+        let offset = Value::DateTimeOffset(
+            FixedOffset::east_opt(-5 * 3600).unwrap().from_utc_datetime(
+                &NaiveDate::from_ymd_opt(2050, 12, 31)
+                    .unwrap()
+                    .and_hms_nano_opt(23, 59, 59, 10)
+                    .unwrap(),
+            ),
+        );
+        let bytes: Bytes = vec![MARKER_TINY_STRUCT | 3, SIGNATURE_DATE_TIME_OFFSET_UTC]
+            .into_iter()
+            .chain([MARKER_INT_64])
+            .chain(2556143999_i64.to_be_bytes())
+            .chain([10, MARKER_INT_16])
+            .chain((-5 * 3600_i16).to_be_bytes())
+            .collect();
+        assert_eq!(offset.clone().serialize_with(&options).unwrap(), bytes);
+        assert_eq!(Value::deserialize(bytes).unwrap().0, offset);
+
+        let zoned = Value::DateTimeZoned(
+            chrono_tz::Asia::Ulaanbaatar
+                .with_ymd_and_hms(2030, 8, 3, 14, 30, 1)
+                .unwrap(),
+        );
+        let bytes = zoned.clone().serialize_with(&options).unwrap();
+        assert_eq!(bytes[1], SIGNATURE_DATE_TIME_ZONED_UTC);
+        assert_eq!(bytes[3..7], 1911969001_i32.to_be_bytes());
+        assert_eq!(Value::deserialize(bytes).unwrap().0, zoned);
+    }
+
+    #[test]
+    fn date_time_zoned_dst() {
+        // 2021-10-31 02:30 happens twice in Europe/Berlin; only the UTC encoding is unambiguous
+        let at_utc = |hour| {
+            Value::DateTimeZoned(
+                chrono_tz::Europe::Berlin.from_utc_datetime(
+                    &NaiveDate::from_ymd_opt(2021, 10, 31)
+                        .unwrap()
+                        .and_hms_opt(hour, 30, 0)
+                        .unwrap(),
+                ),
+            )
+        };
+        let (first, second) = (at_utc(0), at_utc(1));
+        let utc = SerializeOptions::new().with_utc_date_times(true);
+        for value in [&first, &second] {
+            let bytes = value.clone().serialize_with(&utc).unwrap();
+            assert_eq!(&Value::deserialize(bytes).unwrap().0, value);
+        }
+
+        // The legacy encoding resolves the overlap to the earlier instant
+        let legacy = second.clone().serialize().unwrap();
+        assert_eq!(Value::deserialize(legacy).unwrap().0, first);
+
+        // Times in a gap are shifted forward by the length of the gap
+        let gap = NaiveDate::from_ymd_opt(2021, 3, 28)
+            .unwrap()
+            .and_hms_opt(2, 30, 0)
+            .unwrap();
+        let bytes: Bytes = vec![MARKER_TINY_STRUCT | 3, SIGNATURE_DATE_TIME_ZONED]
+            .into_iter()
+            .chain(Value::from(gap.and_utc().timestamp()).serialize().unwrap())
+            .chain([0])
+            .chain(Value::from("Europe/Berlin").serialize().unwrap())
+            .collect();
+        assert_eq!(
+            Value::deserialize(bytes).unwrap().0,
+            Value::DateTimeZoned(
+                chrono_tz::Europe::Berlin
+                    .with_ymd_and_hms(2021, 3, 28, 3, 30, 0)
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn serialize_options_for_version() {
+        use crate::version::*;
+
+        assert!(!SerializeOptions::for_version(V4_4, false).utc_date_times());
+        assert!(!SerializeOptions::for_version(V4_2, true).utc_date_times());
+        assert!(SerializeOptions::for_version(V4_3, true).utc_date_times());
+        assert!(SerializeOptions::for_version(V4_4, true).utc_date_times());
+        assert!(SerializeOptions::for_version(0x0005, false).utc_date_times());
+        assert!(!SerializeOptions::for_version(0x0005, false).vectors());
+        assert!(SerializeOptions::for_version(0x0006, false).vectors());
+    }
+
+    #[test]
+    fn max_depth() {
+        let mut value = Value::Null;
+        for _ in 0..10 {
+            value = Value::List(vec![value]);
+        }
+        let bytes = value.clone().serialize().unwrap();
+
+        let options = DeserializeOptions::new().with_max_depth(10);
+        assert_eq!(
+            Value::deserialize_with(bytes.clone(), &options).unwrap().0,
+            value
+        );
+        let options = DeserializeOptions::new().with_max_depth(9);
+        assert!(matches!(
+            Value::deserialize_with(bytes, &options),
+            Err(DeserializationError::MaxDepthExceeded(9))
+        ));
+
+        // Structures count as a level too
+        let node = Value::from(Node::new(
+            1,
+            vec![],
+            HashMap::from([(String::from("a"), Value::List(vec![]))]),
+        ));
+        let bytes = node.clone().serialize().unwrap();
+        let options = DeserializeOptions::new().with_max_depth(2);
+        assert!(matches!(
+            Value::deserialize_with(bytes.clone(), &options),
+            Err(DeserializationError::MaxDepthExceeded(2))
+        ));
+        let options = DeserializeOptions::new().with_max_depth(3);
+        assert_eq!(Value::deserialize_with(bytes, &options).unwrap().0, node);
+    }
+
+    #[test]
+    fn untrusted_sizes() {
+        // The declared sizes are huge, but the input is not, so this should fail without
+        // allocating space for billions of elements
+        for marker in [MARKER_LARGE_LIST, MARKER_LARGE_MAP] {
+            let bytes = Bytes::from(vec![marker, 0xFF, 0xFF, 0xFF, 0xFF, MARKER_NULL]);
+            assert!(Value::deserialize(bytes).is_err());
+        }
+    }
+
+    #[test]
+    fn truncated_input() {
+        let inputs: [&[u8]; 6] = [
+            &[MARKER_INT_16, 0x01],
+            &[MARKER_FLOAT, 0x00, 0x00],
+            &[MARKER_SMALL_STRING, 5, b'a'],
+            &[MARKER_MEDIUM_BYTES, 0xFF],
+            &[MARKER_TINY_LIST | 2, 1],
+            &[MARKER_SMALL_STRUCT],
+        ];
+        for bytes in inputs {
+            assert!(matches!(
+                Value::deserialize(bytes),
+                Err(DeserializationError::UnexpectedEnd(_))
+            ));
+        }
+    }
+
+    // Serialize a structure with a built-in signature, bypassing the check for reserved signatures
+    fn raw_structure(signature: u8, fields: Vec<Value>) -> Bytes {
+        let bytes = Value::Structure {
+            signature: 0x00,
+            fields,
+        }
+        .serialize()
+        .unwrap();
+        let mut bytes = BytesMut::from(&bytes[..]);
+        bytes[1] = signature;
+        bytes.freeze()
+    }
+
+    #[test]
+    fn invalid_temporal_values() {
+        let structures = [
+            (SIGNATURE_DATE, vec![Value::from(i64::MAX)]),
+            (SIGNATURE_LOCAL_TIME, vec![Value::from(-1)]),
+            (SIGNATURE_TIME, vec![Value::from(0), Value::from(100_000)]),
+            (
+                SIGNATURE_LOCAL_DATE_TIME,
+                vec![Value::from(i64::MIN), Value::from(0)],
+            ),
+            (
+                SIGNATURE_LOCAL_DATE_TIME,
+                vec![Value::from(0), Value::from(-1)],
+            ),
+            (
+                SIGNATURE_DATE_TIME_OFFSET,
+                vec![Value::from(i64::MAX), Value::from(0), Value::from(0)],
+            ),
+            (
+                SIGNATURE_DATE_TIME_ZONED,
+                vec![
+                    // Before the earliest representable instant once the offset is applied
+                    Value::from(NaiveDateTime::MIN.and_utc().timestamp()),
+                    Value::from(0),
+                    Value::from("Asia/Tokyo"),
+                ],
+            ),
+        ];
+        for (signature, fields) in structures {
+            let bytes = raw_structure(signature, fields);
+            assert!(matches!(
+                Value::deserialize(bytes),
+                Err(DeserializationError::InvalidTemporal(_))
+            ));
+        }
+
+        let bytes = raw_structure(
+            SIGNATURE_DATE_TIME_ZONED_UTC,
+            vec![
+                Value::from(0),
+                Value::from(0),
+                Value::from("Nowhere/Special"),
+            ],
+        );
+        assert!(matches!(
+            Value::deserialize(bytes),
+            Err(DeserializationError::InvalidTimezone(timezone)) if timezone == "Nowhere/Special"
+        ));
+    }
+
+    #[test]
+    fn canonical() {
+        let options = SerializeOptions::new().with_canonical(true);
+
+        let keys = ["b", "c", "a", "e", "d", "g", "f"];
+        let forward: HashMap<&str, i8> = keys.iter().map(|&key| (key, 1)).collect();
+        let backward: HashMap<&str, i8> = keys.iter().rev().map(|&key| (key, 1)).collect();
+        let bytes = Value::from(forward).serialize_with(&options).unwrap();
+        assert_eq!(
+            Value::from(backward).serialize_with(&options).unwrap(),
+            bytes
+        );
+        let expected: Bytes = [MARKER_TINY_MAP | 7]
+            .into_iter()
+            .chain(
+                ["a", "b", "c", "d", "e", "f", "g"]
+                    .into_iter()
+                    .flat_map(|key| [MARKER_TINY_STRING | 1, key.as_bytes()[0], 1]),
+            )
+            .collect();
+        assert_eq!(bytes, expected);
+
+        let nan = f64::from_bits(f64::NAN.to_bits() | 1);
+        assert_eq!(
+            Value::Float(nan).serialize_with(&options).unwrap(),
+            Value::Float(f64::NAN).serialize().unwrap()
+        );
+        assert_eq!(Value::Float(-0.0), Value::Float(0.0));
+        assert_eq!(
+            Value::Float(-0.0).serialize_with(&options).unwrap(),
+            Value::Float(0.0).serialize().unwrap()
+        );
+        assert_ne!(
+            Value::Float(-0.0).serialize().unwrap(),
+            Value::Float(0.0).serialize().unwrap()
+        );
+    }
+
+    #[test]
+    fn structure() {
+        let structure = Value::Structure {
+            signature: 0x24,
+            fields: vec![Value::from(150), Value::from("EUR")],
+        };
+        let bytes: Bytes = vec![MARKER_TINY_STRUCT | 2, 0x24, MARKER_INT_16]
+            .into_iter()
+            .chain(150_i16.to_be_bytes())
+            .chain([MARKER_TINY_STRING | 3])
+            .chain(*b"EUR")
+            .collect();
+        assert_eq!(structure.marker().unwrap(), MARKER_TINY_STRUCT | 2);
+        assert_eq!(structure.clone().serialize().unwrap(), bytes);
+        let (deserialized, remaining) = Value::deserialize(bytes).unwrap();
+        assert_eq!(deserialized, structure);
+        assert_eq!(remaining.len(), 0);
+
+        let structure = Value::Structure {
+            signature: 0x24,
+            fields: vec![Value::Null; 16],
+        };
+        let bytes = structure.clone().serialize().unwrap();
+        assert_eq!(bytes[..3], [MARKER_SMALL_STRUCT, 16, 0x24]);
+        assert_eq!(Value::deserialize(bytes).unwrap().0, structure);
+
+        for signature in BUILT_IN_SIGNATURES {
+            assert!(matches!(
+                Value::Structure {
+                    signature,
+                    fields: vec![]
+                }
+                .serialize(),
+                Err(SerializationError::ReservedSignature(s)) if s == signature
+            ));
+        }
+    }
+
+    #[test]
+    fn structure_decoder() {
+        fn decode(signature: u8, fields: Vec<Value>) -> ConversionResult<Value> {
+            match (signature, &fields[..]) {
+                (0x24, [Value::Integer(cents)]) => Ok(Value::Float(*cents as f64 / 100.0)),
+                (0x24, _) => Err(ConversionError::FromValue(Value::List(fields))),
+                _ => Ok(Value::Structure { signature, fields }),
+            }
+        }
+        let options = DeserializeOptions::new().with_structure_decoder(Some(decode));
+        let structure = |signature, fields| Value::Structure { signature, fields };
+
+        let bytes = structure(0x24, vec![Value::from(150)]).serialize().unwrap();
+        assert_eq!(
+            Value::deserialize_with(bytes, &options).unwrap().0,
+            Value::Float(1.5)
+        );
+
+        // Nested structures are decoded too, and other signatures fall back to Value::Structure
+        let other = structure(0x25, vec![structure(0x24, vec![Value::from(1)])]);
+        assert_eq!(
+            Value::deserialize_with(other.serialize().unwrap(), &options)
+                .unwrap()
+                .0,
+            structure(0x25, vec![Value::Float(0.01)])
+        );
+
+        // Built-in structures aren't passed to the decoder
+        let node = Value::from(Node::new(1, vec![], HashMap::<String, Value>::new()));
+        assert_eq!(
+            Value::deserialize_with(node.clone().serialize().unwrap(), &options)
+                .unwrap()
+                .0,
+            node
+        );
+
+        let bytes = structure(0x24, vec![]).serialize().unwrap();
+        assert!(matches!(
+            Value::deserialize_with(bytes, &options),
+            Err(DeserializationError::ConversionError(_))
+        ));
+    }
+
+    #[test]
+    fn vector() {
+        let options = SerializeOptions::new().with_vectors(true);
+
+        let vector = Value::from(vec![1.5_f32, -2.0]);
+        let bytes: Bytes = vec![MARKER_TINY_STRUCT | 2, SIGNATURE_VECTOR]
+            .into_iter()
+            .chain([MARKER_SMALL_BYTES, 1, vector::TYPE_FLOAT_32])
+            .chain([MARKER_SMALL_BYTES, 8])
+            .chain(1.5_f32.to_be_bytes())
+            .chain((-2.0_f32).to_be_bytes())
+            .collect();
+        assert_eq!(vector.clone().serialize_with(&options).unwrap(), bytes);
+        assert_eq!(Value::deserialize(bytes).unwrap().0, vector);
+        assert!(matches!(
+            vector.serialize(),
+            Err(SerializationError::VectorsNotSupported)
+        ));
+
+        let vector = Value::from(Vector::from(vec![i16::MIN; 200]));
+        let bytes = vector.clone().serialize_with(&options).unwrap();
+        assert_eq!(bytes[5..8], [MARKER_MEDIUM_BYTES, 1, 144]);
+        assert_eq!(Value::deserialize(bytes).unwrap().0, vector);
+
+        let bytes = Bytes::from_static(&[
+            MARKER_TINY_STRUCT | 2,
+            SIGNATURE_VECTOR,
+            MARKER_SMALL_BYTES,
+            1,
+            vector::TYPE_INT_32,
+            MARKER_SMALL_BYTES,
+            3,
+            0,
+            0,
+            1,
+        ]);
+        assert!(matches!(
+            Value::deserialize(bytes),
+            Err(DeserializationError::InvalidVector(_))
+        ));
+        let bytes = Bytes::from_static(&[
+            MARKER_TINY_STRUCT | 2,
+            SIGNATURE_VECTOR,
+            MARKER_SMALL_BYTES,
+            1,
+            0x00,
+            MARKER_SMALL_BYTES,
+            0,
+        ]);
+        assert!(matches!(
+            Value::deserialize(bytes),
+            Err(DeserializationError::InvalidMarkerByte(0x00))
+        ));
+    }
+
     value_test!(
         local_time,
         Value::LocalTime(NaiveTime::from_hms_nano_opt(23, 59, 59, 999).unwrap()),
@@ -68,6 +68,8 @@ where
     }
 }
 
+impl_from!(ValueMap, Map);
+
 impl From<&str> for Value {
     fn from(value: &str) -> Self {
         Value::String(String::from(value))
@@ -95,7 +97,7 @@ impl<O: Offset> From<(NaiveTime, O)> for Value {
 
 impl<T: TimeZone> From<DateTime<T>> for Value {
     fn from(value: DateTime<T>) -> Self {
-        Value::DateTimeOffset(DateTime::from_utc(value.naive_utc(), value.offset().fix()))
+        Value::DateTimeOffset(value.with_timezone(&value.offset().fix()))
     }
 }
 
@@ -123,6 +125,21 @@ impl_from!(Point2D, Point2D);
 
 impl_from!(Point3D, Point3D);
 
+impl_from!(Vector, Vector);
+
+// Lists of f32 are most likely embeddings, so store them compactly
+impl From<Vec<f32>> for Value {
+    fn from(value: Vec<f32>) -> Self {
+        Value::Vector(Vector::Float32(value))
+    }
+}
+
+impl From<&[f32]> for Value {
+    fn from(value: &[f32]) -> Self {
+        Value::Vector(Vector::Float32(value.to_vec()))
+    }
+}
+
 impl<T: Into<Value>> From<Option<T>> for Value {
     fn from(option: Option<T>) -> Self {
         match option {
@@ -192,6 +209,8 @@ where
 
 impl_try_from_value!(Vec<Value>, List);
 
+impl_try_from_value!(ValueMap, Map);
+
 impl<V, S> TryFrom<Value> for HashMap<std::string::String, V, S>
 where
     V: TryFrom<Value, Error = ConversionError>,
@@ -273,3 +292,16 @@ impl_try_from_value!(Duration, Duration);
 impl_try_from_value!(Point2D, Point2D);
 
 impl_try_from_value!(Point3D, Point3D);
+
+impl_try_from_value!(Vector, Vector);
+
+impl TryFrom<Value> for Vec<f32> {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Vector(Vector::Float32(elements)) => Ok(elements),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
@@ -1,9 +1,29 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    ops::{Add, Mul, Neg, Sub},
+    str::FromStr,
+};
+
 use bolt_proto_derive::*;
+use chrono::{DateTime, Months, NaiveDate, NaiveDateTime, Offset, TimeDelta, TimeZone};
+
+use crate::{
+    error::{ConversionError, ParseError},
+    value::SIGNATURE_DURATION,
+};
 
-use crate::value::SIGNATURE_DURATION;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
 
+/// A temporal amount with separate months, days, seconds, and nanoseconds components, since
+/// months and days do not have a fixed length.
+///
+/// Durations are formatted and parsed using the ISO-8601 format used by Cypher, e.g.
+/// `P1Y2M3DT4H5M6.007S`. Arithmetic follows Cypher's semantics: components are combined
+/// separately, and when adding a duration to a date or date-time, months are added first (clamping
+/// to the end of the month if needed), followed by days, then seconds and nanoseconds.
 #[bolt_structure(SIGNATURE_DURATION)]
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub struct Duration {
     pub(crate) months: i64,
     pub(crate) days: i64,
@@ -36,6 +56,333 @@ impl Duration {
     pub fn nanos(&self) -> i32 {
         self.nanos
     }
+
+    /// Get an equivalent duration with nanoseconds in the range `0..1_000_000_000`, carrying any
+    /// excess into seconds. Months and days are left as-is, since they have no fixed length.
+    /// Returns `None` if the seconds overflow.
+    pub fn checked_normalize(&self) -> Option<Self> {
+        Self::from_total_nanos(self.months, self.days, self.total_nanos())
+    }
+
+    /// Get an equivalent duration with nanoseconds in the range `0..1_000_000_000`. See
+    /// [`Duration::checked_normalize`].
+    ///
+    /// # Panics
+    /// Panics if the seconds overflow.
+    pub fn normalize(&self) -> Self {
+        self.checked_normalize()
+            .expect("overflow when normalizing duration")
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        Self::from_total_nanos(
+            self.months.checked_add(other.months)?,
+            self.days.checked_add(other.days)?,
+            self.total_nanos() + other.total_nanos(),
+        )
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Self::from_total_nanos(
+            self.months.checked_sub(other.months)?,
+            self.days.checked_sub(other.days)?,
+            self.total_nanos() - other.total_nanos(),
+        )
+    }
+
+    pub fn checked_neg(&self) -> Option<Self> {
+        Self::from_total_nanos(
+            self.months.checked_neg()?,
+            self.days.checked_neg()?,
+            -self.total_nanos(),
+        )
+    }
+
+    /// Multiply each component of this duration by the given factor.
+    pub fn checked_mul(&self, factor: i64) -> Option<Self> {
+        Self::from_total_nanos(
+            self.months.checked_mul(factor)?,
+            self.days.checked_mul(factor)?,
+            self.total_nanos().checked_mul(i128::from(factor))?,
+        )
+    }
+
+    /// Add this duration to a date. Only the months and days are used, as in Cypher.
+    pub fn checked_add_to_date(&self, date: NaiveDate) -> Option<NaiveDate> {
+        add_days(add_months(date, self.months)?, self.days)
+    }
+
+    /// Add this duration to a date-time without a time zone.
+    pub fn checked_add_to_date_time(&self, date_time: NaiveDateTime) -> Option<NaiveDateTime> {
+        NaiveDateTime::new(
+            self.checked_add_to_date(date_time.date())?,
+            date_time.time(),
+        )
+        .checked_add_signed(self.time_delta()?)
+    }
+
+    /// Add this duration to a date-time with a time zone. Months and days are added to the local
+    /// date-time, and the seconds are then added to the resulting instant. If the local date-time
+    /// falls in a gap (e.g. at the start of daylight saving time), the original UTC offset is used
+    /// to resolve it.
+    pub fn checked_add_to_zoned<Tz: TimeZone>(
+        &self,
+        date_time: DateTime<Tz>,
+    ) -> Option<DateTime<Tz>> {
+        let timezone = date_time.timezone();
+        let local = date_time.naive_local();
+        let local = NaiveDateTime::new(self.checked_add_to_date(local.date())?, local.time());
+        let shifted = match timezone.from_local_datetime(&local).earliest() {
+            Some(shifted) => shifted,
+            None => {
+                let offset = date_time.offset().fix();
+                timezone.from_utc_datetime(&(local - offset))
+            }
+        };
+        shifted.checked_add_signed(self.time_delta()?)
+    }
+
+    fn total_nanos(&self) -> i128 {
+        i128::from(self.seconds) * i128::from(NANOS_PER_SECOND) + i128::from(self.nanos)
+    }
+
+    fn from_total_nanos(months: i64, days: i64, total_nanos: i128) -> Option<Self> {
+        let seconds = i64::try_from(total_nanos.div_euclid(i128::from(NANOS_PER_SECOND))).ok()?;
+        let nanos = total_nanos.rem_euclid(i128::from(NANOS_PER_SECOND)) as i32;
+        Some(Self::new(months, days, seconds, nanos))
+    }
+
+    fn time_delta(&self) -> Option<TimeDelta> {
+        TimeDelta::try_seconds(self.seconds)?
+            .checked_add(&TimeDelta::nanoseconds(self.nanos.into()))
+    }
+}
+
+fn add_months(date: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let amount = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+    if months >= 0 {
+        date.checked_add_months(amount)
+    } else {
+        date.checked_sub_months(amount)
+    }
+}
+
+fn add_days(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    date.checked_add_signed(TimeDelta::try_days(days)?)
+}
+
+impl Display for Duration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let (years, months) = (self.months / 12, self.months % 12);
+        let total_nanos = self.total_nanos();
+        let (hours, total_nanos) = (
+            total_nanos / 3_600_000_000_000,
+            total_nanos % 3_600_000_000_000,
+        );
+        let (minutes, total_nanos) = (total_nanos / 60_000_000_000, total_nanos % 60_000_000_000);
+        let (seconds, nanos) = (total_nanos / 1_000_000_000, total_nanos % 1_000_000_000);
+
+        f.write_str("P")?;
+        for (amount, unit) in [(years, 'Y'), (months, 'M'), (self.days, 'D')] {
+            if amount != 0 {
+                write!(f, "{amount}{unit}")?;
+            }
+        }
+        if hours != 0 || minutes != 0 || seconds != 0 || nanos != 0 {
+            f.write_str("T")?;
+            for (amount, unit) in [(hours, 'H'), (minutes, 'M')] {
+                if amount != 0 {
+                    write!(f, "{amount}{unit}")?;
+                }
+            }
+            if seconds != 0 || nanos != 0 {
+                let sign = if seconds < 0 || nanos < 0 { "-" } else { "" };
+                write!(f, "{sign}{}", seconds.abs())?;
+                if nanos != 0 {
+                    let fraction = format!("{:09}", nanos.abs());
+                    write!(f, ".{}", fraction.trim_end_matches('0'))?;
+                }
+                f.write_str("S")?;
+            }
+        } else if self.months == 0 && self.days == 0 {
+            f.write_str("T0S")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ParseError;
+
+    /// Parse a duration in the ISO-8601 format used by Cypher, e.g. `P1Y2M3DT4H5M6.7S`. Each
+    /// component may be negative, weeks (`W`) are converted to days, and only seconds may have a
+    /// fractional part. The result is normalized.
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        parse_iso_8601(string).ok_or_else(|| ParseError::InvalidLiteral {
+            kind: "duration",
+            literal: string.to_string(),
+        })
+    }
+}
+
+fn parse_iso_8601(string: &str) -> Option<Duration> {
+    let mut rest = string.strip_prefix('P')?;
+    if rest.is_empty() {
+        return None;
+    }
+
+    let (mut months, mut days, mut seconds, mut nanos) = (0_i64, 0_i64, 0_i64, 0_i64);
+    let mut in_time = false;
+    // Components must appear in order, each at most once
+    let mut last_rank = None;
+    while !rest.is_empty() {
+        if let Some(time) = rest.strip_prefix('T') {
+            if in_time || time.is_empty() {
+                return None;
+            }
+            in_time = true;
+            rest = time;
+            continue;
+        }
+
+        let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+        let (number, unit) = (&rest[..end], rest[end..].chars().next()?);
+        rest = &rest[end + 1..];
+
+        let rank = match (in_time, unit) {
+            (false, 'Y') => 0,
+            (false, 'M') => 1,
+            (false, 'W') => 2,
+            (false, 'D') => 3,
+            (true, 'H') => 4,
+            (true, 'M') => 5,
+            (true, 'S') => 6,
+            _ => return None,
+        };
+        if last_rank >= Some(rank) {
+            return None;
+        }
+        last_rank = Some(rank);
+
+        if in_time && unit == 'S' {
+            let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+            if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let fraction_nanos = format!("{fraction:0<9}").parse::<i64>().ok()?;
+            seconds = seconds.checked_add(whole.parse().ok()?)?;
+            nanos += if whole.starts_with('-') {
+                -fraction_nanos
+            } else {
+                fraction_nanos
+            };
+            continue;
+        }
+
+        let amount: i64 = number.parse().ok()?;
+        match (in_time, unit) {
+            (false, 'Y') => months = months.checked_add(amount.checked_mul(12)?)?,
+            (false, 'M') => months = months.checked_add(amount)?,
+            (false, 'W') => days = days.checked_add(amount.checked_mul(7)?)?,
+            (false, 'D') => days = days.checked_add(amount)?,
+            (true, 'H') => seconds = seconds.checked_add(amount.checked_mul(3600)?)?,
+            (true, 'M') => seconds = seconds.checked_add(amount.checked_mul(60)?)?,
+            _ => unreachable!(),
+        }
+    }
+
+    Duration::new(months, days, seconds, 0).checked_add(&Duration::new(
+        0,
+        0,
+        0,
+        nanos.try_into().ok()?,
+    ))
+}
+
+impl Add for Duration {
+    type Output = Duration;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs)
+            .expect("overflow when adding durations")
+    }
+}
+
+impl Sub for Duration {
+    type Output = Duration;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs)
+            .expect("overflow when subtracting durations")
+    }
+}
+
+impl Neg for Duration {
+    type Output = Duration;
+
+    fn neg(self) -> Self::Output {
+        self.checked_neg().expect("overflow when negating duration")
+    }
+}
+
+impl Mul<i64> for Duration {
+    type Output = Duration;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        self.checked_mul(rhs)
+            .expect("overflow when multiplying duration")
+    }
+}
+
+impl Add<Duration> for NaiveDate {
+    type Output = NaiveDate;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        rhs.checked_add_to_date(self)
+            .expect("overflow when adding duration to date")
+    }
+}
+
+impl Sub<Duration> for NaiveDate {
+    type Output = NaiveDate;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Add<Duration> for NaiveDateTime {
+    type Output = NaiveDateTime;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        rhs.checked_add_to_date_time(self)
+            .expect("overflow when adding duration to date-time")
+    }
+}
+
+impl Sub<Duration> for NaiveDateTime {
+    type Output = NaiveDateTime;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<Tz: TimeZone> Add<Duration> for DateTime<Tz> {
+    type Output = DateTime<Tz>;
+
+    fn add(self, rhs: Duration) -> Self::Output {
+        rhs.checked_add_to_zoned(self)
+            .expect("overflow when adding duration to date-time")
+    }
+}
+
+impl<Tz: TimeZone> Sub<Duration> for DateTime<Tz> {
+    type Output = DateTime<Tz>;
+
+    fn sub(self, rhs: Duration) -> Self::Output {
+        self + -rhs
+    }
 }
 
 impl From<std::time::Duration> for Duration {
@@ -56,3 +403,194 @@ impl From<std::time::Duration> for Duration {
         }
     }
 }
+
+impl TryFrom<Duration> for TimeDelta {
+    type Error = ConversionError;
+
+    /// Convert a duration into a [`TimeDelta`], treating days as 24 hours. Fails if the duration
+    /// has a non-zero number of months, or if it is out of range.
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        if duration.months != 0 {
+            return Err(ConversionError::DurationHasMonths(duration.months));
+        }
+        duration
+            .days
+            .checked_mul(SECONDS_PER_DAY)
+            .and_then(|seconds| seconds.checked_add(duration.seconds))
+            .and_then(|seconds| Duration::new(0, 0, seconds, duration.nanos).time_delta())
+            .ok_or(ConversionError::DurationOutOfRange(duration))
+    }
+}
+
+impl TryFrom<Duration> for std::time::Duration {
+    type Error = ConversionError;
+
+    /// Convert a duration into a [`std::time::Duration`], treating days as 24 hours. Fails if the
+    /// duration has a non-zero number of months, or if it is negative or out of range.
+    fn try_from(duration: Duration) -> Result<Self, Self::Error> {
+        TimeDelta::try_from(duration)?
+            .to_std()
+            .map_err(|_| ConversionError::DurationOutOfRange(duration))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, NaiveTime};
+
+    use super::*;
+
+    #[test]
+    fn format_and_parse() {
+        let cases = vec![
+            (Duration::new(0, 0, 0, 0), "PT0S"),
+            (Duration::new(14, 3, 0, 0), "P1Y2M3D"),
+            (
+                Duration::new(14, 3, 14_706, 7_000_000),
+                "P1Y2M3DT4H5M6.007S",
+            ),
+            (Duration::new(0, 0, -1, 500_000_000), "PT-0.5S"),
+            (Duration::new(-1, 0, -60, 0), "P-1MT-1M"),
+        ];
+        for (duration, string) in cases {
+            assert_eq!(duration.to_string(), string);
+            assert_eq!(string.parse::<Duration>().unwrap(), duration);
+        }
+
+        assert_eq!(
+            "P2W".parse::<Duration>().unwrap(),
+            Duration::new(0, 14, 0, 0)
+        );
+        assert_eq!(
+            "PT1.5S".parse::<Duration>().unwrap(),
+            Duration::new(0, 0, 1, 500_000_000)
+        );
+        for invalid in [
+            "",
+            "P",
+            "PT",
+            "1Y",
+            "P1S",
+            "PT1D",
+            "PT1.1234567891S",
+            "P1.5D",
+            "PXD",
+            "P1D1Y",
+            "PT1S1S",
+        ] {
+            assert!(invalid.parse::<Duration>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(
+            Duration::new(1, 2, 3, 1_500_000_000).normalize(),
+            Duration::new(1, 2, 4, 500_000_000)
+        );
+        assert_eq!(
+            Duration::new(0, 0, 0, -1).normalize(),
+            Duration::new(0, 0, -1, 999_999_999)
+        );
+        assert!(Duration::new(0, 0, i64::MAX, 1_000_000_000)
+            .checked_normalize()
+            .is_none());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let a = Duration::new(1, 2, 3, 600_000_000);
+        let b = Duration::new(4, 5, 6, 700_000_000);
+        assert_eq!(a + b, Duration::new(5, 7, 10, 300_000_000));
+        assert_eq!(a - b, Duration::new(-3, -3, -4, 900_000_000));
+        assert_eq!(-a, Duration::new(-1, -2, -4, 400_000_000));
+        assert_eq!(a * 3, Duration::new(3, 6, 10, 800_000_000));
+        assert!(Duration::new(i64::MAX, 0, 0, 0).checked_mul(2).is_none());
+        assert!(Duration::new(i64::MIN, 0, 0, 0).checked_neg().is_none());
+    }
+
+    #[test]
+    fn add_to_dates() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+        // Months clamp to the end of the month before days are added
+        assert_eq!(
+            date + Duration::new(1, 1, 0, 0),
+            NaiveDate::from_ymd_opt(2020, 3, 1).unwrap()
+        );
+        // Time components are ignored for dates
+        assert_eq!(date + Duration::new(0, 0, 86_400 * 2, 0), date);
+        assert_eq!(
+            date - Duration::new(1, 0, 0, 0),
+            NaiveDate::from_ymd_opt(2019, 12, 31).unwrap()
+        );
+
+        let date_time = date.and_time(NaiveTime::from_hms_opt(23, 0, 0).unwrap());
+        assert_eq!(
+            date_time + Duration::new(1, 0, 3600, 5),
+            NaiveDate::from_ymd_opt(2020, 3, 1)
+                .unwrap()
+                .and_hms_nano_opt(0, 0, 0, 5)
+                .unwrap()
+        );
+
+        let offset = FixedOffset::east_opt(3600).unwrap();
+        assert_eq!(
+            date_time.and_local_timezone(offset).unwrap() + Duration::new(0, 1, 0, 0),
+            NaiveDate::from_ymd_opt(2020, 2, 1)
+                .unwrap()
+                .and_hms_opt(23, 0, 0)
+                .unwrap()
+                .and_local_timezone(offset)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn add_across_daylight_saving_time() {
+        use chrono_tz::Europe::Paris;
+
+        // Clocks move forward from 02:00 to 03:00 on 2020-03-29
+        let before = Paris.with_ymd_and_hms(2020, 3, 28, 12, 0, 0).unwrap();
+        // Adding a day keeps the local time, while adding 24 hours does not
+        assert_eq!(
+            before + Duration::new(0, 1, 0, 0),
+            Paris.with_ymd_and_hms(2020, 3, 29, 12, 0, 0).unwrap()
+        );
+        assert_eq!(
+            before + Duration::new(0, 0, 86_400, 0),
+            Paris.with_ymd_and_hms(2020, 3, 29, 13, 0, 0).unwrap()
+        );
+
+        // 02:30 does not exist on 2020-03-29, so the original offset is used
+        let gap = Paris.with_ymd_and_hms(2020, 3, 28, 2, 30, 0).unwrap();
+        assert_eq!(
+            gap + Duration::new(0, 1, 0, 0),
+            Paris.with_ymd_and_hms(2020, 3, 29, 3, 30, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn conversions() {
+        let duration = Duration::new(0, 1, 30, 5);
+        assert_eq!(
+            std::time::Duration::try_from(duration).unwrap(),
+            std::time::Duration::new(86_430, 5)
+        );
+        assert_eq!(
+            TimeDelta::try_from(Duration::new(0, -1, 0, 0)).unwrap(),
+            TimeDelta::try_days(-1).unwrap()
+        );
+        assert!(matches!(
+            std::time::Duration::try_from(Duration::new(1, 0, 0, 0)),
+            Err(ConversionError::DurationHasMonths(1))
+        ));
+        assert!(matches!(
+            std::time::Duration::try_from(Duration::new(0, -1, 0, 0)),
+            Err(ConversionError::DurationOutOfRange(_))
+        ));
+        assert!(matches!(
+            TimeDelta::try_from(Duration::new(0, i64::MAX, 0, 0)),
+            Err(ConversionError::DurationOutOfRange(_))
+        ));
+    }
+}
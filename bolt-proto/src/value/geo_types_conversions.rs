@@ -0,0 +1,59 @@
+//! Conversions between [`Value`]s and points of the [`geo_types`] crate.
+
+use crate::{
+    error::{ConversionError, ConversionResult},
+    value::Point2D,
+    Value,
+};
+
+impl From<Point2D> for geo_types::Point<f64> {
+    /// Convert a point into a [`geo_types::Point`]. The SRID is dropped, so geographic points
+    /// become `(longitude, latitude)` pairs.
+    fn from(point: Point2D) -> Self {
+        geo_types::Point::new(point.x, point.y)
+    }
+}
+
+impl From<geo_types::Point<f64>> for Point2D {
+    /// Convert a [`geo_types::Point`] into a geographic WGS-84 point, interpreting `x` as the
+    /// longitude and `y` as the latitude. Use [`Point2D::cartesian`] for Cartesian points.
+    fn from(point: geo_types::Point<f64>) -> Self {
+        Point2D::wgs_84(point.x(), point.y())
+    }
+}
+
+impl From<geo_types::Point<f64>> for Value {
+    fn from(value: geo_types::Point<f64>) -> Self {
+        Value::Point2D(Point2D::from(value))
+    }
+}
+
+impl TryFrom<Value> for geo_types::Point<f64> {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Point2D(point) => Ok(point.into()),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point() {
+        let point = geo_types::Point::new(12.5, 55.7);
+        let value = Value::from(point);
+        assert_eq!(value, Value::Point2D(Point2D::wgs_84(12.5, 55.7)));
+        assert_eq!(geo_types::Point::try_from(value).unwrap(), point);
+
+        assert_eq!(
+            geo_types::Point::from(Point2D::cartesian(1.0, 2.0)),
+            geo_types::Point::new(1.0, 2.0)
+        );
+        assert!(geo_types::Point::try_from(Value::from(1.0)).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Conversions between [`Value`]s and [`geojson`] geometries.
+
+use geojson::{Geometry, GeometryValue};
+
+use crate::{
+    error::{ConversionError, ConversionResult},
+    value::{Point2D, Point3D},
+    Value,
+};
+
+impl TryFrom<Value> for Geometry {
+    type Error = ConversionError;
+
+    /// Convert a WGS-84 or WGS-84-3D point into a GeoJSON `Point` geometry. GeoJSON positions
+    /// are always geographic, so points in other coordinate reference systems cannot be
+    /// converted.
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Point2D(point) if point.srid == Point2D::SRID_WGS_84 => {
+                Ok(Geometry::new_point([point.x, point.y]))
+            }
+            Value::Point3D(point) if point.srid == Point3D::SRID_WGS_84_3D => {
+                Ok(Geometry::new_point([point.x, point.y, point.z]))
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Geometry> for Value {
+    type Error = ConversionError;
+
+    /// Convert a GeoJSON `Point` geometry into a WGS-84 point, or a WGS-84-3D point if it has a
+    /// height. Other geometries cannot be converted.
+    fn try_from(geometry: Geometry) -> ConversionResult<Self> {
+        match &geometry.value {
+            GeometryValue::Point { coordinates } => match *coordinates.as_slice() {
+                [longitude, latitude] => Ok(Value::from(Point2D::wgs_84(longitude, latitude))),
+                [longitude, latitude, height] => {
+                    Ok(Value::from(Point3D::wgs_84_3d(longitude, latitude, height)))
+                }
+                _ => Err(ConversionError::UnsupportedGeometry(format!(
+                    "Point with {} coordinates",
+                    coordinates.len()
+                ))),
+            },
+            other => Err(ConversionError::UnsupportedGeometry(
+                other.type_name().to_string(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point() {
+        let geometry = Geometry::new_point([12.5, 55.7]);
+        let value = Value::try_from(geometry.clone()).unwrap();
+        assert_eq!(value, Value::from(Point2D::wgs_84(12.5, 55.7)));
+        assert_eq!(Geometry::try_from(value).unwrap(), geometry);
+
+        let geometry = Geometry::new_point([12.5, 55.7, 10.0]);
+        let value = Value::try_from(geometry.clone()).unwrap();
+        assert_eq!(value, Value::from(Point3D::wgs_84_3d(12.5, 55.7, 10.0)));
+        assert_eq!(Geometry::try_from(value).unwrap(), geometry);
+    }
+
+    #[test]
+    fn unsupported() {
+        assert!(matches!(
+            Geometry::try_from(Value::from(Point2D::cartesian(1.0, 2.0))),
+            Err(ConversionError::FromValue(_))
+        ));
+        assert!(matches!(
+            Value::try_from(Geometry::new_line_string([[0.0, 0.0], [1.0, 1.0]])),
+            Err(ConversionError::UnsupportedGeometry(name)) if name == "LineString"
+        ));
+    }
+}
@@ -0,0 +1,78 @@
+//! Conversions between [`Value`]s and [`IndexMap`]s.
+
+use std::hash::BuildHasher;
+
+use indexmap::IndexMap;
+
+use crate::{
+    error::{ConversionError, ConversionResult},
+    Value,
+};
+
+impl<K, V, S> From<IndexMap<K, V, S>> for Value
+where
+    K: Into<String>,
+    V: Into<Value>,
+{
+    fn from(value: IndexMap<K, V, S>) -> Self {
+        Value::Map(
+            value
+                .into_iter()
+                .map(|(k, v)| (K::into(k), V::into(v)))
+                .collect(),
+        )
+    }
+}
+
+impl<V, S> TryFrom<Value> for IndexMap<String, V, S>
+where
+    V: TryFrom<Value, Error = ConversionError>,
+    S: BuildHasher + Default,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Map(map) => {
+                let mut new_map = IndexMap::with_capacity_and_hasher(map.len(), Default::default());
+                for (k, v) in map {
+                    new_map.insert(k, V::try_from(v)?);
+                }
+                Ok(new_map)
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl<S> TryFrom<Value> for IndexMap<String, Value, S>
+where
+    S: BuildHasher + Default,
+{
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Map(map) => Ok(map.into_iter().collect()),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn order_is_preserved() {
+        let map = IndexMap::from([("z", 1), ("a", 2), ("m", 3)]);
+        let bytes = Value::from(map).serialize().unwrap();
+        let (value, _) = Value::deserialize(bytes.clone()).unwrap();
+        let keys: Vec<String> = IndexMap::<String, Value>::try_from(value.clone())
+            .unwrap()
+            .into_keys()
+            .collect();
+        assert_eq!(keys, ["z", "a", "m"]);
+        assert_eq!(value.serialize().unwrap(), bytes);
+    }
+}
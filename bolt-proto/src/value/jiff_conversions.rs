@@ -0,0 +1,447 @@
+//! Conversions between [`Value`]s and the date and time types of the [`jiff`] crate.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
+use jiff::{
+    civil,
+    tz::{self, TimeZone as JiffTimeZone},
+    SignedDuration, Span, Timestamp, Zoned,
+};
+
+use crate::{
+    error::{ConversionError, ConversionResult},
+    value::Duration,
+    Value,
+};
+
+fn from_date(date: civil::Date) -> NaiveDate {
+    // Every date representable by jiff is representable by chrono
+    NaiveDate::from_ymd_opt(date.year().into(), date.month() as u32, date.day() as u32).unwrap()
+}
+
+fn to_date(date: NaiveDate) -> Option<civil::Date> {
+    civil::Date::new(
+        date.year().try_into().ok()?,
+        date.month() as i8,
+        date.day() as i8,
+    )
+    .ok()
+}
+
+fn from_time(time: civil::Time) -> NaiveTime {
+    NaiveTime::from_hms_nano_opt(
+        time.hour() as u32,
+        time.minute() as u32,
+        time.second() as u32,
+        time.subsec_nanosecond() as u32,
+    )
+    .unwrap()
+}
+
+fn to_time(time: NaiveTime) -> Option<civil::Time> {
+    // Fails for leap seconds, which jiff cannot represent
+    civil::Time::new(
+        time.hour() as i8,
+        time.minute() as i8,
+        time.second() as i8,
+        time.nanosecond() as i32,
+    )
+    .ok()
+}
+
+fn from_offset(offset: tz::Offset) -> FixedOffset {
+    FixedOffset::east_opt(offset.seconds()).unwrap()
+}
+
+fn to_offset(offset: FixedOffset) -> Option<tz::Offset> {
+    tz::Offset::from_seconds(offset.local_minus_utc()).ok()
+}
+
+fn from_timestamp(timestamp: Timestamp) -> DateTime<Utc> {
+    DateTime::from_timestamp(timestamp.as_second(), timestamp.subsec_nanosecond() as u32).unwrap()
+}
+
+fn to_timestamp<T: TimeZone>(date_time: &DateTime<T>) -> Option<Timestamp> {
+    Timestamp::new(
+        date_time.timestamp(),
+        date_time.timestamp_subsec_nanos() as i32,
+    )
+    .ok()
+}
+
+// ------------------------- Into Value -------------------------
+
+impl From<civil::Date> for Value {
+    fn from(value: civil::Date) -> Self {
+        Value::Date(from_date(value))
+    }
+}
+
+impl From<(civil::Time, tz::Offset)> for Value {
+    fn from(pair: (civil::Time, tz::Offset)) -> Self {
+        Value::Time(from_time(pair.0), from_offset(pair.1))
+    }
+}
+
+impl From<Timestamp> for Value {
+    /// Convert a [`Timestamp`] into a date-time with a UTC offset.
+    fn from(value: Timestamp) -> Self {
+        Value::DateTimeOffset(from_timestamp(value).fixed_offset())
+    }
+}
+
+impl From<Zoned> for Value {
+    /// Convert a [`Zoned`] into a date-time with a time zone ID if its time zone has an IANA name
+    /// known to [`chrono_tz`], or into a date-time with a fixed offset otherwise.
+    fn from(value: Zoned) -> Self {
+        let date_time = from_timestamp(value.timestamp());
+        match value
+            .time_zone()
+            .iana_name()
+            .and_then(|name| name.parse::<Tz>().ok())
+        {
+            Some(tz) => Value::DateTimeZoned(date_time.with_timezone(&tz)),
+            None => Value::DateTimeOffset(date_time.with_timezone(&from_offset(value.offset()))),
+        }
+    }
+}
+
+impl From<civil::Time> for Value {
+    fn from(value: civil::Time) -> Self {
+        Value::LocalTime(from_time(value))
+    }
+}
+
+impl From<civil::DateTime> for Value {
+    fn from(value: civil::DateTime) -> Self {
+        Value::LocalDateTime(NaiveDateTime::new(
+            from_date(value.date()),
+            from_time(value.time()),
+        ))
+    }
+}
+
+impl From<Span> for Value {
+    fn from(value: Span) -> Self {
+        Value::Duration(Duration::from(value))
+    }
+}
+
+impl From<SignedDuration> for Value {
+    fn from(value: SignedDuration) -> Self {
+        Value::Duration(Duration::from(value))
+    }
+}
+
+// ------------------------- From Value -------------------------
+
+impl TryFrom<Value> for civil::Date {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Date(date) => to_date(date).ok_or(ConversionError::FromValue(value)),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for (civil::Time, tz::Offset) {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Time(time, offset) => to_time(time)
+                .zip(to_offset(offset))
+                .ok_or(ConversionError::FromValue(value)),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Timestamp {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::DateTimeOffset(date_time_offset) => {
+                to_timestamp(&date_time_offset).ok_or(ConversionError::FromValue(value))
+            }
+            Value::DateTimeZoned(date_time_zoned) => {
+                to_timestamp(&date_time_zoned).ok_or(ConversionError::FromValue(value))
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Zoned {
+    type Error = ConversionError;
+
+    /// Convert a date-time into a [`Zoned`]. Date-times with a time zone ID keep that time zone,
+    /// which is looked up in jiff's time zone database. Date-times with an offset use a fixed
+    /// offset time zone.
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        let zoned = match &value {
+            Value::DateTimeOffset(date_time_offset) => to_timestamp(date_time_offset)
+                .zip(to_offset(*date_time_offset.offset()))
+                .map(|(timestamp, offset)| timestamp.to_zoned(JiffTimeZone::fixed(offset))),
+            Value::DateTimeZoned(date_time_zoned) => to_timestamp(date_time_zoned)
+                .zip(JiffTimeZone::get(date_time_zoned.timezone().name()).ok())
+                .map(|(timestamp, time_zone)| timestamp.to_zoned(time_zone)),
+            _ => None,
+        };
+        zoned.ok_or(ConversionError::FromValue(value))
+    }
+}
+
+impl TryFrom<Value> for civil::Time {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::LocalTime(local_time) => {
+                to_time(local_time).ok_or(ConversionError::FromValue(value))
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for civil::DateTime {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::LocalDateTime(local_date_time) => to_date(local_date_time.date())
+                .zip(to_time(local_date_time.time()))
+                .map(|(date, time)| civil::DateTime::from_parts(date, time))
+                .ok_or(ConversionError::FromValue(value)),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for Span {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Duration(duration) => Span::try_from(duration),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for SignedDuration {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Duration(duration) => SignedDuration::try_from(duration),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+// ------------------------- Durations -------------------------
+
+impl From<Span> for Duration {
+    /// Convert a [`Span`] into a duration, keeping its calendar units. Years become 12 months,
+    /// weeks become 7 days, and all time units are combined into seconds and nanoseconds.
+    fn from(span: Span) -> Self {
+        let months = i64::from(span.get_years()) * 12 + i64::from(span.get_months());
+        let days = i64::from(span.get_weeks()) * 7 + i64::from(span.get_days());
+        // Time units in a span are bounded well within the range of an i64 number of seconds
+        let nanos = i128::from(span.get_hours()) * 3_600_000_000_000
+            + i128::from(span.get_minutes()) * 60_000_000_000
+            + i128::from(span.get_seconds()) * 1_000_000_000
+            + i128::from(span.get_milliseconds()) * 1_000_000
+            + i128::from(span.get_microseconds()) * 1_000
+            + i128::from(span.get_nanoseconds());
+        Duration::new(
+            months,
+            days,
+            nanos.div_euclid(1_000_000_000) as i64,
+            nanos.rem_euclid(1_000_000_000) as i32,
+        )
+    }
+}
+
+impl TryFrom<Duration> for Span {
+    type Error = ConversionError;
+
+    /// Convert a duration into a [`Span`] with months, days, seconds, and nanoseconds. Fails if
+    /// the components have different signs, since all units of a span must have the same sign, or
+    /// if any component is out of jiff's range.
+    fn try_from(duration: Duration) -> ConversionResult<Self> {
+        let nanos = i128::from(duration.seconds) * 1_000_000_000 + i128::from(duration.nanos);
+        let signs = [
+            duration.months.signum(),
+            duration.days.signum(),
+            nanos.signum() as i64,
+        ];
+        if signs.contains(&1) && signs.contains(&-1) {
+            return Err(ConversionError::DurationOutOfRange(duration));
+        }
+
+        Span::new()
+            .try_months(duration.months)
+            .and_then(|span| span.try_days(duration.days))
+            .and_then(|span| span.try_seconds((nanos / 1_000_000_000) as i64))
+            .and_then(|span| span.try_nanoseconds((nanos % 1_000_000_000) as i64))
+            .map_err(|_| ConversionError::DurationOutOfRange(duration))
+    }
+}
+
+impl From<SignedDuration> for Duration {
+    /// Convert a [`SignedDuration`] into a duration with only seconds and nanoseconds, since days
+    /// do not always have 24 hours.
+    fn from(duration: SignedDuration) -> Self {
+        Duration::new(0, 0, duration.as_secs(), duration.subsec_nanos()).normalize()
+    }
+}
+
+impl TryFrom<Duration> for SignedDuration {
+    type Error = ConversionError;
+
+    /// Convert a duration into a [`SignedDuration`], treating days as 24 hours. Fails if the
+    /// duration has a non-zero number of months, or if it is out of range.
+    fn try_from(duration: Duration) -> ConversionResult<Self> {
+        let delta = chrono::TimeDelta::try_from(duration)?;
+        Ok(SignedDuration::new(
+            delta.num_seconds(),
+            delta.subsec_nanos(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::{civil::date, ToSpan};
+
+    use super::*;
+
+    #[test]
+    fn civil() {
+        let value = Value::from(date(2024, 2, 29));
+        assert_eq!(
+            value,
+            Value::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())
+        );
+        assert_eq!(civil::Date::try_from(value).unwrap(), date(2024, 2, 29));
+
+        let time = civil::time(12, 34, 56, 789_000_000);
+        let value = Value::from(time);
+        assert_eq!(
+            value,
+            Value::LocalTime(NaiveTime::from_hms_milli_opt(12, 34, 56, 789).unwrap())
+        );
+        assert_eq!(civil::Time::try_from(value).unwrap(), time);
+
+        let offset = tz::offset(-5);
+        let value = Value::from((time, offset));
+        assert_eq!(
+            <(civil::Time, tz::Offset)>::try_from(value).unwrap(),
+            (time, offset)
+        );
+
+        let date_time = date(2000, 1, 1).at(0, 0, 0, 1);
+        let value = Value::from(date_time);
+        assert_eq!(civil::DateTime::try_from(value).unwrap(), date_time);
+
+        // Out of range for jiff
+        let value = Value::Date(NaiveDate::from_ymd_opt(100_000, 1, 1).unwrap());
+        assert!(matches!(
+            civil::Date::try_from(value),
+            Err(ConversionError::FromValue(_))
+        ));
+        assert!(civil::Date::try_from(Value::from(1)).is_err());
+    }
+
+    #[test]
+    fn zoned() {
+        let zoned = date(2024, 7, 1)
+            .at(12, 0, 0, 0)
+            .in_tz("Europe/Paris")
+            .unwrap();
+        let value = Value::from(zoned.clone());
+        assert_eq!(
+            value,
+            Value::DateTimeZoned(
+                Tz::Europe__Paris
+                    .with_ymd_and_hms(2024, 7, 1, 12, 0, 0)
+                    .unwrap()
+            )
+        );
+        let round_trip = Zoned::try_from(value.clone()).unwrap();
+        assert_eq!(round_trip, zoned);
+        assert_eq!(round_trip.time_zone().iana_name(), Some("Europe/Paris"));
+        assert_eq!(Timestamp::try_from(value).unwrap(), zoned.timestamp());
+
+        let fixed = zoned.with_time_zone(JiffTimeZone::fixed(tz::offset(3)));
+        let value = Value::from(fixed.clone());
+        assert_eq!(
+            value,
+            Value::DateTimeOffset(
+                FixedOffset::east_opt(3 * 3600)
+                    .unwrap()
+                    .with_ymd_and_hms(2024, 7, 1, 13, 0, 0)
+                    .unwrap()
+            )
+        );
+        assert_eq!(Zoned::try_from(value).unwrap(), fixed);
+
+        let value = Value::from(zoned.timestamp());
+        assert_eq!(
+            value,
+            Value::DateTimeOffset(
+                Utc.with_ymd_and_hms(2024, 7, 1, 10, 0, 0)
+                    .unwrap()
+                    .fixed_offset()
+            )
+        );
+    }
+
+    #[test]
+    fn duration() {
+        let span = 1
+            .year()
+            .months(2)
+            .weeks(1)
+            .days(1)
+            .hours(1)
+            .milliseconds(1_500);
+        let duration = Duration::from(span);
+        assert_eq!(duration, Duration::new(14, 8, 3601, 500_000_000));
+        assert_eq!(
+            Span::try_from(duration).unwrap().fieldwise(),
+            14.months().days(8).seconds(3601).nanoseconds(500_000_000)
+        );
+
+        let negative = Duration::from((-90).minutes().nanoseconds(-5));
+        assert_eq!(negative, Duration::new(0, 0, -5401, 999_999_995));
+        assert_eq!(
+            Span::try_from(negative).unwrap().fieldwise(),
+            (-5400).seconds().nanoseconds(-5)
+        );
+        assert!(matches!(
+            Span::try_from(Duration::new(1, -1, 0, 0)),
+            Err(ConversionError::DurationOutOfRange(_))
+        ));
+
+        let signed = SignedDuration::new(-90_000, -5);
+        let value = Value::from(signed);
+        assert_eq!(
+            value,
+            Value::Duration(Duration::new(0, 0, -90_001, 999_999_995))
+        );
+        assert_eq!(SignedDuration::try_from(value).unwrap(), signed);
+        assert!(matches!(
+            SignedDuration::try_from(Duration::new(1, 0, 0, 0)),
+            Err(ConversionError::DurationHasMonths(1))
+        ));
+    }
+}
@@ -0,0 +1,989 @@
+use std::{borrow::Borrow, fmt::Write, iter::Peekable, str::CharIndices};
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, SecondsFormat, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{
+    error::{ParseError, ParseResult},
+    value::{Duration, Node, Point2D, Point3D, UnboundRelationship, Value, ValueMap, Vector},
+};
+
+const TIME_FORMAT: &str = "%H:%M:%S%.f";
+const LOCAL_DATE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+// Same limit as deserialization uses by default, to avoid overflowing the stack
+const MAX_DEPTH: usize = 256;
+
+impl Value {
+    /// Render this value as a Cypher expression that evaluates to the same value, e.g. `'it\'s'`,
+    /// `[1, 2.5, null]`, `date('2020-01-31')`, `duration('P1Y2M3DT1M')`, or
+    /// `point({srid: 4326, x: 1.0, y: 2.0})`.
+    ///
+    /// Map keys are sorted. Cypher has no literals for byte arrays or structures, so
+    /// [`Bytes`](Value::Bytes) are rendered as lists of integers, and [`Node`](Value::Node)s,
+    /// [`Relationship`](Value::Relationship)s,
+    /// [`UnboundRelationship`](Value::UnboundRelationship)s, [`Path`](Value::Path)s, and other
+    /// [`Structure`](Value::Structure)s are rendered as maps with the same layout as their JSON
+    /// representation (`id`, `labels`, `type`, `startNode`, `endNode`, `properties`, `nodes`,
+    /// `relationships`, `sequence`, `signature`, `fields`). These are parsed back as lists and maps
+    /// by [`Value::from_cypher_literal`]. [`Vector`](Value::Vector)s are rendered as calls to
+    /// `vector`, e.g. `vector([1.0, 2.0], 2, FLOAT32)`.
+    pub fn to_cypher_literal(&self) -> String {
+        let mut literal = String::new();
+        write_literal(self, &mut literal);
+        literal
+    }
+
+    /// Parse a Cypher literal into a value. Supports everything produced by
+    /// [`Value::to_cypher_literal`]: `null`, booleans, integers (decimal, or hexadecimal with a
+    /// `0x` prefix), floats (including `NaN` and `Infinity`), single- or double-quoted strings,
+    /// lists, maps, and calls to `date`, `time`, `datetime`, `localtime`, and `localdatetime` with
+    /// a string argument, to `point` with a map argument, to `duration` with either an ISO-8601
+    /// string or a map argument, or to `vector` with a list, its dimension, and an element type
+    /// (`INTEGER8`, `INTEGER16`, `INTEGER32`, `INTEGER64`, `FLOAT32`, or `FLOAT64`, or the
+    /// aliases `INT8`, `INT16`, `INT32`, `INT64`, `INTEGER`, and `FLOAT`).
+    ///
+    /// Lists, maps and function calls may be nested at most 256 levels deep, otherwise
+    /// [`ParseError::MaxDepthExceeded`] is returned.
+    pub fn from_cypher_literal(literal: &str) -> ParseResult<Value> {
+        let mut parser = Parser {
+            input: literal,
+            chars: literal.char_indices().peekable(),
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        match parser.chars.next() {
+            Some((position, found)) => Err(ParseError::UnexpectedCharacter { found, position }),
+            None => Ok(value),
+        }
+    }
+}
+
+/// The ISO-8601 string used to represent a date or time value, if the value is one.
+pub(crate) fn temporal_string(value: &Value) -> Option<String> {
+    let string = match value {
+        Value::Date(date) => date.to_string(),
+        Value::Time(time, offset) => format!("{}{}", time.format(TIME_FORMAT), offset),
+        Value::DateTimeOffset(date_time) => date_time.to_rfc3339_opts(SecondsFormat::AutoSi, false),
+        Value::DateTimeZoned(date_time) => format!(
+            "{}[{}]",
+            date_time
+                .fixed_offset()
+                .to_rfc3339_opts(SecondsFormat::AutoSi, false),
+            date_time.timezone().name()
+        ),
+        Value::LocalTime(time) => time.format(TIME_FORMAT).to_string(),
+        Value::LocalDateTime(date_time) => date_time.format(LOCAL_DATE_TIME_FORMAT).to_string(),
+        _ => return None,
+    };
+    Some(string)
+}
+
+fn write_literal(value: &Value, out: &mut String) {
+    match value {
+        Value::Boolean(boolean) => out.push_str(if *boolean { "true" } else { "false" }),
+        Value::Integer(integer) => write!(out, "{integer}").unwrap(),
+        Value::Float(float) => write_float(*float, out),
+        Value::Bytes(bytes) => write_list(bytes.iter().map(|&b| Value::Integer(b.into())), out),
+        Value::List(list) => write_list(list.iter(), out),
+        Value::Map(map) => write_map(map.iter().map(|(k, v)| (k.as_str(), v)), out),
+        Value::Null => out.push_str("null"),
+        Value::String(string) => write_string(string, out),
+        Value::Node(node) => write_node(node, out),
+        Value::Relationship(rel) => write_map(
+            [
+                ("id", &Value::Integer(rel.rel_identity)),
+                ("type", &Value::String(rel.rel_type.clone())),
+                ("startNode", &Value::Integer(rel.start_node_identity)),
+                ("endNode", &Value::Integer(rel.end_node_identity)),
+                ("properties", &Value::Map(rel.properties.clone())),
+            ],
+            out,
+        ),
+        Value::Path(path) => {
+            out.push_str("{nodes: [");
+            for (index, node) in path.nodes.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_node(node, out);
+            }
+            out.push_str("], relationships: [");
+            for (index, rel) in path.relationships.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_unbound_relationship(rel, out);
+            }
+            out.push_str("], sequence: ");
+            write_list(path.sequence.iter().map(|&i| Value::Integer(i)), out);
+            out.push('}');
+        }
+        Value::UnboundRelationship(rel) => write_unbound_relationship(rel, out),
+        Value::Date(_) => write_function("date", value, out),
+        Value::Time(_, _) => write_function("time", value, out),
+        Value::DateTimeOffset(_) | Value::DateTimeZoned(_) => {
+            write_function("datetime", value, out)
+        }
+        Value::LocalTime(_) => write_function("localtime", value, out),
+        Value::LocalDateTime(_) => write_function("localdatetime", value, out),
+        Value::Duration(duration) => {
+            out.push_str("duration(");
+            write_string(&duration.to_string(), out);
+            out.push(')');
+        }
+        Value::Point2D(point) => {
+            out.push_str("point(");
+            write_map(
+                [
+                    ("srid", &Value::from(point.srid)),
+                    ("x", &Value::Float(point.x)),
+                    ("y", &Value::Float(point.y)),
+                ],
+                out,
+            );
+            out.push(')');
+        }
+        Value::Point3D(point) => {
+            out.push_str("point(");
+            write_map(
+                [
+                    ("srid", &Value::from(point.srid)),
+                    ("x", &Value::Float(point.x)),
+                    ("y", &Value::Float(point.y)),
+                    ("z", &Value::Float(point.z)),
+                ],
+                out,
+            );
+            out.push(')');
+        }
+        Value::Vector(vector) => {
+            out.push_str("vector(");
+            write_list(vector.to_values().iter(), out);
+            let type_name = match vector {
+                Vector::Int8(_) => "INTEGER8",
+                Vector::Int16(_) => "INTEGER16",
+                Vector::Int32(_) => "INTEGER32",
+                Vector::Int64(_) => "INTEGER64",
+                Vector::Float32(_) => "FLOAT32",
+                Vector::Float64(_) => "FLOAT64",
+            };
+            write!(out, ", {}, {})", vector.len(), type_name).unwrap();
+        }
+        Value::Structure { signature, fields } => write_map(
+            [
+                ("fields", &Value::List(fields.clone())),
+                ("signature", &Value::Integer((*signature).into())),
+            ],
+            out,
+        ),
+    }
+}
+
+fn write_float(float: f64, out: &mut String) {
+    if float.is_nan() {
+        out.push_str("NaN");
+    } else if float.is_infinite() {
+        out.push_str(if float > 0.0 { "Infinity" } else { "-Infinity" });
+    } else {
+        // Debug formatting always includes a decimal point or exponent
+        write!(out, "{float:?}").unwrap();
+    }
+}
+
+fn write_string(string: &str, out: &mut String) {
+    out.push('\'');
+    for c in string.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{c}' => out.push_str("\\f"),
+            c if c.is_control() => write!(out, "\\u{:04X}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+}
+
+fn write_list<V: Borrow<Value>>(values: impl Iterator<Item = V>, out: &mut String) {
+    out.push('[');
+    for (index, value) in values.enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        write_literal(value.borrow(), out);
+    }
+    out.push(']');
+}
+
+fn write_map<'a>(entries: impl IntoIterator<Item = (&'a str, &'a Value)>, out: &mut String) {
+    let mut entries: Vec<_> = entries.into_iter().collect();
+    entries.sort_by_key(|(key, _)| *key);
+
+    out.push('{');
+    for (index, (key, value)) in entries.into_iter().enumerate() {
+        if index > 0 {
+            out.push_str(", ");
+        }
+        write_key(key, out);
+        out.push_str(": ");
+        write_literal(value, out);
+    }
+    out.push('}');
+}
+
+fn write_key(key: &str, out: &mut String) {
+    let mut chars = key.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+    if is_identifier {
+        out.push_str(key);
+    } else {
+        write!(out, "`{}`", key.replace('`', "``")).unwrap();
+    }
+}
+
+fn write_function(name: &str, value: &Value, out: &mut String) {
+    out.push_str(name);
+    out.push('(');
+    write_string(&temporal_string(value).unwrap(), out);
+    out.push(')');
+}
+
+fn write_node(node: &Node, out: &mut String) {
+    write_map(
+        [
+            ("id", &Value::Integer(node.node_identity)),
+            ("labels", &Value::from(node.labels.clone())),
+            ("properties", &Value::Map(node.properties.clone())),
+        ],
+        out,
+    );
+}
+
+fn write_unbound_relationship(rel: &UnboundRelationship, out: &mut String) {
+    write_map(
+        [
+            ("id", &Value::Integer(rel.rel_identity)),
+            ("type", &Value::String(rel.rel_type.clone())),
+            ("properties", &Value::Map(rel.properties.clone())),
+        ],
+        out,
+    );
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    // Number of lists, maps and function calls enclosing the value currently being parsed
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> ParseResult<(usize, char)> {
+        self.skip_whitespace();
+        self.chars.peek().copied().ok_or(ParseError::UnexpectedEnd)
+    }
+
+    fn expect(&mut self, expected: char) -> ParseResult<()> {
+        match self.peek()? {
+            (_, found) if found == expected => {
+                self.chars.next();
+                Ok(())
+            }
+            (position, found) => Err(ParseError::UnexpectedCharacter { found, position }),
+        }
+    }
+
+    fn value(&mut self) -> ParseResult<Value> {
+        if self.depth >= MAX_DEPTH {
+            return Err(ParseError::MaxDepthExceeded(MAX_DEPTH));
+        }
+        self.depth += 1;
+        let value = self.nested_value();
+        self.depth -= 1;
+        value
+    }
+
+    fn nested_value(&mut self) -> ParseResult<Value> {
+        match self.peek()? {
+            (_, '\'' | '"') => Ok(Value::String(self.string()?)),
+            (_, '[') => self.list(),
+            (_, '{') => Ok(Value::Map(self.map()?)),
+            (_, c) if c.is_ascii_digit() || c == '-' || c == '.' => self.number(),
+            (_, c) if c.is_alphabetic() || c == '_' => {
+                let name = self.identifier();
+                match name.to_lowercase().as_str() {
+                    "null" => Ok(Value::Null),
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    "nan" => Ok(Value::Float(f64::NAN)),
+                    "inf" | "infinity" => Ok(Value::Float(f64::INFINITY)),
+                    function => self.function(function),
+                }
+            }
+            (position, found) => Err(ParseError::UnexpectedCharacter { found, position }),
+        }
+    }
+
+    fn identifier(&mut self) -> &'a str {
+        let start = self.chars.peek().map_or(self.input.len(), |(i, _)| *i);
+        while self
+            .chars
+            .next_if(|(_, c)| c.is_alphanumeric() || *c == '_')
+            .is_some()
+        {}
+        let end = self.chars.peek().map_or(self.input.len(), |(i, _)| *i);
+        &self.input[start..end]
+    }
+
+    fn number(&mut self) -> ParseResult<Value> {
+        let (start, _) = self.peek()?;
+        self.chars.next_if(|(_, c)| *c == '-');
+        if let Some((_, c)) = self.chars.peek() {
+            if c.is_alphabetic() {
+                return match self.identifier().to_lowercase().as_str() {
+                    "inf" | "infinity" => Ok(Value::Float(f64::NEG_INFINITY)),
+                    _ => Err(self.invalid("number", start)),
+                };
+            }
+        }
+
+        while self
+            .chars
+            .next_if(|(_, c)| c.is_ascii_alphanumeric() || *c == '.' || *c == '_')
+            .is_some()
+        {
+            // Allow signed exponents, e.g. 1e-5
+            if self.input[..self.position()].ends_with(['e', 'E'])
+                && !self.input[start..].starts_with("0x")
+                && !self.input[start..].starts_with("-0x")
+            {
+                self.chars.next_if(|(_, c)| *c == '-' || *c == '+');
+            }
+        }
+
+        let literal = self.input[start..self.position()].replace('_', "");
+        let (negative, digits) = match literal.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, literal.as_str()),
+        };
+        if let Some(hex) = digits.strip_prefix("0x") {
+            let signed = if negative {
+                format!("-{hex}")
+            } else {
+                hex.to_string()
+            };
+            return i64::from_str_radix(&signed, 16)
+                .map(Value::Integer)
+                .map_err(|_| self.invalid("number", start));
+        }
+        if digits.contains(['.', 'e', 'E']) {
+            literal
+                .parse::<f64>()
+                .map(Value::Float)
+                .map_err(|_| self.invalid("number", start))
+        } else {
+            literal
+                .parse::<i64>()
+                .map(Value::Integer)
+                .map_err(|_| self.invalid("number", start))
+        }
+    }
+
+    fn string(&mut self) -> ParseResult<String> {
+        let (_, quote) = self.chars.next().ok_or(ParseError::UnexpectedEnd)?;
+        let mut string = String::new();
+        loop {
+            let (position, c) = self.chars.next().ok_or(ParseError::UnexpectedEnd)?;
+            match c {
+                c if c == quote => return Ok(string),
+                '\\' => {
+                    let (_, escaped) = self.chars.next().ok_or(ParseError::UnexpectedEnd)?;
+                    let c = match escaped {
+                        '\\' | '\'' | '"' => escaped,
+                        'n' => '\n',
+                        'r' => '\r',
+                        't' => '\t',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' | 'U' => {
+                            let length = if escaped == 'u' { 4 } else { 8 };
+                            let mut hex = String::with_capacity(length);
+                            for _ in 0..length {
+                                hex.push(self.chars.next().ok_or(ParseError::UnexpectedEnd)?.1);
+                            }
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.invalid("string", position))?
+                        }
+                        _ => return Err(self.invalid("string", position)),
+                    };
+                    string.push(c);
+                }
+                c => string.push(c),
+            }
+        }
+    }
+
+    fn list(&mut self) -> ParseResult<Value> {
+        self.expect('[')?;
+        let mut list = Vec::new();
+        if self.peek()?.1 == ']' {
+            self.chars.next();
+            return Ok(Value::List(list));
+        }
+        loop {
+            list.push(self.value()?);
+            match self.peek()? {
+                (_, ',') => self.chars.next(),
+                (_, ']') => {
+                    self.chars.next();
+                    return Ok(Value::List(list));
+                }
+                (position, found) => {
+                    return Err(ParseError::UnexpectedCharacter { found, position })
+                }
+            };
+        }
+    }
+
+    fn map(&mut self) -> ParseResult<ValueMap> {
+        self.expect('{')?;
+        let mut map = ValueMap::new();
+        if self.peek()?.1 == '}' {
+            self.chars.next();
+            return Ok(map);
+        }
+        loop {
+            let key = match self.peek()? {
+                (_, '`') => {
+                    self.chars.next();
+                    let mut key = String::new();
+                    loop {
+                        match self.chars.next().ok_or(ParseError::UnexpectedEnd)? {
+                            (_, '`') if self.chars.next_if(|(_, c)| *c == '`').is_some() => {
+                                key.push('`')
+                            }
+                            (_, '`') => break,
+                            (_, c) => key.push(c),
+                        }
+                    }
+                    key
+                }
+                (_, c) if c.is_alphabetic() || c == '_' => self.identifier().to_string(),
+                (position, found) => {
+                    return Err(ParseError::UnexpectedCharacter { found, position })
+                }
+            };
+            self.expect(':')?;
+            map.insert(key, self.value()?);
+            match self.peek()? {
+                (_, ',') => self.chars.next(),
+                (_, '}') => {
+                    self.chars.next();
+                    return Ok(map);
+                }
+                (position, found) => {
+                    return Err(ParseError::UnexpectedCharacter { found, position })
+                }
+            };
+        }
+    }
+
+    fn function(&mut self, name: &str) -> ParseResult<Value> {
+        let start = self.position();
+        self.expect('(')?;
+        let value = if name == "point" {
+            let map = self.map()?;
+            parse_point(&map).ok_or_else(|| self.invalid("point", start))?
+        } else if name == "duration" && self.peek()?.1 == '{' {
+            let map = self.map()?;
+            parse_duration(&map).ok_or_else(|| self.invalid("duration", start))?
+        } else if name == "vector" {
+            let elements = self.value()?;
+            self.expect(',')?;
+            let dimension = self.value()?;
+            self.expect(',')?;
+            self.skip_whitespace();
+            let type_name = self.identifier();
+            parse_vector(elements, dimension, type_name)
+                .ok_or_else(|| self.invalid("vector", start))?
+        } else {
+            let argument = match self.peek()? {
+                (_, '\'' | '"') => self.string()?,
+                (position, found) => {
+                    return Err(ParseError::UnexpectedCharacter { found, position })
+                }
+            };
+            let value = match name {
+                "date" => parse_date(&argument),
+                "time" => parse_time(&argument),
+                "datetime" => parse_date_time(&argument),
+                "localtime" => parse_local_time(&argument).map(Value::LocalTime),
+                "localdatetime" => parse_local_date_time(&argument).map(Value::LocalDateTime),
+                "duration" => argument.parse().ok().map(Value::Duration),
+                _ => return Err(ParseError::UnknownFunction(name.to_string())),
+            };
+            value.ok_or(ParseError::InvalidLiteral {
+                kind: "temporal",
+                literal: argument,
+            })?
+        };
+        self.expect(')')?;
+        Ok(value)
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars.peek().map_or(self.input.len(), |(i, _)| *i)
+    }
+
+    fn invalid(&mut self, kind: &'static str, start: usize) -> ParseError {
+        let end = self.position();
+        ParseError::InvalidLiteral {
+            kind,
+            literal: self.input[start..end].to_string(),
+        }
+    }
+}
+
+pub(crate) fn parse_date(string: &str) -> Option<Value> {
+    NaiveDate::parse_from_str(string, "%Y-%m-%d")
+        .ok()
+        .map(Value::Date)
+}
+
+pub(crate) fn parse_local_time(string: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(string, TIME_FORMAT)
+        .or_else(|_| NaiveTime::parse_from_str(string, "%H:%M"))
+        .ok()
+}
+
+pub(crate) fn parse_local_date_time(string: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(string, LOCAL_DATE_TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(string, "%Y-%m-%dT%H:%M"))
+        .ok()
+}
+
+fn parse_offset(string: &str) -> Option<FixedOffset> {
+    if string == "Z" {
+        return FixedOffset::east_opt(0);
+    }
+    let (sign, rest) = match string.chars().next()? {
+        '+' => (1, &string[1..]),
+        '-' => (-1, &string[1..]),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "00"));
+    let seconds = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+    FixedOffset::east_opt(sign * seconds)
+}
+
+/// Split a string like `12:00:00+01:00` into its local part and offset.
+fn split_offset(string: &str) -> Option<(&str, FixedOffset)> {
+    let time_start = string.find('T').map_or(0, |index| index + 1);
+    let offset_start = time_start + string[time_start..].find(['+', '-', 'Z'])?;
+    Some((
+        &string[..offset_start],
+        parse_offset(&string[offset_start..])?,
+    ))
+}
+
+pub(crate) fn parse_time(string: &str) -> Option<Value> {
+    let (time, offset) = split_offset(string)?;
+    Some(Value::Time(parse_local_time(time)?, offset))
+}
+
+pub(crate) fn parse_date_time(string: &str) -> Option<Value> {
+    match string.strip_suffix(']').and_then(|s| s.split_once('[')) {
+        Some((date_time, zone)) => {
+            let zone: Tz = zone.parse().ok()?;
+            let date_time = match split_offset(date_time) {
+                Some((local, offset)) => offset
+                    .from_local_datetime(&parse_local_date_time(local)?)
+                    .single()?
+                    .with_timezone(&zone),
+                None => zone
+                    .from_local_datetime(&parse_local_date_time(date_time)?)
+                    .earliest()?,
+            };
+            Some(Value::DateTimeZoned(date_time))
+        }
+        None => {
+            let (local, offset) = split_offset(string)?;
+            let date_time: DateTime<FixedOffset> = offset
+                .from_local_datetime(&parse_local_date_time(local)?)
+                .single()?;
+            Some(Value::DateTimeOffset(date_time))
+        }
+    }
+}
+
+fn parse_point(map: &ValueMap) -> Option<Value> {
+    let coordinate = |keys: &[&str]| -> Option<Option<f64>> {
+        match keys.iter().find_map(|key| map.get(*key)) {
+            Some(Value::Float(float)) => Some(Some(*float)),
+            Some(Value::Integer(integer)) => Some(Some(*integer as f64)),
+            Some(_) => None,
+            None => Some(None),
+        }
+    };
+    let geographic = map.contains_key("longitude") || map.contains_key("latitude");
+    let x = coordinate(&["x", "longitude"])??;
+    let y = coordinate(&["y", "latitude"])??;
+    let z = coordinate(&["z", "height"])?;
+
+    let srid = match (map.get("srid"), map.get("crs")) {
+        (Some(Value::Integer(srid)), _) => i32::try_from(*srid).ok()?,
+        (None, Some(Value::String(crs))) => match crs.to_lowercase().as_str() {
+            "cartesian" => Point2D::SRID_CARTESIAN,
+            "cartesian-3d" => Point3D::SRID_CARTESIAN_3D,
+            "wgs-84" => Point2D::SRID_WGS_84,
+            "wgs-84-3d" => Point3D::SRID_WGS_84_3D,
+            _ => return None,
+        },
+        (None, None) => match (geographic, z.is_some()) {
+            (false, false) => Point2D::SRID_CARTESIAN,
+            (false, true) => Point3D::SRID_CARTESIAN_3D,
+            (true, false) => Point2D::SRID_WGS_84,
+            (true, true) => Point3D::SRID_WGS_84_3D,
+        },
+        _ => return None,
+    };
+
+    Some(match z {
+        Some(z) => Value::Point3D(Point3D::new(srid, x, y, z)),
+        None => Value::Point2D(Point2D::new(srid, x, y)),
+    })
+}
+
+// Each component defaults to zero, and larger units are converted into the stored ones
+fn parse_duration(map: &ValueMap) -> Option<Value> {
+    let component = |key: &str| -> Option<i64> {
+        match map.get(key) {
+            Some(Value::Integer(integer)) => Some(*integer),
+            Some(_) => None,
+            None => Some(0),
+        }
+    };
+    if map.keys().any(|key| {
+        ![
+            "years",
+            "months",
+            "weeks",
+            "days",
+            "hours",
+            "minutes",
+            "seconds",
+            "nanoseconds",
+        ]
+        .contains(&key.as_str())
+    }) {
+        return None;
+    }
+
+    let months = component("years")?
+        .checked_mul(12)?
+        .checked_add(component("months")?)?;
+    let days = component("weeks")?
+        .checked_mul(7)?
+        .checked_add(component("days")?)?;
+    let seconds = component("hours")?
+        .checked_mul(3600)?
+        .checked_add(component("minutes")?.checked_mul(60)?)?
+        .checked_add(component("seconds")?)?;
+    let nanos = component("nanoseconds")?;
+    // Keep nanoseconds within [0, 1e9), like the server does
+    let seconds = seconds.checked_add(nanos.div_euclid(1_000_000_000))?;
+    let nanos = nanos.rem_euclid(1_000_000_000) as i32;
+    Some(Value::Duration(Duration::new(months, days, seconds, nanos)))
+}
+
+fn parse_vector(elements: Value, dimension: Value, type_name: &str) -> Option<Value> {
+    let elements = match elements {
+        Value::List(elements) => elements,
+        _ => return None,
+    };
+    if dimension != Value::Integer(elements.len() as i64) {
+        return None;
+    }
+
+    fn integers<T: TryFrom<i64>>(elements: Vec<Value>) -> Option<Vec<T>> {
+        elements
+            .into_iter()
+            .map(|element| match element {
+                Value::Integer(integer) => T::try_from(integer).ok(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn floats(elements: Vec<Value>) -> Option<Vec<f64>> {
+        elements
+            .into_iter()
+            .map(|element| match element {
+                Value::Integer(integer) => Some(integer as f64),
+                Value::Float(float) => Some(float),
+                _ => None,
+            })
+            .collect()
+    }
+
+    let vector = match type_name.to_uppercase().as_str() {
+        "INTEGER8" | "INT8" => Vector::Int8(integers(elements)?),
+        "INTEGER16" | "INT16" => Vector::Int16(integers(elements)?),
+        "INTEGER32" | "INT32" => Vector::Int32(integers(elements)?),
+        "INTEGER64" | "INT64" | "INTEGER" => Vector::Int64(integers(elements)?),
+        "FLOAT32" => Vector::Float32(floats(elements)?.into_iter().map(|f| f as f32).collect()),
+        "FLOAT64" | "FLOAT" => Vector::Float64(floats(elements)?),
+        _ => return None,
+    };
+    Some(Value::Vector(vector))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{NaiveDate, NaiveTime};
+
+    use crate::value::*;
+
+    fn round_trip(value: Value, literal: &str) {
+        assert_eq!(value.to_cypher_literal(), literal);
+        assert_eq!(Value::from_cypher_literal(literal).unwrap(), value);
+    }
+
+    #[test]
+    fn scalars() {
+        round_trip(Value::Null, "null");
+        round_trip(Value::from(true), "true");
+        round_trip(Value::from(-42), "-42");
+        round_trip(Value::from(i64::MIN), "-9223372036854775808");
+        round_trip(Value::from(1.0), "1.0");
+        round_trip(Value::from(-2.5e-10), "-2.5e-10");
+        round_trip(Value::from(f64::INFINITY), "Infinity");
+        round_trip(Value::from(f64::NEG_INFINITY), "-Infinity");
+        round_trip(
+            Value::from("it's a \"test\"\\\n\u{1}"),
+            r#"'it\'s a "test"\\\n\u0001'"#,
+        );
+        assert!(matches!(
+            Value::from_cypher_literal("NaN").unwrap(),
+            Value::Float(float) if float.is_nan()
+        ));
+        assert_eq!(Value::from_cypher_literal("0x1F").unwrap(), Value::from(31));
+        assert_eq!(
+            Value::from_cypher_literal(" \"double\" ").unwrap(),
+            Value::from("double")
+        );
+        assert_eq!(
+            Value::from_cypher_literal("'\\u00e9'").unwrap(),
+            Value::from("é")
+        );
+        assert_eq!(
+            Value::from_cypher_literal("1e3").unwrap(),
+            Value::from(1000.0)
+        );
+    }
+
+    #[test]
+    fn collections() {
+        round_trip(Value::from(Vec::<Value>::new()), "[]");
+        round_trip(
+            Value::from(vec![Value::from(1), Value::Null, Value::from("a")]),
+            "[1, null, 'a']",
+        );
+        round_trip(
+            Value::from(HashMap::from([
+                ("b", Value::from(vec![1])),
+                ("a", Value::from(HashMap::<String, Value>::new())),
+                ("with space", Value::from(1.5)),
+                ("back`tick", Value::Null),
+            ])),
+            "{a: {}, b: [1], `back``tick`: null, `with space`: 1.5}",
+        );
+        assert_eq!(
+            Value::Bytes(vec![1, 2]).to_cypher_literal(),
+            String::from("[1, 2]")
+        );
+    }
+
+    #[test]
+    fn temporal() {
+        let date = NaiveDate::from_ymd_opt(2020, 1, 31).unwrap();
+        let time = NaiveTime::from_hms_nano_opt(12, 34, 56, 789_000_000).unwrap();
+        let offset = FixedOffset::east_opt(-5400).unwrap();
+        round_trip(Value::from(date), "date('2020-01-31')");
+        round_trip(Value::from((time, offset)), "time('12:34:56.789-01:30')");
+        round_trip(Value::from(time), "localtime('12:34:56.789')");
+        round_trip(
+            Value::from(date.and_time(time)),
+            "localdatetime('2020-01-31T12:34:56.789')",
+        );
+        round_trip(
+            Value::from(date.and_time(time).and_local_timezone(offset).unwrap()),
+            "datetime('2020-01-31T12:34:56.789-01:30')",
+        );
+        round_trip(
+            Value::from((
+                date.and_hms_opt(12, 0, 0).unwrap(),
+                chrono_tz::Europe::Paris,
+            )),
+            "datetime('2020-01-31T13:00:00+01:00[Europe/Paris]')",
+        );
+        round_trip(
+            Value::from(Duration::new(14, 3, 3723, 500_000_000)),
+            "duration('P1Y2M3DT1H2M3.5S')",
+        );
+        round_trip(
+            Value::from(Duration::new(0, 0, -1, 500_000_000)),
+            "duration('PT-0.5S')",
+        );
+
+        assert_eq!(
+            Value::from_cypher_literal("datetime('2020-01-31T12:00Z')").unwrap(),
+            Value::from(date.and_hms_opt(12, 0, 0).unwrap().and_utc().fixed_offset())
+        );
+        assert_eq!(
+            Value::from_cypher_literal("datetime('2020-07-01T12:00:00[Europe/Paris]')").unwrap(),
+            Value::from((
+                NaiveDate::from_ymd_opt(2020, 7, 1)
+                    .unwrap()
+                    .and_hms_opt(10, 0, 0)
+                    .unwrap(),
+                chrono_tz::Europe::Paris
+            ))
+        );
+        assert_eq!(
+            Value::from_cypher_literal("duration({weeks: 1, hours: 1, nanoseconds: -1})").unwrap(),
+            Value::from(Duration::new(0, 7, 3599, 999_999_999))
+        );
+        assert!(Value::from_cypher_literal("duration({fortnights: 1})").is_err());
+        assert_eq!(
+            Value::from_cypher_literal("duration('P1W')").unwrap(),
+            Value::from(Duration::new(0, 7, 0, 0))
+        );
+    }
+
+    #[test]
+    fn points() {
+        round_trip(
+            Value::from(Point2D::new(4326, 1.5, -2.0)),
+            "point({srid: 4326, x: 1.5, y: -2.0})",
+        );
+        round_trip(
+            Value::from(Point3D::new(9157, 1.0, 2.0, 3.0)),
+            "point({srid: 9157, x: 1.0, y: 2.0, z: 3.0})",
+        );
+        assert_eq!(
+            Value::from_cypher_literal("point({longitude: 1, latitude: 2})").unwrap(),
+            Value::from(Point2D::new(4326, 1.0, 2.0))
+        );
+        assert_eq!(
+            Value::from_cypher_literal("point({x: 1, y: 2, z: 3, crs: 'cartesian-3d'})").unwrap(),
+            Value::from(Point3D::new(9157, 1.0, 2.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn vectors() {
+        round_trip(
+            Value::from(vec![1.5_f32, -2.0]),
+            "vector([1.5, -2.0], 2, FLOAT32)",
+        );
+        round_trip(
+            Value::from(Vector::from(vec![1_i8, -2, 3])),
+            "vector([1, -2, 3], 3, INTEGER8)",
+        );
+        assert_eq!(
+            Value::from_cypher_literal("vector([1, 2.5], 2, float)").unwrap(),
+            Value::from(Vector::from(vec![1.0_f64, 2.5]))
+        );
+        assert!(matches!(
+            Value::from_cypher_literal("vector([1, 2], 3, INT8)"),
+            Err(ParseError::InvalidLiteral { kind: "vector", .. })
+        ));
+        assert!(matches!(
+            Value::from_cypher_literal("vector([1, 200], 2, INT8)"),
+            Err(ParseError::InvalidLiteral { kind: "vector", .. })
+        ));
+    }
+
+    #[test]
+    fn graph_structures() {
+        let node = Node::new(
+            1,
+            vec![String::from("A")],
+            HashMap::from([(String::from("k"), Value::from(1))]),
+        );
+        assert_eq!(
+            Value::from(node).to_cypher_literal(),
+            "{id: 1, labels: ['A'], properties: {k: 1}}"
+        );
+        let rel = Relationship::new(2, 1, 3, String::from("R"), HashMap::<String, Value>::new());
+        assert_eq!(
+            Value::from(rel).to_cypher_literal(),
+            "{endNode: 3, id: 2, properties: {}, startNode: 1, type: 'R'}"
+        );
+        let structure = Value::Structure {
+            signature: 0x24,
+            fields: vec![Value::from(150)],
+        };
+        assert_eq!(
+            structure.to_cypher_literal(),
+            "{fields: [150], signature: 36}"
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            Value::from_cypher_literal("[1, 2"),
+            Err(ParseError::UnexpectedEnd)
+        ));
+        assert!(matches!(
+            Value::from_cypher_literal("1 2"),
+            Err(ParseError::UnexpectedCharacter {
+                found: '2',
+                position: 2
+            })
+        ));
+        assert!(matches!(
+            Value::from_cypher_literal("foo('x')"),
+            Err(ParseError::UnknownFunction(_))
+        ));
+        assert!(matches!(
+            Value::from_cypher_literal("date('2020-13-01')"),
+            Err(ParseError::InvalidLiteral { .. })
+        ));
+        assert!(matches!(
+            Value::from_cypher_literal("99999999999999999999"),
+            Err(ParseError::InvalidLiteral { .. })
+        ));
+    }
+
+    #[test]
+    fn max_depth() {
+        let nested = format!("{}{}", "[".repeat(256), "]".repeat(256));
+        assert!(Value::from_cypher_literal(&nested).is_ok());
+        let nested = format!("{}{}", "[".repeat(257), "]".repeat(257));
+        assert!(matches!(
+            Value::from_cypher_literal(&nested),
+            Err(ParseError::MaxDepthExceeded(256))
+        ));
+        assert!(matches!(
+            Value::from_cypher_literal(&"[".repeat(200_000)),
+            Err(ParseError::MaxDepthExceeded(256))
+        ));
+    }
+}
@@ -2,14 +2,17 @@ use std::collections::HashMap;
 
 use bolt_proto_derive::*;
 
-use crate::{value::SIGNATURE_NODE, Value};
+use crate::{
+    value::{ValueMap, SIGNATURE_NODE},
+    Value,
+};
 
 #[bolt_structure(SIGNATURE_NODE)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Node {
     pub(crate) node_identity: i64,
     pub(crate) labels: Vec<String>,
-    pub(crate) properties: HashMap<String, Value>,
+    pub(crate) properties: ValueMap,
 }
 
 impl Node {
@@ -33,7 +36,7 @@ impl Node {
         &self.labels
     }
 
-    pub fn properties(&self) -> &HashMap<String, Value> {
+    pub fn properties(&self) -> &ValueMap {
         &self.properties
     }
 }
@@ -0,0 +1,673 @@
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+};
+
+use chrono::{Offset, Timelike};
+
+use crate::value::{Duration, Path, Value};
+
+// Average length of a month in seconds, as used by Neo4j to compare durations
+const AVG_SECONDS_PER_MONTH: i128 = 2_629_746;
+const SECONDS_PER_DAY: i128 = 86_400;
+
+impl Value {
+    /// Compare two values according to Cypher's orderability rules, which define a total order
+    /// over all values.
+    ///
+    /// Values of different types are ordered as follows: maps, nodes, relationships, lists, paths,
+    /// date-times with offset or time zone, local date-times, dates, times with offset, local
+    /// times, durations, points, strings, booleans, numbers, other structures, and finally nulls.
+    /// Within a type:
+    /// - Integers and floats are compared numerically, and `NaN` is greater than all other numbers.
+    /// - Lists are compared element by element, and [`Bytes`](Value::Bytes) and
+    ///   [`Vector`](Value::Vector)s are treated as lists of numbers.
+    /// - Maps are compared by size, then by their sorted keys, then by the values of those keys.
+    /// - Nodes and relationships are compared by identity, and paths by the identities of the
+    ///   nodes and relationships they traverse.
+    /// - Temporal values are compared by the instant they represent, with ties broken by offset
+    ///   and time zone.
+    /// - Durations are compared by approximate length, assuming an average month length, with ties
+    ///   broken by months, days, seconds, and nanoseconds.
+    /// - Points are compared by SRID, then by coordinates.
+    /// - Other [`Structure`](Value::Structure)s are compared by signature, then by fields.
+    pub fn cypher_cmp(&self, other: &Value) -> Ordering {
+        let rank = type_rank(self).cmp(&type_rank(other));
+        if rank != Ordering::Equal {
+            return rank;
+        }
+
+        match (self, other) {
+            (Value::Null, Value::Null) => Ordering::Equal,
+            (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+            (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+            (Value::Float(a), Value::Float(b)) => cmp_float(*a, *b),
+            (Value::Integer(a), Value::Float(b)) => cmp_integer_float(*a, *b),
+            (Value::Float(a), Value::Integer(b)) => cmp_integer_float(*b, *a).reverse(),
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            (Value::Map(a), Value::Map(b)) => {
+                let mut a_keys: Vec<&String> = a.keys().collect();
+                let mut b_keys: Vec<&String> = b.keys().collect();
+                a_keys.sort_unstable();
+                b_keys.sort_unstable();
+                a_keys
+                    .len()
+                    .cmp(&b_keys.len())
+                    .then_with(|| a_keys.cmp(&b_keys))
+                    .then_with(|| {
+                        a_keys
+                            .iter()
+                            .map(|key| a[*key].cypher_cmp(&b[*key]))
+                            .find(|ordering| ordering.is_ne())
+                            .unwrap_or(Ordering::Equal)
+                    })
+            }
+            (Value::Node(a), Value::Node(b)) => a.node_identity.cmp(&b.node_identity),
+            (Value::Path(a), Value::Path(b)) => path_key(a).cmp(&path_key(b)),
+            (Value::Date(a), Value::Date(b)) => a.cmp(b),
+            (Value::Time(a_time, a_offset), Value::Time(b_time, b_offset)) => {
+                utc_nanos(a_time, a_offset.local_minus_utc())
+                    .cmp(&utc_nanos(b_time, b_offset.local_minus_utc()))
+                    .then_with(|| a_offset.local_minus_utc().cmp(&b_offset.local_minus_utc()))
+            }
+            (Value::LocalTime(a), Value::LocalTime(b)) => a.cmp(b),
+            (Value::LocalDateTime(a), Value::LocalDateTime(b)) => a.cmp(b),
+            (Value::Duration(a), Value::Duration(b)) => cmp_duration(a, b),
+            (
+                Value::Structure {
+                    signature: a_signature,
+                    fields: a_fields,
+                },
+                Value::Structure {
+                    signature: b_signature,
+                    fields: b_fields,
+                },
+            ) => a_signature
+                .cmp(b_signature)
+                .then_with(|| cmp_lists(a_fields, b_fields)),
+            (a, b) => match (a.as_list(), b.as_list()) {
+                (Some(a), Some(b)) => cmp_lists(&a, &b),
+                _ => match (relationship_identity(a), relationship_identity(b)) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    _ => match (date_time_key(a), date_time_key(b)) {
+                        (Some(a), Some(b)) => a.cmp(&b),
+                        _ => cmp_points(a, b),
+                    },
+                },
+            },
+        }
+    }
+
+    /// Compare two values for equality according to Cypher's `=` operator. Returns `None` if the
+    /// result is `null`, which happens when either value is [`Null`](Value::Null), or when lists
+    /// or maps are otherwise equal but contain nulls.
+    ///
+    /// Unlike [`Value::cypher_cmp`], `NaN` is not equal to anything, including itself.
+    pub fn cypher_eq(&self, other: &Value) -> Option<bool> {
+        match (self, other) {
+            (Value::Null, _) | (_, Value::Null) => None,
+            (Value::Float(float), _) | (_, Value::Float(float)) if float.is_nan() => Some(false),
+            (Value::Map(a), Value::Map(b)) => {
+                if a.len() != b.len() || a.keys().any(|key| !b.contains_key(key)) {
+                    return Some(false);
+                }
+                all_equal(a.iter().map(|(key, value)| value.cypher_eq(&b[key])))
+            }
+            (a, b) => match (a.as_list(), b.as_list()) {
+                (Some(a), Some(b)) => {
+                    if a.len() != b.len() {
+                        return Some(false);
+                    }
+                    all_equal(a.iter().zip(b.iter()).map(|(a, b)| a.cypher_eq(b)))
+                }
+                _ => Some(a.cypher_cmp(b).is_eq()),
+            },
+        }
+    }
+
+    fn as_list(&self) -> Option<Cow<'_, [Value]>> {
+        match self {
+            Value::List(list) => Some(Cow::Borrowed(list)),
+            Value::Bytes(bytes) => Some(Cow::Owned(
+                bytes
+                    .iter()
+                    .map(|&byte| Value::Integer(byte.into()))
+                    .collect(),
+            )),
+            Value::Vector(vector) => Some(Cow::Owned(vector.to_values())),
+            _ => None,
+        }
+    }
+}
+
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Map(_) => 0,
+        Value::Node(_) => 1,
+        Value::Relationship(_) | Value::UnboundRelationship(_) => 2,
+        Value::List(_) | Value::Bytes(_) | Value::Vector(_) => 3,
+        Value::Path(_) => 4,
+        Value::DateTimeOffset(_) | Value::DateTimeZoned(_) => 5,
+        Value::LocalDateTime(_) => 6,
+        Value::Date(_) => 7,
+        Value::Time(_, _) => 8,
+        Value::LocalTime(_) => 9,
+        Value::Duration(_) => 10,
+        Value::Point2D(_) | Value::Point3D(_) => 11,
+        Value::String(_) => 12,
+        Value::Boolean(_) => 13,
+        Value::Integer(_) | Value::Float(_) => 14,
+        Value::Structure { .. } => 15,
+        Value::Null => 16,
+    }
+}
+
+/// `None` is `null`, which makes the whole comparison `null` unless something is unequal.
+fn all_equal(results: impl Iterator<Item = Option<bool>>) -> Option<bool> {
+    let mut result = Some(true);
+    for equal in results {
+        match equal {
+            Some(false) => return Some(false),
+            None => result = None,
+            Some(true) => {}
+        }
+    }
+    result
+}
+
+fn cmp_float(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => a.partial_cmp(&b).unwrap(),
+    }
+}
+
+// 2^63, the smallest float greater than i64::MAX
+const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+
+fn cmp_integer_float(integer: i64, float: f64) -> Ordering {
+    if float.is_nan() || float >= I64_BOUND {
+        return Ordering::Less;
+    }
+    if float < -I64_BOUND {
+        return Ordering::Greater;
+    }
+
+    // The float is now within i64 range, so this comparison is exact
+    let truncated = float.trunc();
+    integer
+        .cmp(&(truncated as i64))
+        .then_with(|| cmp_float(truncated, float))
+}
+
+fn cmp_lists(a: &[Value], b: &[Value]) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| a.cypher_cmp(b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn cmp_duration(a: &Duration, b: &Duration) -> Ordering {
+    let approximate_seconds = |duration: &Duration| {
+        i128::from(duration.months) * AVG_SECONDS_PER_MONTH
+            + i128::from(duration.days) * SECONDS_PER_DAY
+            + i128::from(duration.seconds)
+    };
+    approximate_seconds(a)
+        .cmp(&approximate_seconds(b))
+        .then_with(|| a.nanos.cmp(&b.nanos))
+        .then_with(|| {
+            (a.months, a.days, a.seconds, a.nanos).cmp(&(b.months, b.days, b.seconds, b.nanos))
+        })
+}
+
+fn cmp_points(a: &Value, b: &Value) -> Ordering {
+    let (a_srid, a_coords) = point_key(a);
+    let (b_srid, b_coords) = point_key(b);
+    a_srid.cmp(&b_srid).then_with(|| {
+        a_coords
+            .iter()
+            .zip(&b_coords)
+            .map(|(a, b)| cmp_float(*a, *b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a_coords.len().cmp(&b_coords.len()))
+    })
+}
+
+fn point_key(value: &Value) -> (i32, Vec<f64>) {
+    match value {
+        Value::Point2D(point) => (point.srid, vec![point.x, point.y]),
+        Value::Point3D(point) => (point.srid, vec![point.x, point.y, point.z]),
+        other => unreachable!("{:?} is not a point", other),
+    }
+}
+
+fn relationship_identity(value: &Value) -> Option<i64> {
+    match value {
+        Value::Relationship(rel) => Some(rel.rel_identity),
+        Value::UnboundRelationship(rel) => Some(rel.rel_identity),
+        _ => None,
+    }
+}
+
+/// The UTC instant in nanoseconds, followed by the offset and time zone name.
+fn date_time_key(value: &Value) -> Option<(i128, i32, Option<&'static str>)> {
+    match value {
+        Value::DateTimeOffset(date_time) => Some((
+            date_time_nanos(date_time.timestamp(), date_time.nanosecond()),
+            date_time.offset().local_minus_utc(),
+            None,
+        )),
+        Value::DateTimeZoned(date_time) => Some((
+            date_time_nanos(date_time.timestamp(), date_time.nanosecond()),
+            date_time.offset().fix().local_minus_utc(),
+            Some(date_time.timezone().name()),
+        )),
+        _ => None,
+    }
+}
+
+fn date_time_nanos(seconds: i64, nanos: u32) -> i128 {
+    i128::from(seconds) * 1_000_000_000 + i128::from(nanos)
+}
+
+fn utc_nanos(time: &impl Timelike, offset_seconds: i32) -> i64 {
+    (i64::from(time.num_seconds_from_midnight()) - i64::from(offset_seconds)) * 1_000_000_000
+        + i64::from(time.nanosecond())
+}
+
+/// Identities of the nodes and relationships traversed by a path, in order. Malformed paths are
+/// ordered after all well-formed paths, by their raw contents.
+fn path_key(path: &Path) -> (bool, Vec<i64>) {
+    let traversal = || {
+        if !path.sequence.len().is_multiple_of(2) {
+            return None;
+        }
+        let mut identities = vec![path.nodes.first()?.node_identity];
+        for pair in path.sequence.chunks(2) {
+            let rel_index = usize::try_from(pair[0].unsigned_abs())
+                .ok()?
+                .checked_sub(1)?;
+            identities.push(path.relationships.get(rel_index)?.rel_identity);
+            let node_index = usize::try_from(pair[1]).ok()?;
+            identities.push(path.nodes.get(node_index)?.node_identity);
+        }
+        Some(identities)
+    };
+
+    match traversal() {
+        Some(identities) => (false, identities),
+        None => (
+            true,
+            path.nodes
+                .iter()
+                .map(|node| node.node_identity)
+                .chain(path.relationships.iter().map(|rel| rel.rel_identity))
+                .chain(path.sequence.iter().copied())
+                .collect(),
+        ),
+    }
+}
+
+/// A wrapper around a [`Value`] that implements [`Eq`], [`Ord`], and [`Hash`] using Cypher's
+/// equivalence and orderability rules (see [`Value::cypher_cmp`]), so values can be sorted or
+/// used as keys in sets and maps.
+///
+/// Under these rules, `null` is equivalent to `null`, `NaN` is equivalent to `NaN`, integers are
+/// equivalent to floats with the same numeric value, and nodes and relationships are equivalent if
+/// they have the same identity.
+#[derive(Debug, Clone)]
+pub struct ValueKey(pub Value);
+
+impl ValueKey {
+    pub fn into_inner(self) -> Value {
+        self.0
+    }
+}
+
+impl From<Value> for ValueKey {
+    fn from(value: Value) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ValueKey> for Value {
+    fn from(key: ValueKey) -> Self {
+        key.0
+    }
+}
+
+impl AsRef<Value> for ValueKey {
+    fn as_ref(&self) -> &Value {
+        &self.0
+    }
+}
+
+impl PartialEq for ValueKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.cypher_cmp(&other.0).is_eq()
+    }
+}
+
+impl Eq for ValueKey {}
+
+impl PartialOrd for ValueKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cypher_cmp(&other.0)
+    }
+}
+
+impl Hash for ValueKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_value(&self.0, state);
+    }
+}
+
+/// Hashes values so that values which are equivalent under [`Value::cypher_cmp`] have the same
+/// hash.
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    type_rank(value).hash(state);
+    match value {
+        Value::Boolean(boolean) => boolean.hash(state),
+        Value::Integer(integer) => integer.hash(state),
+        Value::Float(float) => {
+            if float.fract() == 0.0 && (-I64_BOUND..I64_BOUND).contains(float) {
+                (*float as i64).hash(state);
+            } else {
+                hash_float(*float, state);
+            }
+        }
+        Value::Bytes(_) | Value::List(_) | Value::Vector(_) => {
+            let list = value.as_list().unwrap();
+            list.len().hash(state);
+            for value in list.iter() {
+                hash_value(value, state);
+            }
+        }
+        Value::Map(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_unstable();
+            keys.len().hash(state);
+            for key in keys {
+                key.hash(state);
+                hash_value(&map[key], state);
+            }
+        }
+        Value::Null => {}
+        Value::String(string) => string.hash(state),
+        Value::Node(node) => node.node_identity.hash(state),
+        Value::Relationship(_) | Value::UnboundRelationship(_) => {
+            relationship_identity(value).hash(state)
+        }
+        Value::Path(path) => path_key(path).hash(state),
+        Value::Date(date) => date.hash(state),
+        Value::Time(time, offset) => {
+            time.hash(state);
+            offset.local_minus_utc().hash(state);
+        }
+        Value::DateTimeOffset(_) | Value::DateTimeZoned(_) => date_time_key(value).hash(state),
+        Value::LocalTime(time) => time.hash(state),
+        Value::LocalDateTime(date_time) => date_time.hash(state),
+        Value::Duration(duration) => duration.hash(state),
+        Value::Point2D(_) | Value::Point3D(_) => {
+            let (srid, coords) = point_key(value);
+            srid.hash(state);
+            coords.len().hash(state);
+            for coord in coords {
+                hash_float(coord, state);
+            }
+        }
+        Value::Structure { signature, fields } => {
+            signature.hash(state);
+            fields.len().hash(state);
+            for field in fields {
+                hash_value(field, state);
+            }
+        }
+    }
+}
+
+fn hash_float<H: Hasher>(float: f64, state: &mut H) {
+    if float.is_nan() {
+        f64::NAN.to_bits().hash(state);
+    } else if float == 0.0 {
+        // Treat -0.0 and 0.0 the same
+        0.0_f64.to_bits().hash(state);
+    } else {
+        float.to_bits().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use chrono::{FixedOffset, NaiveDate, NaiveTime};
+
+    use crate::value::*;
+
+    use super::*;
+
+    #[test]
+    fn type_order() {
+        let date_time = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        let values = vec![
+            Value::from(HashMap::<String, Value>::new()),
+            Value::from(Node::new(1, vec![], HashMap::<String, Value>::new())),
+            Value::from(Relationship::new(
+                1,
+                1,
+                2,
+                String::from("R"),
+                HashMap::<String, Value>::new(),
+            )),
+            Value::from(vec![1]),
+            Value::from(Path::new(
+                vec![Node::new(1, vec![], HashMap::<String, Value>::new())],
+                vec![],
+                vec![],
+            )),
+            Value::from(date_time.and_utc().fixed_offset()),
+            Value::from(date_time),
+            Value::from(date_time.date()),
+            Value::from((date_time.time(), FixedOffset::east_opt(0).unwrap())),
+            Value::from(date_time.time()),
+            Value::from(Duration::new(0, 0, 0, 0)),
+            Value::from(Point2D::new(4326, 0.0, 0.0)),
+            Value::from("a"),
+            Value::from(false),
+            Value::from(0),
+            Value::Structure {
+                signature: 0x24,
+                fields: vec![],
+            },
+            Value::Null,
+        ];
+        for (i, a) in values.iter().enumerate() {
+            for (j, b) in values.iter().enumerate() {
+                assert_eq!(a.cypher_cmp(b), i.cmp(&j), "{:?} vs {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(
+            Value::from(1).cypher_cmp(&Value::from(1.0)),
+            Ordering::Equal
+        );
+        assert_eq!(Value::from(1).cypher_cmp(&Value::from(1.5)), Ordering::Less);
+        assert_eq!(
+            Value::from(-1).cypher_cmp(&Value::from(-1.5)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::from(i64::MAX).cypher_cmp(&Value::from(i64::MAX as f64)),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(f64::NAN).cypher_cmp(&Value::from(f64::INFINITY)),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::from(f64::NAN).cypher_cmp(&Value::from(f64::NAN)),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::from(0.0).cypher_cmp(&Value::from(-0.0)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn collections() {
+        assert_eq!(
+            Value::from(vec![1, 2]).cypher_cmp(&Value::from(vec![1, 2, 0])),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(vec![2]).cypher_cmp(&Value::from(vec![1, 2, 0])),
+            Ordering::Greater
+        );
+        assert_eq!(
+            Value::Bytes(vec![1, 2]).cypher_cmp(&Value::from(vec![1, 2])),
+            Ordering::Equal
+        );
+        assert_eq!(
+            Value::from(vec![1.0_f32, 2.5]).cypher_cmp(&Value::from(vec![1.0, 2.5])),
+            Ordering::Equal
+        );
+
+        let small = Value::from(HashMap::from([("b", 1)]));
+        let large = Value::from(HashMap::from([("a", 1), ("b", 1)]));
+        assert_eq!(small.cypher_cmp(&large), Ordering::Less);
+        assert_eq!(
+            Value::from(HashMap::from([("a", 1)]))
+                .cypher_cmp(&Value::from(HashMap::from([("b", 0)]))),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(HashMap::from([("a", 2)]))
+                .cypher_cmp(&Value::from(HashMap::from([("a", 1)]))),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn temporal() {
+        let date_time = NaiveDate::from_ymd_opt(2020, 1, 1)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc();
+        let utc = Value::from(date_time.fixed_offset());
+        let plus_one = Value::from(date_time.with_timezone(&FixedOffset::east_opt(3600).unwrap()));
+        // Same instant, different offsets
+        assert_eq!(utc.cypher_cmp(&plus_one), Ordering::Less);
+        assert_eq!(utc.cypher_eq(&plus_one), Some(false));
+
+        let noon_utc = Value::from((
+            NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            FixedOffset::east_opt(0).unwrap(),
+        ));
+        let noon_plus_one = Value::from((
+            NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            FixedOffset::east_opt(3600).unwrap(),
+        ));
+        assert_eq!(noon_plus_one.cypher_cmp(&noon_utc), Ordering::Less);
+
+        assert_eq!(
+            Value::from(Duration::new(1, 0, 0, 0))
+                .cypher_cmp(&Value::from(Duration::new(0, 31, 0, 0))),
+            Ordering::Less
+        );
+        assert_eq!(
+            Value::from(Duration::new(0, 1, 0, 0))
+                .cypher_cmp(&Value::from(Duration::new(0, 0, 86_400, 0))),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn equality() {
+        assert_eq!(Value::Null.cypher_eq(&Value::Null), None);
+        assert_eq!(Value::from(1).cypher_eq(&Value::Null), None);
+        assert_eq!(
+            Value::from(f64::NAN).cypher_eq(&Value::from(f64::NAN)),
+            Some(false)
+        );
+        assert_eq!(Value::from(1).cypher_eq(&Value::from(1.0)), Some(true));
+        assert_eq!(Value::from(1).cypher_eq(&Value::from("1")), Some(false));
+        assert_eq!(
+            Value::from(vec![Value::from(1), Value::Null])
+                .cypher_eq(&Value::from(vec![Value::from(1), Value::Null])),
+            None
+        );
+        assert_eq!(
+            Value::from(vec![Value::from(1), Value::Null])
+                .cypher_eq(&Value::from(vec![Value::from(2), Value::Null])),
+            Some(false)
+        );
+        assert_eq!(
+            Value::from(vec![1]).cypher_eq(&Value::from(vec![1, 2])),
+            Some(false)
+        );
+        assert_eq!(
+            Value::from(HashMap::from([("a", Value::Null)]))
+                .cypher_eq(&Value::from(HashMap::from([("b", Value::Null)]))),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn value_key() {
+        let mut set = HashSet::new();
+        assert!(set.insert(ValueKey(Value::from(1))));
+        assert!(!set.insert(ValueKey(Value::from(1.0))));
+        assert!(set.insert(ValueKey(Value::from(f64::NAN))));
+        assert!(!set.insert(ValueKey(Value::from(f64::NAN))));
+        assert!(set.insert(ValueKey(Value::Null)));
+        assert!(!set.insert(ValueKey(Value::Null)));
+        assert!(set.insert(ValueKey(Value::from(HashMap::from([("a", 1), ("b", 2)])))));
+        assert!(!set.insert(ValueKey(Value::from(HashMap::from([
+            ("b", 2.0),
+            ("a", 1.0)
+        ])))));
+        assert!(set.insert(ValueKey(Value::Bytes(vec![1]))));
+        assert!(!set.insert(ValueKey(Value::from(vec![1.0]))));
+        assert!(set.insert(ValueKey(Value::from(Point2D::new(1, 0.0, 1.0)))));
+        assert!(!set.insert(ValueKey(Value::from(Point2D::new(1, -0.0, 1.0)))));
+        assert_eq!(set.len(), 6);
+
+        let mut rows = vec![
+            ValueKey(Value::Null),
+            ValueKey(Value::from(2)),
+            ValueKey(Value::from("b")),
+            ValueKey(Value::from(1.5)),
+        ];
+        rows.sort();
+        let rows: Vec<Value> = rows.into_iter().map(ValueKey::into_inner).collect();
+        assert_eq!(
+            rows,
+            vec![
+                Value::from("b"),
+                Value::from(1.5),
+                Value::from(2),
+                Value::Null
+            ]
+        );
+    }
+}
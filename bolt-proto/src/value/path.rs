@@ -1,9 +1,12 @@
 use bolt_proto_derive::*;
 
-use crate::value::{Node, UnboundRelationship, SIGNATURE_PATH};
+use crate::{
+    error::{PathError, PathResult},
+    value::{Node, Relationship, UnboundRelationship, SIGNATURE_PATH},
+};
 
 #[bolt_structure(SIGNATURE_PATH)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Path {
     pub(crate) nodes: Vec<Node>,
     pub(crate) relationships: Vec<UnboundRelationship>,
@@ -34,4 +37,154 @@ impl Path {
     pub fn sequence(&self) -> &[i64] {
         &self.sequence
     }
+
+    /// Get the number of relationships traversed by this path.
+    pub fn len(&self) -> usize {
+        self.sequence.len() / 2
+    }
+
+    /// Returns `true` if this path consists of a single node.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the node this path starts at.
+    pub fn start(&self) -> PathResult<&Node> {
+        self.nodes.first().ok_or(PathError::NoNodes)
+    }
+
+    /// Get the node this path ends at.
+    pub fn end(&self) -> PathResult<&Node> {
+        match self.segments()?.pop() {
+            Some((_, _, end)) => Ok(end),
+            None => self.start(),
+        }
+    }
+
+    /// Check that the index sequence of this path only refers to nodes and relationships that are
+    /// present in the path.
+    pub fn validate(&self) -> PathResult<()> {
+        self.segments().map(|_| ())
+    }
+
+    /// Decode the index sequence of this path into `(start, relationship, end)` triples, in
+    /// traversal order. Each relationship is bound to the identities of its actual start and end
+    /// nodes, which may be reversed compared to the direction of traversal.
+    pub fn segments(&self) -> PathResult<Vec<(&Node, Relationship, &Node)>> {
+        if !self.sequence.len().is_multiple_of(2) {
+            return Err(PathError::OddSequenceLength(self.sequence.len()));
+        }
+
+        let mut current = self.start()?;
+        let mut segments = Vec::with_capacity(self.len());
+        for pair in self.sequence.chunks(2) {
+            let (rel_index, node_index) = (pair[0], pair[1]);
+            let next = usize::try_from(node_index)
+                .ok()
+                .and_then(|index| self.nodes.get(index))
+                .ok_or(PathError::InvalidNodeIndex(node_index))?;
+            // Relationship indices are 1-based, and negative if traversed against their direction
+            let rel = usize::try_from(rel_index.unsigned_abs())
+                .ok()
+                .and_then(|index| index.checked_sub(1))
+                .and_then(|index| self.relationships.get(index))
+                .ok_or(PathError::InvalidRelationshipIndex(rel_index))?;
+            let (start, end) = if rel_index > 0 {
+                (current, next)
+            } else {
+                (next, current)
+            };
+            let relationship = Relationship {
+                rel_identity: rel.rel_identity,
+                start_node_identity: start.node_identity,
+                end_node_identity: end.node_identity,
+                rel_type: rel.rel_type.clone(),
+                properties: rel.properties.clone(),
+            };
+            segments.push((current, relationship, next));
+            current = next;
+        }
+        Ok(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::Value;
+
+    use super::*;
+
+    fn node(identity: i64) -> Node {
+        Node::new(identity, vec![], HashMap::<String, Value>::new())
+    }
+
+    fn rel(identity: i64) -> UnboundRelationship {
+        UnboundRelationship::new(identity, String::from("R"), HashMap::<String, Value>::new())
+    }
+
+    #[test]
+    fn segments() {
+        // (1)-[10]->(2)<-[11]-(3)
+        let path = Path::new(
+            vec![node(1), node(2), node(3)],
+            vec![rel(10), rel(11)],
+            vec![1, 1, -2, 2],
+        );
+        assert_eq!(path.len(), 2);
+        assert!(!path.is_empty());
+        assert_eq!(path.start().unwrap(), &node(1));
+        assert_eq!(path.end().unwrap(), &node(3));
+
+        let segments: Vec<(i64, i64, i64, i64, i64)> = path
+            .segments()
+            .unwrap()
+            .into_iter()
+            .map(|(start, rel, end)| {
+                (
+                    start.node_identity(),
+                    rel.rel_identity(),
+                    rel.start_node_identity(),
+                    rel.end_node_identity(),
+                    end.node_identity(),
+                )
+            })
+            .collect();
+        assert_eq!(segments, vec![(1, 10, 1, 2, 2), (2, 11, 3, 2, 3)]);
+    }
+
+    #[test]
+    fn single_node() {
+        let path = Path::new(vec![node(1)], vec![], vec![]);
+        assert!(path.is_empty());
+        assert_eq!(path.end().unwrap(), &node(1));
+        assert!(path.segments().unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed() {
+        let cases = vec![
+            (Path::new(vec![], vec![], vec![]), "path has no nodes"),
+            (
+                Path::new(vec![node(1)], vec![rel(10)], vec![1]),
+                "path sequence has odd length 1",
+            ),
+            (
+                Path::new(vec![node(1)], vec![rel(10)], vec![1, 1]),
+                "path sequence refers to invalid node index 1",
+            ),
+            (
+                Path::new(vec![node(1), node(2)], vec![rel(10)], vec![0, 1]),
+                "path sequence refers to invalid relationship index 0",
+            ),
+            (
+                Path::new(vec![node(1), node(2)], vec![rel(10)], vec![-2, 1]),
+                "path sequence refers to invalid relationship index -2",
+            ),
+        ];
+        for (path, message) in cases {
+            assert_eq!(path.validate().unwrap_err().to_string(), message);
+        }
+    }
 }
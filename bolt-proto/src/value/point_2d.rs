@@ -1,7 +1,11 @@
 use bolt_proto_derive::*;
 
-use crate::value::SIGNATURE_POINT_2D;
+use crate::value::{point_3d::haversine_distance, SIGNATURE_POINT_2D};
 
+/// A two-dimensional point in a coordinate reference system identified by its SRID.
+///
+/// For geographic points in [WGS-84](Point2D::SRID_WGS_84), `x` is the longitude and `y` is the
+/// latitude, both in degrees.
 #[bolt_structure(SIGNATURE_POINT_2D)]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Point2D {
@@ -11,10 +15,25 @@ pub struct Point2D {
 }
 
 impl Point2D {
+    /// The SRID of the geographic WGS-84 coordinate reference system.
+    pub const SRID_WGS_84: i32 = 4326;
+    /// The SRID of the two-dimensional Cartesian coordinate reference system.
+    pub const SRID_CARTESIAN: i32 = 7203;
+
     pub fn new(srid: i32, x: f64, y: f64) -> Self {
         Self { srid, x, y }
     }
 
+    /// Create a geographic point in WGS-84 from a longitude and latitude in degrees.
+    pub fn wgs_84(longitude: f64, latitude: f64) -> Self {
+        Self::new(Self::SRID_WGS_84, longitude, latitude)
+    }
+
+    /// Create a point in the two-dimensional Cartesian coordinate reference system.
+    pub fn cartesian(x: f64, y: f64) -> Self {
+        Self::new(Self::SRID_CARTESIAN, x, y)
+    }
+
     pub fn srid(&self) -> i32 {
         self.srid
     }
@@ -26,4 +45,45 @@ impl Point2D {
     pub fn y(&self) -> f64 {
         self.y
     }
+
+    /// Get the distance to another point, like Cypher's `point.distance`. For WGS-84 points this
+    /// is the great-circle distance in meters, computed with the haversine formula, and for
+    /// Cartesian points it is the Euclidean distance.
+    ///
+    /// Returns [`None`] if the points have different SRIDs, or an SRID other than
+    /// [`SRID_WGS_84`](Point2D::SRID_WGS_84) or [`SRID_CARTESIAN`](Point2D::SRID_CARTESIAN).
+    pub fn distance(&self, other: &Point2D) -> Option<f64> {
+        if self.srid != other.srid {
+            return None;
+        }
+        match self.srid {
+            Self::SRID_WGS_84 => Some(haversine_distance(self.x, self.y, other.x, other.y, 0.0)),
+            Self::SRID_CARTESIAN => Some((other.x - self.x).hypot(other.y - self.y)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        let a = Point2D::cartesian(1.0, 2.0);
+        let b = Point2D::cartesian(4.0, 6.0);
+        assert_eq!(a.distance(&b), Some(5.0));
+        assert_eq!(a.distance(&Point2D::wgs_84(4.0, 6.0)), None);
+        assert_eq!(
+            Point2D::new(1234, 0.0, 0.0).distance(&Point2D::new(1234, 1.0, 1.0)),
+            None
+        );
+
+        // Copenhagen to Malmö
+        let copenhagen = Point2D::wgs_84(12.568337, 55.676098);
+        let malmo = Point2D::wgs_84(13.003822, 55.604981);
+        let distance = copenhagen.distance(&malmo).unwrap();
+        assert!((distance - 28_482.4).abs() < 0.1, "{}", distance);
+        assert_eq!(malmo.distance(&malmo), Some(0.0));
+    }
 }
@@ -2,6 +2,13 @@ use bolt_proto_derive::*;
 
 use crate::value::SIGNATURE_POINT_3D;
 
+/// The equatorial Earth radius in meters, which Neo4j uses for geographic distances.
+const EARTH_RADIUS_METERS: f64 = 6_378_140.0;
+
+/// A three-dimensional point in a coordinate reference system identified by its SRID.
+///
+/// For geographic points in [WGS-84-3D](Point3D::SRID_WGS_84_3D), `x` is the longitude and `y` is
+/// the latitude, both in degrees, and `z` is the height in meters.
 #[bolt_structure(SIGNATURE_POINT_3D)]
 #[derive(Debug, Clone, PartialEq)]
 pub struct Point3D {
@@ -12,10 +19,26 @@ pub struct Point3D {
 }
 
 impl Point3D {
+    /// The SRID of the geographic WGS-84-3D coordinate reference system.
+    pub const SRID_WGS_84_3D: i32 = 4979;
+    /// The SRID of the three-dimensional Cartesian coordinate reference system.
+    pub const SRID_CARTESIAN_3D: i32 = 9157;
+
     pub fn new(srid: i32, x: f64, y: f64, z: f64) -> Self {
         Self { srid, x, y, z }
     }
 
+    /// Create a geographic point in WGS-84-3D from a longitude and latitude in degrees and a
+    /// height in meters.
+    pub fn wgs_84_3d(longitude: f64, latitude: f64, height: f64) -> Self {
+        Self::new(Self::SRID_WGS_84_3D, longitude, latitude, height)
+    }
+
+    /// Create a point in the three-dimensional Cartesian coordinate reference system.
+    pub fn cartesian_3d(x: f64, y: f64, z: f64) -> Self {
+        Self::new(Self::SRID_CARTESIAN_3D, x, y, z)
+    }
+
     pub fn srid(&self) -> i32 {
         self.srid
     }
@@ -31,4 +54,70 @@ impl Point3D {
     pub fn z(&self) -> f64 {
         self.z
     }
+
+    /// Get the distance to another point, like Cypher's `point.distance`. For WGS-84-3D points,
+    /// the haversine distance at the points' average height is combined with the difference in
+    /// height, and for Cartesian points this is the Euclidean distance.
+    ///
+    /// Returns [`None`] if the points have different SRIDs, or an SRID other than
+    /// [`SRID_WGS_84_3D`](Point3D::SRID_WGS_84_3D) or
+    /// [`SRID_CARTESIAN_3D`](Point3D::SRID_CARTESIAN_3D).
+    pub fn distance(&self, other: &Point3D) -> Option<f64> {
+        if self.srid != other.srid {
+            return None;
+        }
+        match self.srid {
+            Self::SRID_WGS_84_3D => {
+                let average_height = (self.z + other.z) / 2.0;
+                let distance = haversine_distance(self.x, self.y, other.x, other.y, average_height);
+                Some(distance.hypot(other.z - self.z))
+            }
+            Self::SRID_CARTESIAN_3D => Some(
+                ((other.x - self.x).powi(2)
+                    + (other.y - self.y).powi(2)
+                    + (other.z - self.z).powi(2))
+                .sqrt(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// Great-circle distance in meters between two longitude/latitude pairs in degrees, at the given
+/// height above the Earth's surface.
+pub(crate) fn haversine_distance(
+    longitude_1: f64,
+    latitude_1: f64,
+    longitude_2: f64,
+    latitude_2: f64,
+    height: f64,
+) -> f64 {
+    let (latitude_1, latitude_2) = (latitude_1.to_radians(), latitude_2.to_radians());
+    let delta_latitude = latitude_2 - latitude_1;
+    let delta_longitude = (longitude_2 - longitude_1).to_radians();
+    let a = (delta_latitude / 2.0).sin().powi(2)
+        + latitude_1.cos() * latitude_2.cos() * (delta_longitude / 2.0).sin().powi(2);
+    let angle = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
+    (EARTH_RADIUS_METERS + height) * angle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        let a = Point3D::cartesian_3d(1.0, 2.0, 3.0);
+        let b = Point3D::cartesian_3d(3.0, 4.0, 4.0);
+        assert_eq!(a.distance(&b), Some(3.0));
+        assert_eq!(a.distance(&Point3D::wgs_84_3d(3.0, 4.0, 4.0)), None);
+
+        let ground = Point3D::wgs_84_3d(12.568337, 55.676098, 0.0);
+        let above = Point3D::wgs_84_3d(12.568337, 55.676098, 100.0);
+        assert_eq!(ground.distance(&above), Some(100.0));
+
+        let malmo = Point3D::wgs_84_3d(13.003822, 55.604981, 0.0);
+        let distance = ground.distance(&malmo).unwrap();
+        assert!((distance - 28_482.4).abs() < 0.1, "{}", distance);
+    }
 }
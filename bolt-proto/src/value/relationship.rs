@@ -2,16 +2,19 @@ use std::collections::HashMap;
 
 use bolt_proto_derive::*;
 
-use crate::{value::SIGNATURE_RELATIONSHIP, Value};
+use crate::{
+    value::{ValueMap, SIGNATURE_RELATIONSHIP},
+    Value,
+};
 
 #[bolt_structure(SIGNATURE_RELATIONSHIP)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Relationship {
     pub(crate) rel_identity: i64,
     pub(crate) start_node_identity: i64,
     pub(crate) end_node_identity: i64,
     pub(crate) rel_type: String,
-    pub(crate) properties: HashMap<String, Value>,
+    pub(crate) properties: ValueMap,
 }
 
 impl Relationship {
@@ -47,7 +50,7 @@ impl Relationship {
         &self.rel_type
     }
 
-    pub fn properties(&self) -> &HashMap<String, Value> {
+    pub fn properties(&self) -> &ValueMap {
         &self.properties
     }
 }
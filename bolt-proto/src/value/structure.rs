@@ -0,0 +1,119 @@
+use crate::{
+    error::{ConversionError, ConversionResult},
+    Value,
+};
+
+/// Decodes structures whose signatures aren't used by the built-in [`Value`] variants, given the
+/// signature and the deserialized fields. Register one with
+/// [`DeserializeOptions::with_structure_decoder`](crate::DeserializeOptions::with_structure_decoder)
+/// to check or reshape vendor-specific structures while they're deserialized, before they would
+/// fall back to [`Value::Structure`]. Return the structure unchanged for signatures you don't
+/// handle.
+/// ```
+/// use bolt_proto::{error::*, value::ValueMap, DeserializeOptions, Value};
+///
+/// // Decode 0x24 structures as maps, and reject them if they're malformed
+/// fn decode(signature: u8, fields: Vec<Value>) -> ConversionResult<Value> {
+///     match (signature, &fields[..]) {
+///         (0x24, [Value::Integer(cents), Value::String(currency)]) => {
+///             Ok(Value::from(ValueMap::from([
+///                 (String::from("cents"), Value::from(*cents)),
+///                 (String::from("currency"), Value::from(currency.as_str())),
+///             ])))
+///         }
+///         (0x24, _) => Err(ConversionError::FromValue(Value::List(fields))),
+///         _ => Ok(Value::Structure { signature, fields }),
+///     }
+/// }
+///
+/// let structure = Value::Structure {
+///     signature: 0x24,
+///     fields: vec![Value::from(150), Value::from("EUR")],
+/// };
+/// let options = DeserializeOptions::new().with_structure_decoder(Some(decode));
+/// let (value, _) = Value::deserialize_with(structure.serialize()?, &options)?;
+/// assert_eq!(
+///     value,
+///     Value::from(ValueMap::from([
+///         (String::from("cents"), Value::from(150)),
+///         (String::from("currency"), Value::from("EUR")),
+///     ]))
+/// );
+/// # Ok::<(), bolt_proto::error::Error>(())
+/// ```
+pub type StructureDecoder = fn(signature: u8, fields: Vec<Value>) -> ConversionResult<Value>;
+
+/// Conversion between your own types and [`Value::Structure`]s with a given signature, such as
+/// the vendor-specific structures sent by some Bolt servers.
+///
+/// Structures with unknown signatures are deserialized as [`Value::Structure`], unless a
+/// [`StructureDecoder`] is registered with [`DeserializeOptions`](crate::DeserializeOptions).
+/// This trait converts to and from those values, so your types can be sent as structures and
+/// recovered from the structures that are received.
+/// ```
+/// use bolt_proto::{error::*, value::StructureConversion, Value};
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Money {
+///     cents: i64,
+///     currency: String,
+/// }
+///
+/// impl StructureConversion for Money {
+///     const SIGNATURE: u8 = 0x24;
+///
+///     fn into_fields(self) -> Vec<Value> {
+///         vec![Value::from(self.cents), Value::from(self.currency)]
+///     }
+///
+///     fn from_fields(fields: Vec<Value>) -> ConversionResult<Self> {
+///         let [cents, currency]: [Value; 2] = fields
+///             .try_into()
+///             .map_err(|fields| ConversionError::FromValue(Value::List(fields)))?;
+///         Ok(Money {
+///             cents: cents.try_into()?,
+///             currency: currency.try_into()?,
+///         })
+///     }
+/// }
+///
+/// let money = Money { cents: 150, currency: String::from("EUR") };
+/// let value = money.into_value();
+/// assert!(matches!(value, Value::Structure { signature: 0x24, .. }));
+/// assert_eq!(
+///     Money::try_from_value(value).unwrap(),
+///     Money { cents: 150, currency: String::from("EUR") }
+/// );
+/// ```
+///
+/// The signature must not be one of the signatures used by the built-in [`Value`] variants, since
+/// those are always deserialized as the built-in types. Serializing a value with such a signature
+/// fails with
+/// [`SerializationError::ReservedSignature`](crate::error::SerializationError::ReservedSignature).
+// ConversionError is large, but consistent with the TryFrom<Value> conversions
+#[allow(clippy::result_large_err)]
+pub trait StructureConversion: Sized {
+    const SIGNATURE: u8;
+
+    fn into_fields(self) -> Vec<Value>;
+
+    fn from_fields(fields: Vec<Value>) -> ConversionResult<Self>;
+
+    fn into_value(self) -> Value {
+        Value::Structure {
+            signature: Self::SIGNATURE,
+            fields: self.into_fields(),
+        }
+    }
+
+    /// Convert a [`Value::Structure`] with this type's signature. Other values are returned in a
+    /// [`ConversionError::FromValue`].
+    fn try_from_value(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Structure { signature, fields } if signature == Self::SIGNATURE => {
+                Self::from_fields(fields)
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
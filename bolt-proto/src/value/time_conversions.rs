@@ -0,0 +1,307 @@
+//! Conversions between [`Value`]s and the date and time types of the [`time`] crate.
+
+use chrono::{
+    DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta,
+    Timelike,
+};
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+use crate::{
+    error::{ConversionError, ConversionResult},
+    value::Duration,
+    Value,
+};
+
+fn from_date(date: time::Date) -> NaiveDate {
+    let (year, ordinal) = date.to_ordinal_date();
+    // Every date representable by the time crate is representable by chrono
+    NaiveDate::from_yo_opt(year, ordinal as u32).unwrap()
+}
+
+fn to_date(date: NaiveDate) -> Option<time::Date> {
+    time::Date::from_ordinal_date(date.year(), date.ordinal() as u16).ok()
+}
+
+fn from_time(time: time::Time) -> NaiveTime {
+    let (hour, minute, second, nano) = time.as_hms_nano();
+    NaiveTime::from_hms_nano_opt(hour as u32, minute as u32, second as u32, nano).unwrap()
+}
+
+fn to_time(time: NaiveTime) -> Option<time::Time> {
+    // Fails for leap seconds, which the time crate cannot represent
+    time::Time::from_hms_nano(
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+        time.nanosecond(),
+    )
+    .ok()
+}
+
+fn from_offset(offset: UtcOffset) -> FixedOffset {
+    FixedOffset::east_opt(offset.whole_seconds()).unwrap()
+}
+
+fn to_offset(offset: FixedOffset) -> Option<UtcOffset> {
+    UtcOffset::from_whole_seconds(offset.local_minus_utc()).ok()
+}
+
+fn from_date_time(date_time: PrimitiveDateTime) -> NaiveDateTime {
+    NaiveDateTime::new(from_date(date_time.date()), from_time(date_time.time()))
+}
+
+fn to_date_time(date_time: NaiveDateTime) -> Option<PrimitiveDateTime> {
+    Some(PrimitiveDateTime::new(
+        to_date(date_time.date())?,
+        to_time(date_time.time())?,
+    ))
+}
+
+fn to_offset_date_time(date_time: DateTime<FixedOffset>) -> Option<OffsetDateTime> {
+    Some(to_date_time(date_time.naive_local())?.assume_offset(to_offset(*date_time.offset())?))
+}
+
+// ------------------------- Into Value -------------------------
+
+impl From<time::Date> for Value {
+    fn from(value: time::Date) -> Self {
+        Value::Date(from_date(value))
+    }
+}
+
+impl From<(time::Time, UtcOffset)> for Value {
+    fn from(pair: (time::Time, UtcOffset)) -> Self {
+        Value::Time(from_time(pair.0), from_offset(pair.1))
+    }
+}
+
+impl From<OffsetDateTime> for Value {
+    fn from(value: OffsetDateTime) -> Self {
+        let local = from_date_time(PrimitiveDateTime::new(value.date(), value.time()));
+        let offset = from_offset(value.offset());
+        Value::DateTimeOffset(local.and_local_timezone(offset).unwrap())
+    }
+}
+
+impl From<time::Time> for Value {
+    fn from(value: time::Time) -> Self {
+        Value::LocalTime(from_time(value))
+    }
+}
+
+impl From<PrimitiveDateTime> for Value {
+    fn from(value: PrimitiveDateTime) -> Self {
+        Value::LocalDateTime(from_date_time(value))
+    }
+}
+
+impl From<time::Duration> for Value {
+    fn from(value: time::Duration) -> Self {
+        Value::Duration(Duration::from(value))
+    }
+}
+
+// ------------------------- From Value -------------------------
+
+impl TryFrom<Value> for time::Date {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Date(date) => to_date(date).ok_or(ConversionError::FromValue(value)),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for (time::Time, UtcOffset) {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Time(time, offset) => to_time(time)
+                .zip(to_offset(offset))
+                .ok_or(ConversionError::FromValue(value)),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for OffsetDateTime {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::DateTimeOffset(date_time_offset) => {
+                to_offset_date_time(date_time_offset).ok_or(ConversionError::FromValue(value))
+            }
+            Value::DateTimeZoned(date_time_zoned) => {
+                to_offset_date_time(date_time_zoned.with_timezone(&date_time_zoned.offset().fix()))
+                    .ok_or(ConversionError::FromValue(value))
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for time::Time {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::LocalTime(local_time) => {
+                to_time(local_time).ok_or(ConversionError::FromValue(value))
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for PrimitiveDateTime {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::LocalDateTime(local_date_time) => {
+                to_date_time(local_date_time).ok_or(ConversionError::FromValue(value))
+            }
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+impl TryFrom<Value> for time::Duration {
+    type Error = ConversionError;
+
+    fn try_from(value: Value) -> ConversionResult<Self> {
+        match value {
+            Value::Duration(duration) => time::Duration::try_from(duration),
+            _ => Err(ConversionError::FromValue(value)),
+        }
+    }
+}
+
+// ------------------------- Durations -------------------------
+
+impl From<time::Duration> for Duration {
+    /// Convert a [`time::Duration`] into a duration with only seconds and nanoseconds, since days
+    /// do not always have 24 hours.
+    fn from(duration: time::Duration) -> Self {
+        Duration::new(
+            0,
+            0,
+            duration.whole_seconds(),
+            duration.subsec_nanoseconds(),
+        )
+        .normalize()
+    }
+}
+
+impl TryFrom<Duration> for time::Duration {
+    type Error = ConversionError;
+
+    /// Convert a duration into a [`time::Duration`], treating days as 24 hours. Fails if the
+    /// duration has a non-zero number of months, or if it is out of range.
+    fn try_from(duration: Duration) -> ConversionResult<Self> {
+        let delta = TimeDelta::try_from(duration)?;
+        Ok(time::Duration::new(
+            delta.num_seconds(),
+            delta.subsec_nanos(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+    use time::macros::{date, datetime, offset, time};
+
+    use super::*;
+
+    #[test]
+    fn date_and_time() {
+        let value = Value::from(date!(2024 - 02 - 29));
+        assert_eq!(
+            value,
+            Value::Date(NaiveDate::from_ymd_opt(2024, 2, 29).unwrap())
+        );
+        assert_eq!(time::Date::try_from(value).unwrap(), date!(2024 - 02 - 29));
+
+        let value = Value::from(time!(12:34:56.789));
+        assert_eq!(
+            value,
+            Value::LocalTime(NaiveTime::from_hms_milli_opt(12, 34, 56, 789).unwrap())
+        );
+        assert_eq!(time::Time::try_from(value).unwrap(), time!(12:34:56.789));
+
+        let value = Value::from((time!(1:02:03), offset!(-5)));
+        assert_eq!(
+            <(time::Time, UtcOffset)>::try_from(value).unwrap(),
+            (time!(1:02:03), offset!(-5))
+        );
+
+        let value = Value::from(datetime!(2000-01-01 0:00));
+        assert_eq!(
+            PrimitiveDateTime::try_from(value).unwrap(),
+            datetime!(2000-01-01 0:00)
+        );
+
+        // Out of range for the time crate
+        let value = Value::Date(NaiveDate::from_ymd_opt(100_000, 1, 1).unwrap());
+        assert!(matches!(
+            time::Date::try_from(value),
+            Err(ConversionError::FromValue(_))
+        ));
+        assert!(time::Date::try_from(Value::from(1)).is_err());
+    }
+
+    #[test]
+    fn offset_date_time() {
+        let date_time = datetime!(2024-03-10 02:30:00.5 +05:30);
+        let value = Value::from(date_time);
+        assert_eq!(
+            value,
+            Value::DateTimeOffset(
+                FixedOffset::east_opt(19_800)
+                    .unwrap()
+                    .with_ymd_and_hms(2024, 3, 10, 2, 30, 0)
+                    .unwrap()
+                    + TimeDelta::milliseconds(500)
+            )
+        );
+        assert_eq!(OffsetDateTime::try_from(value).unwrap(), date_time);
+
+        let zoned = Value::DateTimeZoned(
+            Tz::Europe__Paris
+                .with_ymd_and_hms(2024, 7, 1, 12, 0, 0)
+                .unwrap(),
+        );
+        assert_eq!(
+            OffsetDateTime::try_from(zoned).unwrap(),
+            datetime!(2024-07-01 12:00 +2)
+        );
+    }
+
+    #[test]
+    fn duration() {
+        let value = Value::from(time::Duration::new(-90_000, -5));
+        assert_eq!(
+            value,
+            Value::Duration(Duration::new(0, 0, -90_001, 999_999_995))
+        );
+        assert_eq!(
+            time::Duration::try_from(value).unwrap(),
+            time::Duration::new(-90_000, -5)
+        );
+
+        assert_eq!(
+            time::Duration::try_from(Duration::new(0, 1, 1, 1)).unwrap(),
+            time::Duration::new(86_401, 1)
+        );
+        assert!(matches!(
+            time::Duration::try_from(Duration::new(1, 0, 0, 0)),
+            Err(ConversionError::DurationHasMonths(1))
+        ));
+    }
+}
@@ -2,14 +2,17 @@ use std::collections::HashMap;
 
 use bolt_proto_derive::*;
 
-use crate::{value::SIGNATURE_UNBOUND_RELATIONSHIP, Value};
+use crate::{
+    value::{ValueMap, SIGNATURE_UNBOUND_RELATIONSHIP},
+    Value,
+};
 
 #[bolt_structure(SIGNATURE_UNBOUND_RELATIONSHIP)]
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnboundRelationship {
     pub(crate) rel_identity: i64,
     pub(crate) rel_type: String,
-    pub(crate) properties: HashMap<String, Value>,
+    pub(crate) properties: ValueMap,
 }
 
 impl UnboundRelationship {
@@ -33,7 +36,7 @@ impl UnboundRelationship {
         &self.rel_type
     }
 
-    pub fn properties(&self) -> &HashMap<String, Value> {
+    pub fn properties(&self) -> &ValueMap {
         &self.properties
     }
 }
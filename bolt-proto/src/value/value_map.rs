@@ -0,0 +1,297 @@
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hash},
+    iter::FusedIterator,
+    ops::Index,
+};
+
+use crate::Value;
+
+#[cfg(not(feature = "indexmap"))]
+mod inner {
+    pub(super) use std::collections::hash_map::{IntoIter, Iter, IterMut};
+    pub(super) type Map = std::collections::HashMap<String, crate::Value>;
+}
+
+#[cfg(feature = "indexmap")]
+mod inner {
+    pub(super) use indexmap::map::{IntoIter, Iter, IterMut};
+    pub(super) type Map = indexmap::IndexMap<String, crate::Value>;
+}
+
+/// The map type used by [`Value::Map`], the properties of nodes and relationships, and message
+/// metadata.
+///
+/// This is backed by a [`HashMap`] by default. If the `indexmap` feature is enabled, it is backed
+/// by an [`IndexMap`](indexmap::IndexMap) that keeps entries in insertion order, so a deserialized
+/// map is serialized again with its entries in the same order. The API is the same either way,
+/// and maps with the same entries are equal regardless of their order.
+#[derive(Clone, Default, PartialEq)]
+pub struct ValueMap(inner::Map);
+
+impl ValueMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(inner::Map::with_capacity(capacity))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&Value>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.get(key)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut Value>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.get_mut(key)
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.0.contains_key(key)
+    }
+
+    /// Insert a value, returning the previous value for the key, if any. With the `indexmap`
+    /// feature, replacing a value keeps the entry in its original position.
+    pub fn insert(&mut self, key: String, value: Value) -> Option<Value> {
+        self.0.insert(key, value)
+    }
+
+    /// Get the value for a key, inserting the result of `default` first if the key is not present.
+    pub fn get_or_insert_with(
+        &mut self,
+        key: String,
+        default: impl FnOnce() -> Value,
+    ) -> &mut Value {
+        self.0.entry(key).or_insert_with(default)
+    }
+
+    /// Remove the value for a key. With the `indexmap` feature, the order of the remaining
+    /// entries is preserved.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Value>
+    where
+        String: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        #[cfg(not(feature = "indexmap"))]
+        return self.0.remove(key);
+        #[cfg(feature = "indexmap")]
+        return self.0.shift_remove(key);
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(&String, &mut Value) -> bool) {
+        self.0.retain(f)
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear()
+    }
+
+    pub fn iter(&self) -> ValueMapIter<'_> {
+        ValueMapIter(self.0.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> ValueMapIterMut<'_> {
+        ValueMapIterMut(self.0.iter_mut())
+    }
+
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &String> + '_ {
+        self.0.keys()
+    }
+
+    pub fn values(&self) -> impl ExactSizeIterator<Item = &Value> + '_ {
+        self.0.values()
+    }
+
+    pub fn values_mut(&mut self) -> impl ExactSizeIterator<Item = &mut Value> + '_ {
+        self.0.values_mut()
+    }
+
+    pub fn into_keys(self) -> impl ExactSizeIterator<Item = String> {
+        self.0.into_keys()
+    }
+
+    pub fn into_values(self) -> impl ExactSizeIterator<Item = Value> {
+        self.0.into_values()
+    }
+}
+
+impl Debug for ValueMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+// Only for the default hasher, so the type of the other map can be inferred
+impl PartialEq<HashMap<String, Value>> for ValueMap {
+    fn eq(&self, other: &HashMap<String, Value>) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key).is_some_and(|other| value == other))
+    }
+}
+
+impl PartialEq<ValueMap> for HashMap<String, Value> {
+    fn eq(&self, other: &ValueMap) -> bool {
+        other == self
+    }
+}
+
+impl<S> From<HashMap<String, Value, S>> for ValueMap {
+    fn from(map: HashMap<String, Value, S>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<S: BuildHasher + Default> From<ValueMap> for HashMap<String, Value, S> {
+    fn from(map: ValueMap) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+impl<const N: usize> From<[(String, Value); N]> for ValueMap {
+    fn from(entries: [(String, Value); N]) -> Self {
+        entries.into_iter().collect()
+    }
+}
+
+impl<Q> Index<&Q> for ValueMap
+where
+    String: Borrow<Q>,
+    Q: Hash + Eq + ?Sized,
+{
+    type Output = Value;
+
+    /// Panics if the key is not present.
+    fn index(&self, key: &Q) -> &Value {
+        self.get(key).expect("key not found in ValueMap")
+    }
+}
+
+impl FromIterator<(String, Value)> for ValueMap {
+    fn from_iter<T: IntoIterator<Item = (String, Value)>>(iter: T) -> Self {
+        Self(inner::Map::from_iter(iter))
+    }
+}
+
+impl Extend<(String, Value)> for ValueMap {
+    fn extend<T: IntoIterator<Item = (String, Value)>>(&mut self, iter: T) {
+        self.0.extend(iter)
+    }
+}
+
+impl IntoIterator for ValueMap {
+    type Item = (String, Value);
+    type IntoIter = ValueMapIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ValueMapIntoIter(self.0.into_iter())
+    }
+}
+
+impl<'a> IntoIterator for &'a ValueMap {
+    type Item = (&'a String, &'a Value);
+    type IntoIter = ValueMapIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut ValueMap {
+    type Item = (&'a String, &'a mut Value);
+    type IntoIter = ValueMapIterMut<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+macro_rules! impl_iterator {
+    ($T:ty, $Item:ty) => {
+        impl<'a> Iterator for $T {
+            type Item = $Item;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                self.0.next()
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl<'a> ExactSizeIterator for $T {}
+
+        impl<'a> FusedIterator for $T {}
+    };
+}
+
+/// An iterator over the entries of a [`ValueMap`].
+#[derive(Debug, Clone)]
+pub struct ValueMapIter<'a>(inner::Iter<'a, String, Value>);
+
+impl_iterator!(ValueMapIter<'a>, (&'a String, &'a Value));
+
+/// A mutable iterator over the entries of a [`ValueMap`].
+#[derive(Debug)]
+pub struct ValueMapIterMut<'a>(inner::IterMut<'a, String, Value>);
+
+impl_iterator!(ValueMapIterMut<'a>, (&'a String, &'a mut Value));
+
+/// An owning iterator over the entries of a [`ValueMap`].
+#[derive(Debug)]
+pub struct ValueMapIntoIter(inner::IntoIter<String, Value>);
+
+impl_iterator!(ValueMapIntoIter, (String, Value));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equality() {
+        let map = ValueMap::from([
+            (String::from("a"), Value::from(1)),
+            (String::from("b"), Value::from(2)),
+        ]);
+        let mut entries: Vec<_> = map.clone().into_iter().collect();
+        entries.reverse();
+        let reversed = ValueMap::from_iter(entries);
+        assert_eq!(map, reversed);
+        assert_eq!(
+            map,
+            HashMap::from([
+                (String::from("b"), Value::from(2)),
+                (String::from("a"), Value::from(1)),
+            ])
+        );
+        assert_ne!(map, HashMap::new());
+        assert_eq!(
+            format!("{:?}", ValueMap::from([(String::from("a"), Value::Null)])),
+            "{\"a\": Null}"
+        );
+    }
+}
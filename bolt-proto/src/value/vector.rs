@@ -0,0 +1,154 @@
+use std::mem;
+
+use bytes::{BufMut, BytesMut};
+
+use crate::{
+    error::{DeserializationError, DeserializeResult},
+    Value,
+};
+
+// Element types are identified by the PackStream marker of the corresponding scalar type
+pub(crate) const TYPE_INT_8: u8 = 0xC8;
+pub(crate) const TYPE_INT_16: u8 = 0xC9;
+pub(crate) const TYPE_INT_32: u8 = 0xCA;
+pub(crate) const TYPE_INT_64: u8 = 0xCB;
+pub(crate) const TYPE_FLOAT_32: u8 = 0xC6;
+pub(crate) const TYPE_FLOAT_64: u8 = 0xC1;
+
+/// A vector of numbers that all have the same type, such as an embedding.
+///
+/// Vectors are sent as packed byte arrays, which is much more compact than a [`Value::List`] of
+/// [`Value::Float`]s. _(Bolt v6+ only. Serializing a vector fails for earlier versions, see
+/// [`SerializeOptions::with_vectors`](crate::SerializeOptions::with_vectors).)_
+///
+/// This is only a PackStream type for now: `bolt-client` does not negotiate Bolt v6 yet, so its
+/// `Client` cannot send or receive vectors.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Vector {
+    Int8(Vec<i8>),
+    Int16(Vec<i16>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Float32(Vec<f32>),
+    Float64(Vec<f64>),
+}
+
+macro_rules! for_each_variant {
+    ($vector:expr, $elements:ident => $body:expr) => {
+        match $vector {
+            Vector::Int8($elements) => $body,
+            Vector::Int16($elements) => $body,
+            Vector::Int32($elements) => $body,
+            Vector::Int64($elements) => $body,
+            Vector::Float32($elements) => $body,
+            Vector::Float64($elements) => $body,
+        }
+    };
+}
+
+macro_rules! unpack {
+    ($data:expr, $T:ty) => {
+        $data
+            .chunks_exact(mem::size_of::<$T>())
+            .map(|chunk| <$T>::from_be_bytes(chunk.try_into().unwrap()))
+            .collect()
+    };
+}
+
+impl Vector {
+    pub fn len(&self) -> usize {
+        for_each_variant!(self, elements => elements.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the elements of this vector as [`Value::Integer`]s or [`Value::Float`]s.
+    pub fn to_values(&self) -> Vec<Value> {
+        match self {
+            Vector::Int8(elements) => elements.iter().map(|&e| Value::from(e)).collect(),
+            Vector::Int16(elements) => elements.iter().map(|&e| Value::from(e)).collect(),
+            Vector::Int32(elements) => elements.iter().map(|&e| Value::from(e)).collect(),
+            Vector::Int64(elements) => elements.iter().map(|&e| Value::from(e)).collect(),
+            Vector::Float32(elements) => elements.iter().map(|&e| Value::from(e as f64)).collect(),
+            Vector::Float64(elements) => elements.iter().map(|&e| Value::from(e)).collect(),
+        }
+    }
+
+    pub(crate) fn type_marker(&self) -> u8 {
+        match self {
+            Vector::Int8(_) => TYPE_INT_8,
+            Vector::Int16(_) => TYPE_INT_16,
+            Vector::Int32(_) => TYPE_INT_32,
+            Vector::Int64(_) => TYPE_INT_64,
+            Vector::Float32(_) => TYPE_FLOAT_32,
+            Vector::Float64(_) => TYPE_FLOAT_64,
+        }
+    }
+
+    /// Size of the packed elements in bytes.
+    pub(crate) fn data_len(&self) -> usize {
+        // Every vector has a valid element type
+        self.len() * element_size(self.type_marker()).unwrap()
+    }
+
+    /// Write the elements as big-endian numbers.
+    pub(crate) fn put_data(&self, bytes: &mut BytesMut) {
+        for_each_variant!(self, elements => {
+            for element in elements {
+                bytes.put_slice(&element.to_be_bytes());
+            }
+        })
+    }
+
+    pub(crate) fn from_data(type_marker: u8, data: &[u8]) -> DeserializeResult<Self> {
+        let size = element_size(type_marker)
+            .ok_or(DeserializationError::InvalidMarkerByte(type_marker))?;
+        if !data.len().is_multiple_of(size) {
+            return Err(DeserializationError::InvalidVector(
+                "data length is not a multiple of the element size",
+            ));
+        }
+        Ok(match type_marker {
+            TYPE_INT_8 => Vector::Int8(unpack!(data, i8)),
+            TYPE_INT_16 => Vector::Int16(unpack!(data, i16)),
+            TYPE_INT_32 => Vector::Int32(unpack!(data, i32)),
+            TYPE_INT_64 => Vector::Int64(unpack!(data, i64)),
+            TYPE_FLOAT_32 => Vector::Float32(unpack!(data, f32)),
+            TYPE_FLOAT_64 => Vector::Float64(unpack!(data, f64)),
+            _ => unreachable!(),
+        })
+    }
+}
+
+fn element_size(type_marker: u8) -> Option<usize> {
+    match type_marker {
+        TYPE_INT_8 => Some(mem::size_of::<i8>()),
+        TYPE_INT_16 => Some(mem::size_of::<i16>()),
+        TYPE_INT_32 => Some(mem::size_of::<i32>()),
+        TYPE_INT_64 => Some(mem::size_of::<i64>()),
+        TYPE_FLOAT_32 => Some(mem::size_of::<f32>()),
+        TYPE_FLOAT_64 => Some(mem::size_of::<f64>()),
+        _ => None,
+    }
+}
+
+macro_rules! impl_from_vec {
+    ($($T:ty => $V:ident),+) => {
+        $(
+            impl From<Vec<$T>> for Vector {
+                fn from(elements: Vec<$T>) -> Self {
+                    Vector::$V(elements)
+                }
+            }
+
+            impl From<&[$T]> for Vector {
+                fn from(elements: &[$T]) -> Self {
+                    Vector::$V(elements.to_vec())
+                }
+            }
+        )*
+    };
+}
+impl_from_vec!(i8 => Int8, i16 => Int16, i32 => Int32, i64 => Int64, f32 => Float32, f64 => Float64);
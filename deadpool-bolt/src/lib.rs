@@ -54,21 +54,13 @@
 //!     Ok(())
 //! }
 
-use std::{convert::Infallible, io, net::SocketAddr};
+use std::{convert::Infallible, io, net::SocketAddr, time::Duration};
 
 use async_trait::async_trait;
 use deadpool::managed::RecycleResult;
-use tokio::{
-    io::BufStream,
-    net::{lookup_host, ToSocketAddrs},
-};
-use tokio_util::compat::*;
-
-use bolt_client::{
-    error::{CommunicationError, ConnectionError, Error as ClientError},
-    Client, Metadata, Stream,
-};
-use bolt_proto::{error::Error as ProtocolError, message, Message};
+use tokio::net::{lookup_host, ToSocketAddrs};
+
+use bolt_client::{error::Error as ClientError, Connection, ConnectionFactory, Metadata};
 
 pub use bolt_client;
 pub use bolt_client::bolt_proto;
@@ -85,10 +77,7 @@ deadpool::managed_reexports!(
 
 #[derive(Debug)]
 pub struct Manager {
-    addr: SocketAddr,
-    domain: Option<String>,
-    version_specifiers: [u32; 4],
-    metadata: Metadata,
+    factory: ConnectionFactory,
 }
 
 impl Manager {
@@ -98,51 +87,46 @@ impl Manager {
         version_specifiers: [u32; 4],
         metadata: Metadata,
     ) -> io::Result<Self> {
-        Ok(Self {
-            addr: lookup_host(addr)
-                .await?
-                .next()
-                .ok_or_else(|| io::Error::from(io::ErrorKind::AddrNotAvailable))?,
-            domain,
-            version_specifiers,
-            metadata,
-        })
+        let addrs: Vec<SocketAddr> = lookup_host(addr).await?.collect();
+        if addrs.is_empty() {
+            return Err(io::Error::from(io::ErrorKind::AddrNotAvailable));
+        }
+
+        Ok(Self::from_factory(
+            ConnectionFactory::from_socket_addrs(addrs)
+                .with_tls_domain(domain)
+                .with_version_specifiers(version_specifiers)
+                .with_metadata(metadata),
+        ))
+    }
+
+    /// Create a manager that opens and checks connections using the given
+    /// [`ConnectionFactory`], which supports more connection options than [`Manager::new`].
+    pub fn from_factory(factory: ConnectionFactory) -> Self {
+        Self { factory }
+    }
+
+    /// Only check connections with a [`RESET`](bolt_proto::Message::Reset) message when they're
+    /// taken from the pool if they've been idle for at least `max_idle`, or aren't ready for use.
+    /// By default, every connection is checked. See
+    /// [`ConnectionFactory::with_max_idle`].
+    pub fn with_max_idle(mut self, max_idle: Duration) -> Self {
+        self.factory = self.factory.with_max_idle(max_idle);
+        self
     }
 }
 
 #[async_trait]
 impl deadpool::managed::Manager for Manager {
-    // TODO: Make a runtime-agnostic stream wrapper
-    type Type = Client<Compat<BufStream<Stream>>>;
+    type Type = Connection;
     type Error = ClientError;
 
     async fn create(&self) -> Result<Self::Type, Self::Error> {
-        let mut client = Client::new(
-            BufStream::new(
-                Stream::connect(self.addr, self.domain.as_ref())
-                    .await
-                    .map_err(ConnectionError::from)?,
-            )
-            .compat(),
-            &self.version_specifiers,
-        )
-        .await?;
-
-        match client.hello(self.metadata.clone()).await? {
-            Message::Success(_) => Ok(client),
-            other => Err(CommunicationError::from(io::Error::new(
-                io::ErrorKind::ConnectionAborted,
-                format!("server responded with {:?}", other),
-            ))
-            .into()),
-        }
+        self.factory.connect().await
     }
 
     async fn recycle(&self, conn: &mut Self::Type) -> RecycleResult<Self::Error> {
-        message::Success::try_from(conn.reset().await.map_err(Self::Error::from)?)
-            .map_err(ProtocolError::from)
-            .map_err(Self::Error::from)?;
-        Ok(())
+        Ok(self.factory.check(conn).await?)
     }
 }
 
@@ -150,7 +134,8 @@ impl deadpool::managed::Manager for Manager {
 mod tests {
     use std::env;
 
-    use bolt_proto::{version::*, Value};
+    use bolt_client::error::{CommunicationError, ConnectionError};
+    use bolt_proto::{message, version::*, Value};
     use deadpool::managed::Manager as DeadpoolManager;
     use futures_util::{stream::FuturesUnordered, StreamExt};
 